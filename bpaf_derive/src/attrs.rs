@@ -41,6 +41,9 @@ pub enum Consumer {
     Switch {
         span: Span,
     },
+    NegatableSwitch {
+        span: Span,
+    },
     Flag {
         present: Expr,
         absent: Expr,
@@ -61,6 +64,16 @@ pub enum Consumer {
         ty: Option<Type>,
         span: Span,
     },
+    Choice {
+        ty: Option<Type>,
+        span: Span,
+    },
+    OptionalValue {
+        metavar: LitStr,
+        bare: Box<Expr>,
+        ty: Option<Type>,
+        span: Span,
+    },
     Positional {
         metavar: Option<LitStr>,
         ty: Option<Type>,
@@ -84,10 +97,13 @@ impl Consumer {
     pub fn span(&self) -> Span {
         match self {
             Consumer::Switch { span }
+            | Consumer::NegatableSwitch { span }
             | Consumer::Flag { span, .. }
             | Consumer::ReqFlag { span, .. }
             | Consumer::Any { span, .. }
             | Consumer::Argument { span, .. }
+            | Consumer::Choice { span, .. }
+            | Consumer::OptionalValue { span, .. }
             | Consumer::Positional { span, .. }
             | Consumer::External { span, .. }
             | Consumer::PureWith { span, .. }
@@ -98,9 +114,12 @@ impl Consumer {
     pub(crate) fn help_placement(&self) -> HelpPlacement {
         match self {
             Consumer::Switch { .. }
+            | Consumer::NegatableSwitch { .. }
             | Consumer::Flag { .. }
             | Consumer::ReqFlag { .. }
-            | Consumer::Argument { .. } => HelpPlacement::AtName,
+            | Consumer::Argument { .. }
+            | Consumer::Choice { .. }
+            | Consumer::OptionalValue { .. } => HelpPlacement::AtName,
             Consumer::Any { .. } | Consumer::Positional { .. } => HelpPlacement::AtConsumer,
             Consumer::External { .. } | Consumer::PureWith { .. } | Consumer::Pure { .. } => {
                 HelpPlacement::NotAvailable
@@ -119,9 +138,12 @@ impl Consumer {
     pub(crate) fn needs_name(&self) -> bool {
         match self {
             Consumer::Switch { .. }
+            | Consumer::NegatableSwitch { .. }
             | Consumer::Flag { .. }
             | Consumer::ReqFlag { .. }
-            | Consumer::Argument { .. } => true,
+            | Consumer::Argument { .. }
+            | Consumer::Choice { .. }
+            | Consumer::OptionalValue { .. } => true,
             Consumer::Pure { .. }
             | Consumer::PureWith { .. }
             | Consumer::Positional { .. }
@@ -139,6 +161,8 @@ pub(crate) enum Name {
     Long { name: Option<LitStr>, span: Span },
     /// Enum variable, name must be specified
     Env { name: Box<Expr> },
+    /// Config key, name must be specified
+    Config { name: Box<Expr> },
 }
 
 impl StrictName {
@@ -165,6 +189,7 @@ impl StrictName {
                 None => return Err(Error::new(span, "Can't derive an explicit name for unnamed struct, try adding a name here like long(\"arg\")", ))
             },
             Name::Env { name, .. } => Self::Env { name },
+            Name::Config { name, .. } => Self::Config { name },
         })
     }
 }
@@ -174,6 +199,7 @@ pub(crate) enum StrictName {
     Short { name: LitChar },
     Long { name: LitStr },
     Env { name: Box<Expr> },
+    Config { name: Box<Expr> },
 }
 
 impl ToTokens for StrictName {
@@ -182,6 +208,7 @@ impl ToTokens for StrictName {
             StrictName::Short { name } => quote!(short(#name)),
             StrictName::Long { name } => quote!(long(#name)),
             StrictName::Env { name } => quote!(env(#name)),
+            StrictName::Config { name } => quote!(config(#name)),
         }
         .to_tokens(tokens);
     }
@@ -229,6 +256,7 @@ impl ToTokens for PostDecor {
             PostDecor::Complete { f, .. } => quote!(complete(#f)),
             PostDecor::CompleteGroup { group, .. } => quote!(group(#group)),
             PostDecor::CompleteShell { f, .. } => quote!(complete_shell(#f)),
+            PostDecor::CompleteDelegate { program, .. } => quote!(complete_delegate(#program)),
             PostDecor::DebugFallback { .. } => quote!(debug_fallback()),
             PostDecor::DisplayFallback { .. } => quote!(display_fallback()),
             PostDecor::Fallback { value, .. } => quote!(fallback(#value)),
@@ -239,6 +267,11 @@ impl ToTokens for PostDecor {
             PostDecor::Hide { .. } => quote!(hide()),
             PostDecor::CustomUsage { usage, .. } => quote!(custom_usage(#usage)),
             PostDecor::HideUsage { .. } => quote!(hide_usage()),
+            PostDecor::Requires { other, .. } => quote!(requires(#other)),
+            PostDecor::ConflictsWith { other, .. } => quote!(conflicts_with(#other)),
+            PostDecor::RequiredIf { cond, .. } => quote!(required_if(#cond)),
+            PostDecor::AtLeastOneOf { .. } => quote!(at_least_one_of()),
+            PostDecor::ExactlyOneOf { .. } => quote!(exactly_one_of()),
         }
         .to_tokens(tokens);
     }
@@ -290,6 +323,10 @@ pub(crate) enum PostDecor {
         span: Span,
         f: Box<Expr>,
     },
+    CompleteDelegate {
+        span: Span,
+        program: Box<Expr>,
+    },
     DebugFallback {
         span: Span,
     },
@@ -326,6 +363,24 @@ pub(crate) enum PostDecor {
     HideUsage {
         span: Span,
     },
+    Requires {
+        span: Span,
+        other: Box<Expr>,
+    },
+    ConflictsWith {
+        span: Span,
+        other: Box<Expr>,
+    },
+    RequiredIf {
+        span: Span,
+        cond: Box<Expr>,
+    },
+    AtLeastOneOf {
+        span: Span,
+    },
+    ExactlyOneOf {
+        span: Span,
+    },
 }
 impl PostDecor {
     fn span(&self) -> Span {
@@ -333,6 +388,7 @@ impl PostDecor {
             Self::Complete { span, .. }
             | Self::CompleteGroup { span, .. }
             | Self::CompleteShell { span, .. }
+            | Self::CompleteDelegate { span, .. }
             | Self::DebugFallback { span }
             | Self::DisplayFallback { span }
             | Self::Fallback { span, .. }
@@ -342,7 +398,12 @@ impl PostDecor {
             | Self::Guard { span, .. }
             | Self::Hide { span }
             | Self::CustomUsage { span, .. }
-            | Self::HideUsage { span } => *span,
+            | Self::HideUsage { span }
+            | Self::Requires { span, .. }
+            | Self::ConflictsWith { span, .. }
+            | Self::RequiredIf { span, .. }
+            | Self::AtLeastOneOf { span }
+            | Self::ExactlyOneOf { span } => *span,
         }
     }
 }
@@ -377,6 +438,9 @@ pub(crate) struct FieldAttrs {
     /// help specified by help(xxx)
     pub help: Vec<CustomHelp>,
 
+    /// translated help specified by help_in("lang", xxx)
+    pub translations: Vec<(LitStr, Box<Expr>)>,
+
     pub(crate) ignore_rustdoc: bool,
 }
 
@@ -400,6 +464,9 @@ impl Name {
         } else if kw == "env" {
             let name = parse_expr(input)?;
             Name::Env { name }
+        } else if kw == "config" {
+            let name = parse_expr(input)?;
+            Name::Config { name }
         } else {
             return Ok(None);
         }))
@@ -413,6 +480,18 @@ impl Consumer {
             let ty = type_fish(input)?;
             let metavar = parse_opt_metavar(input)?;
             Consumer::Argument { metavar, ty, span }
+        } else if kw == "choice" {
+            let ty = type_fish(input)?;
+            Consumer::Choice { ty, span }
+        } else if kw == "optional_value" {
+            let ty = type_fish(input)?;
+            let (metavar, bare) = parse_arg2(input)?;
+            Consumer::OptionalValue {
+                metavar,
+                bare,
+                ty,
+                span,
+            }
         } else if kw == "positional" {
             let ty = type_fish(input)?;
             let metavar = parse_opt_metavar(input)?;
@@ -428,6 +507,8 @@ impl Consumer {
             }
         } else if kw == "switch" {
             Consumer::Switch { span }
+        } else if kw == "negatable" {
+            Consumer::NegatableSwitch { span }
         } else if kw == "flag" {
             let (present, absent) = parse_arg2(input)?;
             Consumer::Flag {
@@ -503,6 +584,9 @@ impl PostDecor {
         } else if kw == "complete_shell" {
             let f = parse_arg(input)?;
             Self::CompleteShell { span, f }
+        } else if kw == "complete_delegate" {
+            let program = parse_arg(input)?;
+            Self::CompleteDelegate { span, program }
         } else if kw == "debug_fallback" {
             Self::DebugFallback { span }
         } else if kw == "display_fallback" {
@@ -528,6 +612,19 @@ impl PostDecor {
         } else if kw == "custom_usage" {
             let usage = parse_arg(input)?;
             Self::CustomUsage { usage, span }
+        } else if kw == "requires" {
+            let other = parse_arg(input)?;
+            Self::Requires { span, other }
+        } else if kw == "conflicts_with" {
+            let other = parse_arg(input)?;
+            Self::ConflictsWith { span, other }
+        } else if kw == "required_if" {
+            let cond = parse_arg(input)?;
+            Self::RequiredIf { span, cond }
+        } else if kw == "at_least_one_of" {
+            Self::AtLeastOneOf { span }
+        } else if kw == "exactly_one_of" {
+            Self::ExactlyOneOf { span }
         } else {
             return Ok(None);
         }))
@@ -584,6 +681,8 @@ impl Parse for FieldAttrs {
                 res.postpr.push(Post::Decor(pp));
             } else if let Some(help) = CustomHelp::parse(input, &kw)? {
                 res.help.push(help);
+            } else if kw == "help_in" {
+                res.translations.push(parse_arg2(input)?);
             } else {
                 return Err(fork.error("Unexpected attribute in field annotation"));
             }
@@ -0,0 +1,67 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    Error, Fields, Ident, ItemEnum, LitStr, Result,
+};
+
+use crate::utils::to_kebab_case;
+
+/// Enum with unit variants used as a set of values for `choice_of`
+#[derive(Debug)]
+pub(crate) struct ChoiceEnum {
+    ty: Ident,
+    variants: Vec<(Ident, LitStr)>,
+}
+
+impl Parse for ChoiceEnum {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let item = input.parse::<ItemEnum>()?;
+        if !item.generics.params.is_empty() {
+            let msg = "Choice can't be derived for a generic enum";
+            return Err(Error::new_spanned(&item.generics, msg));
+        }
+        let mut variants = Vec::new();
+        for variant in item.variants {
+            if !matches!(variant.fields, Fields::Unit) {
+                let msg = "Choice can only be derived for enums with unit variants";
+                return Err(Error::new_spanned(&variant.fields, msg));
+            }
+            let name = LitStr::new(
+                &to_kebab_case(&variant.ident.to_string()),
+                variant.ident.span(),
+            );
+            variants.push((variant.ident, name));
+        }
+        Ok(ChoiceEnum {
+            ty: item.ident,
+            variants,
+        })
+    }
+}
+
+impl ToTokens for ChoiceEnum {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ty = &self.ty;
+        let names = self.variants.iter().map(|(_, name)| name);
+        let branches = self
+            .variants
+            .iter()
+            .map(|(ident, name)| quote!(#name => ::std::result::Result::Ok(#ty::#ident),));
+        quote! {
+            impl ::std::str::FromStr for #ty {
+                type Err = ::std::string::String;
+                fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                    match s {
+                        #(#branches)*
+                        _ => ::std::result::Result::Err(::std::format!("{} is not a valid value", s)),
+                    }
+                }
+            }
+            impl ::bpaf::Choice for #ty {
+                const CHOICES: &'static [&'static str] = &[#(#names),*];
+            }
+        }
+        .to_tokens(tokens);
+    }
+}
@@ -603,6 +603,36 @@ fn env_argument() {
     assert_eq!(input.to_token_stream().to_string(), output.to_string());
 }
 
+#[test]
+fn config_argument() {
+    let input: NamedField = parse_quote! {
+        #[bpaf(env("PORT"), config("net.port"))]
+        port: u16
+    };
+    let output = quote! {
+        ::bpaf::long("port")
+            .env("PORT")
+            .config("net.port")
+            .argument::<u16>("ARG")
+    };
+    assert_eq!(input.to_token_stream().to_string(), output.to_string());
+}
+
+#[test]
+fn translated_help() {
+    let input: NamedField = parse_quote! {
+        /// Verbose output
+        #[bpaf(help_in("de", "Ausführliche Ausgabe"))]
+        verbose: bool
+    };
+    let output = quote! {
+        ::bpaf::long("verbose")
+            .help(::bpaf::Doc::from("Verbose output").translation("de", "Ausführliche Ausgabe"))
+            .switch()
+    };
+    assert_eq!(input.to_token_stream().to_string(), output.to_string());
+}
+
 #[test]
 fn explicit_switch_argument() {
     let input: NamedField = parse_quote! {
@@ -959,3 +989,86 @@ fn any_anywhere() {
     };
     assert_eq!(input.to_token_stream().to_string(), output.to_string());
 }
+
+#[test]
+fn any_with_delegated_complete() {
+    let input: NamedField = parse_quote! {
+        #[bpaf(any("ARG", Some), many, complete_delegate("checker"))]
+        args: Vec<OsString>
+    };
+    let output = quote! {
+        ::bpaf::any::<OsString, _, _>("ARG", Some)
+            .many()
+            .complete_delegate("checker")
+    };
+    assert_eq!(input.to_token_stream().to_string(), output.to_string());
+}
+
+#[test]
+fn choice_optional() {
+    let input: NamedField = parse_quote! {
+        #[bpaf(choice)]
+        mode: Option<Mode>
+    };
+    let output = quote! {
+        ::bpaf::long("mode").choice_of::<Mode>().optional()
+    };
+    assert_eq!(input.to_token_stream().to_string(), output.to_string());
+}
+
+#[test]
+fn optional_value_optional() {
+    let input: NamedField = parse_quote! {
+        #[bpaf(short, long, optional_value("WHEN", String::from("always")))]
+        color: Option<String>
+    };
+    let output = quote! {
+        ::bpaf::short('c')
+            .long("color")
+            .optional_value::<String>("WHEN", String::from("always"))
+            .optional()
+    };
+    assert_eq!(input.to_token_stream().to_string(), output.to_string());
+}
+
+#[test]
+fn switch_with_relations() {
+    let input: NamedField = parse_quote! {
+        #[bpaf(requires(key()), conflicts_with(dry_run()))]
+        sign: bool
+    };
+    let output = quote! {
+        ::bpaf::long("sign")
+            .switch()
+            .requires(key())
+            .conflicts_with(dry_run())
+    };
+    assert_eq!(input.to_token_stream().to_string(), output.to_string());
+}
+
+#[test]
+fn required_if_optional() {
+    let input: NamedField = parse_quote! {
+        #[bpaf(argument("KEY"), optional, required_if(sign()))]
+        key: Option<String>
+    };
+    let output = quote! {
+        ::bpaf::long("key")
+            .argument::<String>("KEY")
+            .optional()
+            .required_if(sign())
+    };
+    assert_eq!(input.to_token_stream().to_string(), output.to_string());
+}
+
+#[test]
+fn negatable_switch() {
+    let input: NamedField = parse_quote! {
+        #[bpaf(short, long, negatable)]
+        color: bool
+    };
+    let output = quote! {
+        ::bpaf::short('c').long("color").negatable_switch()
+    };
+    assert_eq!(input.to_token_stream().to_string(), output.to_string());
+}
@@ -3,6 +3,7 @@
 //! For documentation refer to `bpaf` library docs: <https://docs.rs/bpaf/latest/bpaf/>
 
 mod attrs;
+mod choice;
 mod field;
 mod named_field;
 mod top;
@@ -18,6 +19,7 @@ mod help;
 mod custom_path;
 mod td;
 
+use choice::ChoiceEnum;
 use top::Top;
 
 /// Derive macro for bpaf command line parser
@@ -27,3 +29,11 @@ use top::Top;
 pub fn derive_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     quote::ToTokens::to_token_stream(&syn::parse_macro_input!(input as Top)).into()
 }
+
+/// Derive macro for `bpaf::Choice`, for enums with unit variants
+///
+/// For documentation refer to bpaf library: <https://docs.rs/bpaf/latest/bpaf/>
+#[proc_macro_derive(Choice)]
+pub fn derive_choice(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    quote::ToTokens::to_token_stream(&syn::parse_macro_input!(input as ChoiceEnum)).into()
+}
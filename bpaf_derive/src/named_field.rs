@@ -73,6 +73,7 @@ impl ToTokens for Consumer {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
             Consumer::Switch { .. } => quote!(switch()),
+            Consumer::NegatableSwitch { .. } => quote!(negatable_switch()),
             Consumer::Flag {
                 present, absent, ..
             } => quote!(flag(#present, #absent)),
@@ -88,6 +89,16 @@ impl ToTokens for Consumer {
                 let tf = ty.as_ref().map(TurboFish);
                 quote!(argument #tf(#metavar))
             }
+            Consumer::Choice { ty, .. } => {
+                let tf = ty.as_ref().map(TurboFish);
+                quote!(choice_of #tf())
+            }
+            Consumer::OptionalValue {
+                metavar, bare, ty, ..
+            } => {
+                let tf = ty.as_ref().map(TurboFish);
+                quote!(optional_value #tf(#metavar, #bare))
+            }
             Consumer::Positional { metavar, ty, .. } => {
                 let metavar = MMetavar(metavar.as_ref());
                 let tf = ty.as_ref().map(TurboFish);
@@ -207,6 +218,8 @@ impl StructField {
         for attr in field_attrs.naming {
             if let Name::Env { name, .. } = attr {
                 env.push(StrictName::Env { name });
+            } else if let Name::Config { name, .. } = attr {
+                env.push(StrictName::Config { name });
             } else {
                 naming.push(StrictName::from_name(attr, &name)?);
             }
@@ -253,6 +266,8 @@ impl StructField {
         let shape = split_type(&ty);
 
         if let Consumer::Argument { ty, .. }
+        | Consumer::Choice { ty, .. }
+        | Consumer::OptionalValue { ty, .. }
         | Consumer::Positional { ty, .. }
         | Consumer::Any { ty, .. } = &mut cons
         {
@@ -288,7 +303,10 @@ impl StructField {
                 Shape::Bool => {
                     if name.is_none()
                         && naming.is_empty()
-                        && matches!(cons, Consumer::Switch { .. })
+                        && matches!(
+                            cons,
+                            Consumer::Switch { .. } | Consumer::NegatableSwitch { .. }
+                        )
                     {
                         let msg = "Can't derive consumer for unnamed boolean field, try adding one of #[bpaf(positional)], #[bpaf(long(\"name\")] or #[bpaf(short('n'))] annotations to it";
                         let err = Error::new_spanned(ty, msg);
@@ -299,11 +317,20 @@ impl StructField {
             }
         }
 
-        let help = match field_attrs.help.pop() {
+        let mut help = match field_attrs.help.pop() {
             Some(h) => Some(Help::Custom(h.doc)),
             None => help,
         };
 
+        if !field_attrs.translations.is_empty() {
+            let base = help.unwrap_or_else(|| Help::from(""));
+            let langs = field_attrs.translations.iter().map(|(l, _)| l);
+            let docs = field_attrs.translations.iter().map(|(_, d)| d);
+            help = Some(Help::Custom(Box::new(parse_quote!(
+                ::bpaf::Doc::from(#base) #(.translation(#langs, #docs))*
+            ))));
+        }
+
         Ok(StructField {
             name,
             env,
@@ -30,6 +30,7 @@ pub(crate) struct OptionsCfg {
     pub(crate) usage: Option<Box<Expr>>,
     pub(crate) version: Option<Box<Expr>>,
     pub(crate) max_width: Option<Box<Expr>>,
+    pub(crate) locale: Option<Box<Expr>>,
 }
 
 #[derive(Debug, Default)]
@@ -250,6 +251,9 @@ impl Parse for TopInfo {
             } else if kw == "max_width" {
                 let max_width = parse_arg(input)?;
                 with_options(&kw, options.as_mut(), |opt| opt.max_width = Some(max_width))?;
+            } else if kw == "locale" {
+                let locale = parse_arg(input)?;
+                with_options(&kw, options.as_mut(), |opt| opt.locale = Some(locale))?;
             } else if let Some(pd) = PostDecor::parse(input, &kw)? {
                 attrs.push(pd);
             } else {
@@ -198,6 +198,7 @@ impl ToTokens for Top {
                     footer,
                     header,
                     max_width,
+                    locale,
                 } = options;
 
                 let version = version.as_ref().map(|v| quote!(.version(#v)));
@@ -206,6 +207,7 @@ impl ToTokens for Top {
                 let footer = footer.as_ref().map(|v| quote!(.footer(#v)));
                 let header = header.as_ref().map(|v| quote!(.header(#v)));
                 let max_width = max_width.as_ref().map(|v| quote!(.max_width(#v)));
+                let locale = locale.as_ref().map(|v| quote!(.locale(#v)));
 
                 let CommandCfg {
                     name,
@@ -231,6 +233,7 @@ impl ToTokens for Top {
                         #footer
                         #usage
                         #max_width
+                        #locale
                         .command(#name)
                         #(#short)*
                         #(#long)*
@@ -249,6 +252,7 @@ impl ToTokens for Top {
                     footer,
                     header,
                     max_width,
+                    locale,
                 } = options;
                 let body = match cargo_helper {
                     Some(cargo) => quote!(::bpaf::cargo_helper(#cargo, #body)),
@@ -261,6 +265,7 @@ impl ToTokens for Top {
                 let footer = footer.as_ref().map(|v| quote!(.footer(#v)));
                 let header = header.as_ref().map(|v| quote!(.header(#v)));
                 let max_width = max_width.as_ref().map(|v| quote!(.max_width(#v)));
+                let locale = locale.as_ref().map(|v| quote!(.locale(#v)));
 
                 quote! {
                     #vis fn #generate() -> ::bpaf::OptionParser<#ty> {
@@ -275,6 +280,7 @@ impl ToTokens for Top {
                         #footer
                         #usage
                         #max_width
+                        #locale
                     }
                 }
             }
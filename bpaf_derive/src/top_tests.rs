@@ -956,6 +956,31 @@ fn fallback_for_struct() {
     assert_eq!(top.to_token_stream().to_string(), expected.to_string());
 }
 
+#[test]
+fn exactly_one_of_for_struct() {
+    let top: Top = parse_quote! {
+        #[bpaf(exactly_one_of)]
+        struct Input {
+            file: Option<String>,
+            stdin: bool,
+        }
+    };
+
+    let expected = quote! {
+        fn input() -> impl ::bpaf::Parser<Input> {
+            #[allow(unused_imports)]
+            use ::bpaf::Parser;
+            {
+                let file = ::bpaf::long("file").argument::<String>("ARG").optional();
+                let stdin = ::bpaf::long("stdin").switch();
+                ::bpaf::construct!(Input { file, stdin, })
+            }
+            .exactly_one_of()
+        }
+    };
+    assert_eq!(top.to_token_stream().to_string(), expected.to_string());
+}
+
 #[test]
 fn adjacent_for_struct() {
     let top: Top = parse_quote! {
@@ -1200,6 +1225,29 @@ fn max_width() {
     assert_eq!(input.to_token_stream().to_string(), expected.to_string());
 }
 
+#[test]
+fn locale() {
+    let input: Top = parse_quote! {
+        #[bpaf(options, locale(german()))]
+        struct Opt {
+            verbose: bool,
+        }
+    };
+    let expected = quote! {
+        fn opt() -> ::bpaf::OptionParser<Opt> {
+            #[allow(unused_imports)]
+            use ::bpaf::Parser;
+            {
+                let verbose = ::bpaf::long("verbose").switch();
+                ::bpaf::construct!(Opt { verbose, })
+            }
+            .to_options()
+            .locale(german())
+        }
+    };
+    assert_eq!(input.to_token_stream().to_string(), expected.to_string());
+}
+
 #[test]
 fn custom_bpaf_path_options() {
     let input: Top = parse_quote! {
@@ -1279,3 +1327,29 @@ fn push_down_argument() {
     assert_eq!(top.to_token_stream().to_string(), expected.to_string());
 }
 */
+
+#[test]
+fn choice_enum() {
+    let input: crate::choice::ChoiceEnum = parse_quote! {
+        enum Mode {
+            Fast,
+            SafeMode,
+        }
+    };
+    let expected = quote! {
+        impl ::std::str::FromStr for Mode {
+            type Err = ::std::string::String;
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                match s {
+                    "fast" => ::std::result::Result::Ok(Mode::Fast),
+                    "safe-mode" => ::std::result::Result::Ok(Mode::SafeMode),
+                    _ => ::std::result::Result::Err(::std::format!("{} is not a valid value", s)),
+                }
+            }
+        }
+        impl ::bpaf::Choice for Mode {
+            const CHOICES: &'static [&'static str] = &["fast", "safe-mode"];
+        }
+    };
+    assert_eq!(input.to_token_stream().to_string(), expected.to_string());
+}
@@ -30,6 +30,8 @@ fn main() -> Result<()> {
         "../dotfiles/zsh",
         "../dotfiles/fish/completions",
         "../dotfiles/elvish",
+        "../dotfiles/nushell",
+        "../dotfiles/pwsh",
     ] {
         std::fs::create_dir_all(dir)?;
     }
@@ -109,6 +111,60 @@ fn main() -> Result<()> {
         }
     }
 
+    // nushell config
+    {
+        std::fs::File::create("../dotfiles/nushell/env.nu")?;
+        let mut config = std::fs::File::create("../dotfiles/nushell/config.nu")?;
+        writeln!(config, "$env.config.show_banner = false")?;
+        writeln!(config, "$env.PROMPT_COMMAND = {{|| \"% \" }}")?;
+        writeln!(config, "$env.PROMPT_COMMAND_RIGHT = \"\"")?;
+        writeln!(config, "$env.PROMPT_INDICATOR = \"\"")?;
+
+        for example in &examples {
+            let common = [
+                "run",
+                "--release",
+                "--package=bpaf",
+                "--example",
+                example,
+                "--",
+            ];
+
+            let mut cmd = Command::new("cargo");
+            let nu = cmd
+                .args(common)
+                .arg("--bpaf-complete-style-nu")
+                .output()?
+                .stdout;
+            writeln!(config, "{}", std::str::from_utf8(&nu)?)?;
+        }
+    }
+
+    // pwsh config
+    {
+        let mut profile = std::fs::File::create("../dotfiles/pwsh/profile.ps1")?;
+        writeln!(profile, "function prompt {{ '% ' }}")?;
+
+        for example in &examples {
+            let common = [
+                "run",
+                "--release",
+                "--package=bpaf",
+                "--example",
+                example,
+                "--",
+            ];
+
+            let mut cmd = Command::new("cargo");
+            let pwsh = cmd
+                .args(common)
+                .arg("--bpaf-complete-style-pwsh")
+                .output()?
+                .stdout;
+            writeln!(profile, "{}", std::str::from_utf8(&pwsh)?)?;
+        }
+    }
+
     for example in &examples {
         let common = [
             "run",
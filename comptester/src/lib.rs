@@ -14,6 +14,8 @@ pub const ZSH_TIMEOUT: Duration = Duration::from_millis(100);
 pub const BASH_TIMEOUT: Duration = Duration::from_millis(50);
 pub const FISH_TIMEOUT: Duration = Duration::from_millis(50);
 pub const ELVISH_TIMEOUT: Duration = Duration::from_millis(50);
+pub const NU_TIMEOUT: Duration = Duration::from_millis(100);
+pub const PWSH_TIMEOUT: Duration = Duration::from_millis(200);
 
 /// Do zsh completion test for this input
 ///
@@ -67,6 +69,32 @@ pub fn elvish_comptest(input: &str) -> anyhow::Result<String> {
     comptest(command, false, input, 120, ELVISH_TIMEOUT)
 }
 
+pub fn nu_comptest(input: &str) -> anyhow::Result<String> {
+    let cwd = std::env::current_dir()?;
+    let cwd = cwd.parent().unwrap().to_str().unwrap();
+    let path = format!("{}:{cwd}/target/release/examples", std::env::var("PATH")?,);
+    let mut command = Command::new("nu");
+    command
+        .env("PATH", path)
+        .env("XDG_CONFIG_HOME", format!("{cwd}/dotfiles"));
+    comptest(command, false, input, 120, NU_TIMEOUT)
+}
+
+pub fn pwsh_comptest(input: &str) -> anyhow::Result<String> {
+    let cwd = std::env::current_dir()?;
+    let cwd = cwd.parent().unwrap().to_str().unwrap();
+    let path = format!("{}:{cwd}/target/release/examples", std::env::var("PATH")?,);
+    let mut command = Command::new("pwsh");
+    command.env("PATH", path).args([
+        "-NoLogo",
+        "-NoProfile",
+        "-NoExit",
+        "-File",
+        &format!("{cwd}/dotfiles/pwsh/profile.ps1"),
+    ]);
+    comptest(command, false, input, 120, PWSH_TIMEOUT)
+}
+
 fn comptest(
     command: Command,
     echo: bool,
@@ -21,3 +21,15 @@ uggo                     -- CLI tool to query builds from u.gg";
     //    let buf = zsh_comptest("simple_dynamic ?").unwrap();
     //    todo!("\n{}", buf);
 }
+
+#[test]
+fn sd_single_crate_nu() {
+    let buf = nu_comptest("simple_dynamic --crate cargo-sh\t").unwrap();
+    assert_eq!(buf, "% simple_dynamic --crate cargo-show-asm");
+}
+
+#[test]
+fn sd_single_crate_pwsh() {
+    let buf = pwsh_comptest("simple_dynamic --crate cargo-sh\t").unwrap();
+    assert_eq!(buf, "% simple_dynamic --crate cargo-show-asm");
+}
@@ -10,7 +10,7 @@ pub fn options() -> OptionParser<Options> {
     let desert = ["apple", "banana", "orange", "grape", "strawberry"]
         .iter()
         .map(|name| {
-            long(name)
+            long(*name)
                 .help("Pick one of the options")
                 .req_flag(*name)
                 .boxed()
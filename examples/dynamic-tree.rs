@@ -64,11 +64,11 @@ fn make_parser(item: &Cog) -> Box<dyn Parser<&'static str>> {
             help,
             name,
             operation,
-        } => Box::new(pure(*operation).to_options().descr(*help).command(name)),
+        } => Box::new(pure(*operation).to_options().descr(*help).command(*name)),
         Cog::Group { name, help, nested } => {
             let nested = nested.iter().map(make_parser).collect::<Vec<_>>();
             let inner = choose(nested);
-            inner.to_options().descr(*help).command(name).boxed()
+            inner.to_options().descr(*help).command(*name).boxed()
         }
     }
 }
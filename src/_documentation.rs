@@ -2241,6 +2241,16 @@
             //! 		$ your_program --bpaf-complete-style-elvish >> ~/.config/elvish/rc.elv
             //! 		```
             //! 
+            //! 	 1. **nushell**
+            //! 		```console
+            //! 		$ your_program --bpaf-complete-style-nu >> ~/.config/nushell/config.nu
+            //! 		```
+            //! 
+            //! 	 1. **pwsh**
+            //! 		```console
+            //! 		$ your_program --bpaf-complete-style-pwsh >> $PROFILE
+            //! 		```
+            //! 
             //! 4. Restart your shell - you need to done it only once or optionally after bpaf major version
             //!     upgrade: generated completion files contain only instructions how to ask your program for
             //!     possible completions and don’t change even if options are different.
@@ -1,4 +1,4 @@
-use std::ffi::OsString;
+use std::{ffi::OsString, rc::Rc};
 
 pub(crate) use crate::arg::*;
 use crate::{
@@ -35,6 +35,7 @@ use crate::{
 pub struct Args<'a> {
     items: Box<dyn ExactSizeIterator<Item = OsString> + 'a>,
     name: Option<String>,
+    response_files: bool,
     #[cfg(feature = "autocomplete")]
     c_rev: Option<usize>,
 }
@@ -77,6 +78,41 @@ impl Args<'_> {
         self.name = Some(name.to_owned());
         self
     }
+
+    /// Expand `@path` arguments with contents of response files
+    ///
+    /// Each `@path` word is replaced with words read from the file at `path`. Words in the file
+    /// are separated by whitespace, use single or double quotes to keep spaces inside a word
+    /// and backslash to escape quotes. Response files can refer to other response files,
+    /// words after `--` are not expanded. See also [`OptionParser::response_files`].
+    ///
+    /// Errors related to words from a response file mention the file and the line they came
+    /// from.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # let path = std::env::temp_dir().join("bpaf_with_response_files.txt");
+    /// std::fs::write(&path, "-v\n--name 'hello world'").unwrap();
+    /// # let at_path = format!("@{}", path.display());
+    ///
+    /// let verbose = short('v').switch();
+    /// let name = long("name").argument::<String>("NAME");
+    /// let parser = construct!(verbose, name).to_options();
+    ///
+    /// let args = [at_path.as_str()];
+    /// let r = parser
+    ///     .run_inner(Args::from(&args[..]).with_response_files())
+    ///     .unwrap();
+    /// assert_eq!(r, (true, "hello world".to_owned()));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    /// [`OptionParser::response_files`]: crate::OptionParser::response_files
+    #[must_use]
+    pub fn with_response_files(mut self) -> Self {
+        self.response_files = true;
+        self
+    }
 }
 
 impl<const N: usize> From<&'static [&'static str; N]> for Args<'_> {
@@ -86,6 +122,7 @@ impl<const N: usize> From<&'static [&'static str; N]> for Args<'_> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name: None,
+            response_files: false,
         }
     }
 }
@@ -97,6 +134,7 @@ impl<'a> From<&'a [&'a std::ffi::OsStr]> for Args<'a> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name: None,
+            response_files: false,
         }
     }
 }
@@ -108,6 +146,7 @@ impl<'a> From<&'a [&'a str]> for Args<'a> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name: None,
+            response_files: false,
         }
     }
 }
@@ -119,6 +158,7 @@ impl<'a> From<&'a [String]> for Args<'a> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name: None,
+            response_files: false,
         }
     }
 }
@@ -130,6 +170,7 @@ impl<'a> From<&'a [OsString]> for Args<'a> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name: None,
+            response_files: false,
         }
     }
 }
@@ -150,6 +191,7 @@ impl Args<'_> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name,
+            response_files: false,
         }
     }
 }
@@ -243,11 +285,31 @@ fn disambiguate_short(
 pub use inner::State;
 /// Hides [`State`] internal implementation
 mod inner {
-    use std::{ops::Range, rc::Rc};
+    use std::{borrow::Cow, ffi::OsString, ops::Range, rc::Rc};
 
-    use crate::{error::Message, item::Item, Args};
+    use crate::{
+        config::ConfigProvider, error::Message, item::Item, locale::Messages, response::WordSource,
+        Args, ValueSource,
+    };
 
     use super::{split_os_argument, Arg, ArgType, ItemState};
+
+    /// Items reserved for [`ParsePositional::stop_parsing`] in a command at `path`
+    ///
+    /// [`ParsePositional::stop_parsing`]: crate::parsers::ParsePositional::stop_parsing
+    #[derive(Clone, Debug)]
+    struct Tail {
+        items: Range<usize>,
+        path: Vec<String>,
+    }
+
+    /// Scope and item states saved by [`State::enter_subparser`]
+    #[derive(Clone, Debug)]
+    pub(crate) struct Snapshot {
+        scope: Range<usize>,
+        item_state: Rc<[ItemState]>,
+    }
+
     #[derive(Clone, Debug)]
     #[doc(hidden)]
     pub struct State {
@@ -271,6 +333,46 @@ mod inner {
         #[cfg(feature = "autocomplete")]
         comp: Option<crate::complete_gen::Complete>,
 
+        /// For every item - index of the command line argument it was created from, items can
+        /// be split: `--foo=bar` or `-abc`, or skipped: `--bpaf-complete-rev=8`
+        origin: Rc<[usize]>,
+
+        /// Values for named items with a config key, see [`OptionParser::config_source`]
+        ///
+        /// [`OptionParser::config_source`]: crate::OptionParser::config_source
+        pub(crate) config: Option<ConfigProvider>,
+
+        /// Translations for built-in messages, see [`OptionParser::locale`]
+        ///
+        /// [`OptionParser::locale`]: crate::OptionParser::locale
+        pub(crate) messages: Messages,
+
+        /// Response file positions for words that came from response files, indexed the same
+        /// way as [`origin`](State::origin), empty if response files are not used
+        sources: Rc<[Option<WordSource>]>,
+
+        /// Where the most recently parsed value came from, see [`Parser::with_source`]
+        ///
+        /// [`Parser::with_source`]: crate::Parser::with_source
+        pub(crate) value_source: Option<ValueSource>,
+
+        /// Values taken from environment variables or a config source rather than the command
+        /// line, see [`Parser::at_least_one_of`](crate::Parser::at_least_one_of)
+        pub(crate) implicit: Vec<ValueSource>,
+
+        /// Answers for missing items, see [`OptionParser::interactive`]
+        ///
+        /// [`OptionParser::interactive`]: crate::OptionParser::interactive
+        #[cfg(feature = "prompt")]
+        pub(crate) prompt: Option<crate::prompt::PromptState>,
+
+        /// Items reserved for a passthrough parser, outside of the scope for everything else
+        tail: Option<Tail>,
+
+        /// Items as they were when the current subparser started, used to check relations
+        /// between parsers, see [`Parser::requires`](crate::Parser::requires)
+        initial: Snapshot,
+
         //        /// A way to customize behavior for --help and error handling
         //        pub(crate) improve_error: super::Improve,
         /// Describes scope current parser will be consuming elements from. Usually it will be
@@ -289,6 +391,109 @@ mod inner {
         pub(crate) fn depth(&self) -> usize {
             self.path.len()
         }
+
+        /// Index of the command line argument item at `ix` was created from
+        pub(crate) fn origin(&self, ix: usize) -> usize {
+            self.origin.get(ix).copied().unwrap_or(ix)
+        }
+
+        /// Response file and line the argument at `origin` index was read from, if any
+        pub(crate) fn source(&self, origin: usize) -> Option<&WordSource> {
+            self.sources.get(origin)?.as_ref()
+        }
+
+        /// Record the item at `ix` as a source of the most recently parsed value
+        pub(crate) fn set_argv_source(&mut self, ix: usize) {
+            if let Some(arg) = self.items.get(ix) {
+                self.value_source = Some(ValueSource::Argv {
+                    index: self.origin(ix),
+                    spelling: arg.to_string(),
+                });
+            }
+        }
+
+        /// Ask user for a missing value if item has a prompt and prompting is enabled
+        ///
+        /// Non empty `choices` are offered to the user as a list to pick from
+        #[cfg(feature = "prompt")]
+        pub(crate) fn prompt_value(
+            &mut self,
+            prompt: Option<&crate::prompt::Prompt>,
+            choices: &[Cow<'static, str>],
+        ) -> Option<OsString> {
+            let prompt = prompt?;
+            #[cfg(feature = "autocomplete")]
+            if self.comp.is_some() {
+                return None;
+            }
+            let state = self.prompt.as_mut()?;
+            if !choices.is_empty() {
+                state.choices = Some(choices.iter().map(|c| c.to_string()).collect());
+            }
+            let val = state.ask(prompt)?;
+            self.value_source = Some(ValueSource::Prompt);
+            self.current = None;
+            Some(val)
+        }
+
+        /// Evaluate a parser that deals with missing values on its own: `optional`, `many` or
+        /// `fallback`, asking user for values would defeat its purpose
+        pub(crate) fn without_prompt<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+            #[cfg(feature = "prompt")]
+            let prompt = self.prompt.take();
+            let res = f(self);
+            #[cfg(feature = "prompt")]
+            {
+                self.prompt = prompt;
+            }
+            res
+        }
+
+        /// Most recently parsed value failed validation, if user entered it - ask again
+        #[cfg(feature = "prompt")]
+        pub(crate) fn reject_prompt(&self) {
+            if let (Some(prompt), Some(ValueSource::Prompt)) = (&self.prompt, &self.value_source) {
+                prompt.session.reject_last();
+            }
+        }
+
+        /// Look up a value for one of the config keys, if config source is present
+        pub(crate) fn config_value(&mut self, keys: &[Cow<'static, str>]) -> Option<OsString> {
+            let (key, val) = self.config.as_ref()?.lookup(keys)?;
+            self.set_implicit_source(ValueSource::Config(key.clone()));
+            Some(val)
+        }
+
+        /// Check if config source sets a flag using one of the keys
+        pub(crate) fn config_flag(&mut self, keys: &[Cow<'static, str>]) -> bool {
+            match self
+                .config
+                .as_ref()
+                .and_then(|config| config.lookup_flag(keys))
+            {
+                Some(key) => {
+                    self.set_implicit_source(ValueSource::Config(key.clone()));
+                    true
+                }
+                None => false,
+            }
+        }
+
+        /// Look up the first present environment variable
+        pub(crate) fn env_value(&mut self, names: &[Cow<'static, str>]) -> Option<OsString> {
+            let (name, val) = names
+                .iter()
+                .find_map(|name| Some((name, std::env::var_os(name.as_ref())?)))?;
+            self.set_implicit_source(ValueSource::Env(name.clone()));
+            Some(val)
+        }
+
+        /// Record an environment variable or a config key as a source of the most recently
+        /// parsed value
+        fn set_implicit_source(&mut self, source: ValueSource) {
+            self.implicit.push(source.clone());
+            self.value_source = Some(source);
+        }
     }
 
     pub(crate) struct ArgsIter<'a> {
@@ -317,6 +522,7 @@ mod inner {
             err: &mut Option<Message>,
         ) -> State {
             let mut items = Vec::new();
+            let mut origin = Vec::new();
             let mut pos_only = false;
             let mut double_dash_marker = None;
 
@@ -326,9 +532,27 @@ mod inner {
                 name: args.name.as_deref(),
             };
 
-            for os in args.items {
+            let mut sources = Vec::new();
+            let words: Box<dyn Iterator<Item = OsString>> = if args.response_files {
+                match crate::response::expand(args.items) {
+                    Ok(words) => {
+                        let (words, srcs): (Vec<_>, Vec<_>) = words.into_iter().unzip();
+                        sources = srcs;
+                        Box::new(words.into_iter())
+                    }
+                    Err(e) => {
+                        *err = Some(Message::ResponseFile(e));
+                        Box::new(std::iter::empty())
+                    }
+                }
+            } else {
+                args.items
+            };
+
+            for (pos, os) in words.enumerate() {
                 if pos_only {
                     items.push(Arg::PosWord(os));
+                    origin.push(pos);
                     continue;
                 }
 
@@ -347,6 +571,7 @@ mod inner {
                             short_args,
                             &mut items,
                         ) {
+                            origin.resize(items.len(), pos);
                             *err = Some(msg);
                             break;
                         }
@@ -377,6 +602,7 @@ mod inner {
                         });
                     }
                 }
+                origin.resize(items.len(), pos);
             }
 
             let mut item_state = vec![ItemState::Unparsed; items.len()];
@@ -400,15 +626,29 @@ mod inner {
             if let Some(name) = args.name {
                 path.push(name);
             }
+            let initial = Snapshot {
+                scope: 0..items.len(),
+                item_state: item_state.as_slice().into(),
+            };
             State {
                 item_state,
                 remaining,
                 scope: 0..items.len(),
+                initial,
                 items: items.into(),
+                origin: origin.into(),
                 current: None,
                 path,
                 #[cfg(feature = "autocomplete")]
                 comp,
+                config: None,
+                messages: Messages::default(),
+                sources: sources.into(),
+                value_source: None,
+                implicit: Vec::new(),
+                #[cfg(feature = "prompt")]
+                prompt: None,
+                tail: None,
             }
         }
     }
@@ -481,6 +721,165 @@ mod inner {
             }
         }
 
+        /// Consume everything left in scope, turning items back into command line words
+        pub(crate) fn take_rest(&mut self) -> Vec<OsString> {
+            let ixs = self.items_iter().map(|(ix, _)| ix).collect::<Vec<_>>();
+            for ix in &ixs {
+                self.remove(*ix);
+            }
+            self.words(&ixs)
+        }
+
+        /// Indices of items consumed since `before` was cloned from this state
+        pub(crate) fn taken_since(&self, before: &State) -> Vec<usize> {
+            (0..self.items.len())
+                .filter(|ix| before.item_state[*ix].present() && !self.item_state[*ix].present())
+                .collect()
+        }
+
+        /// Remember items available to a subparser that is about to run, returns the
+        /// snapshot of the enclosing one so it can be restored with [`State::leave_subparser`]
+        pub(crate) fn enter_subparser(&mut self) -> Snapshot {
+            let snapshot = Snapshot {
+                scope: self.scope(),
+                item_state: self.item_state.as_slice().into(),
+            };
+            std::mem::replace(&mut self.initial, snapshot)
+        }
+
+        pub(crate) fn leave_subparser(&mut self, outer: Snapshot) {
+            self.initial = outer;
+        }
+
+        /// A copy of this state with items restored as they were when the current subparser
+        /// started, used to check if some other parser is present on a command line
+        pub(crate) fn probe(&self) -> State {
+            let mut probe = self.clone();
+            probe.item_state = self.initial.item_state.to_vec();
+            probe.set_scope(self.initial.scope.clone());
+            probe.current = None;
+            #[cfg(feature = "autocomplete")]
+            {
+                probe.comp = None;
+            }
+            #[cfg(feature = "prompt")]
+            {
+                probe.prompt = None;
+            }
+            probe
+        }
+
+        /// A copy of this state for shell completion of an empty value in place of the item at
+        /// `ix`, items after it are dropped. Used to find values the item could have been
+        #[cfg(feature = "autocomplete")]
+        pub(crate) fn value_probe(&self, ix: usize) -> Option<State> {
+            let empty = match self.items.get(ix)? {
+                Arg::Word(_) => Arg::Word(OsString::new()),
+                Arg::ArgWord(_) => Arg::ArgWord(OsString::new()),
+                Arg::PosWord(_) => Arg::PosWord(OsString::new()),
+                Arg::Short(..) | Arg::Long(..) => return None,
+            };
+            let mut probe = self.probe();
+            let scope = probe.scope();
+            if !scope.contains(&ix) {
+                return None;
+            }
+            let mut items = probe.items[..ix].to_vec();
+            items.push(empty);
+            probe.items = items.into();
+            probe.item_state.truncate(ix + 1);
+            probe.set_scope(scope.start..ix + 1);
+            probe.tail = None;
+            let mut comp = crate::complete_gen::Complete::new(0);
+            comp.probe = true;
+            probe.comp = Some(comp);
+            Some(probe)
+        }
+
+        /// Reserve items from `start` to the end of the scope for a passthrough parser in a
+        /// command at `path`, everything else will only see items before `start`
+        pub(crate) fn reserve_tail(&mut self, start: usize, path: Vec<String>) {
+            let scope = self.scope();
+            self.tail = Some(Tail {
+                items: start..scope.end,
+                path,
+            });
+            self.set_scope(scope.start..start);
+        }
+
+        /// Return reserved items back to the scope if passthrough parser in this command or
+        /// any of its subcommands didn't take them, so they can be reported as unexpected
+        pub(crate) fn release_tail(&mut self) {
+            if let Some(tail) = &self.tail {
+                if tail.path.starts_with(&self.path) {
+                    let scope = self.scope().start..tail.items.end;
+                    self.tail = None;
+                    self.set_scope(scope);
+                }
+            }
+        }
+
+        /// Consume items reserved for a passthrough parser in the current command, returning
+        /// them exactly as they were written on the command line
+        pub(crate) fn take_tail(&mut self) -> Option<Vec<OsString>> {
+            if self.tail.as_ref()?.path != self.path {
+                return None;
+            }
+            let tail = self.tail.take()?.items;
+            let mut res = Vec::new();
+            let start = tail.start;
+            for ix in tail {
+                if self.item_state[ix].present() {
+                    self.item_state[ix] = ItemState::Parsed;
+                    self.current = Some(ix);
+                }
+                // split items share the origin, first of them keeps the original word
+                if ix == start || self.origin(ix - 1) != self.origin(ix) {
+                    res.push(self.items[ix].os_str().to_owned());
+                }
+            }
+            Some(res)
+        }
+
+        /// Turn items at `ixs` back into command line words
+        ///
+        /// Values attached to flags stay attached and the `--` separator is restored if
+        /// any of the strictly positional items are present
+        pub(crate) fn words(&self, ixs: &[usize]) -> Vec<OsString> {
+            let mut res = Vec::<OsString>::new();
+            let mut pos_only = false;
+            let mut prev = None;
+            for &ix in ixs {
+                let attached =
+                    ix > 0 && prev == Some(ix - 1) && self.origin(ix - 1) == self.origin(ix);
+                prev = Some(ix);
+                match &self.items[ix] {
+                    Arg::Short(s, _, _) => res.push(format!("-{}", s).into()),
+                    Arg::Long(l, _, _) => res.push(format!("--{}", l).into()),
+                    Arg::ArgWord(w) | Arg::Word(w) if attached => {
+                        if let Some(last) = res.last_mut() {
+                            if matches!(self.items[ix], Arg::ArgWord(_)) {
+                                last.push("=");
+                            }
+                            last.push(w);
+                        }
+                    }
+                    Arg::ArgWord(w) | Arg::Word(w) => res.push(w.clone()),
+                    Arg::PosWord(w) => {
+                        if !pos_only {
+                            pos_only = true;
+                            // `--` itself is present when completing right after it
+                            if ix > 0 && matches!(self.items[ix - 1], Arg::PosWord(_)) {
+                                res.push("--".into());
+                            }
+                        }
+                        res.push(w.clone());
+                    }
+                }
+            }
+            res
+        }
+
         #[cfg(feature = "autocomplete")]
         /// Check if parser performs autocompletion
         ///
@@ -490,6 +889,12 @@ mod inner {
             self.comp.is_some()
         }
 
+        /// Check if parser runs to find values to suggest, see [`State::value_probe`]
+        #[cfg(feature = "autocomplete")]
+        pub(crate) fn is_probe(&self) -> bool {
+            self.comp.as_ref().map_or(false, |c| c.probe)
+        }
+
         /// Narrow down scope of &self to adjacently consumed values compared to original.
         pub(crate) fn adjacent_scope(&self, original: &State) -> Option<Range<usize>> {
             if self.items.is_empty() {
@@ -651,6 +1056,7 @@ impl State {
             .items_iter()
             .find(|arg| named.matches_arg(arg.1, false))
         {
+            self.set_argv_source(ix);
             self.remove(ix);
             true
         } else {
@@ -682,12 +1088,39 @@ impl State {
             _ => return Err(Error(Message::NoArgument(key_ix, metavar))),
         };
         let val = val.clone();
+        self.set_argv_source(key_ix);
         self.current = Some(val_ix);
         self.remove(key_ix);
         self.remove(val_ix);
         Ok(Some(val))
     }
 
+    /// take an argument with an optional value: `--color`, `--color=never` or `-cnever`
+    ///
+    /// Value must be attached to the name, `--color never` consumes only the name.
+    /// returns None if the name is absent and Some(None) if value is missing
+    pub(crate) fn take_opt_arg(&mut self, named: &NamedArg) -> Option<Option<OsString>> {
+        let (key_ix, arg) = self
+            .items_iter()
+            .find(|arg| named.matches_arg(arg.1, false))?;
+        let attached = matches!(arg, Arg::Short(_, true, _) | Arg::Long(_, true, _));
+
+        let val_ix = key_ix + 1;
+        let val = match (attached, self.get(val_ix)) {
+            (true, Some(Arg::Word(w) | Arg::ArgWord(w))) => Some(w.clone()),
+            _ => None,
+        };
+        self.set_argv_source(key_ix);
+        self.remove(key_ix);
+        if val.is_some() {
+            self.current = Some(val_ix);
+            self.remove(val_ix);
+        } else {
+            self.current = Some(key_ix);
+        }
+        Some(val)
+    }
+
     /// gets first positional argument present
     ///
     /// returns Ok(None) if input is empty
@@ -703,6 +1136,7 @@ impl State {
         }) {
             Some((ix, strict, w)) => {
                 let w = w.clone();
+                self.set_argv_source(ix);
                 self.current = Some(ix);
                 self.remove(ix);
                 Ok((ix, strict, w))
@@ -713,6 +1147,8 @@ impl State {
                     item: Item::Positional {
                         help: None,
                         metavar,
+                        choices: Rc::from(Vec::new()),
+                        passthrough: false,
                     },
                     position: scope.start,
                     scope,
@@ -748,7 +1184,7 @@ mod tests {
     use super::*;
     use crate::meta_help::Metavar;
     use crate::{long, short};
-    const M: Metavar = Metavar("M");
+    const M: Metavar = Metavar(std::borrow::Cow::Borrowed("M"));
 
     #[allow(clippy::fallible_impl_from)] // this is for tests only, panic is okay
     impl<const N: usize> From<&'static [&'static str; N]> for State {
@@ -8,6 +8,8 @@
 //! Examples contain combinatoric usage, for derive usage you should create a parser function and
 //! use `external` annotation.
 
+use std::borrow::Cow;
+
 use crate::{construct, literal, parsers::NamedArg, short, Parser};
 
 /// `--verbose` and `--quiet` flags with results encoded as number
@@ -102,6 +104,9 @@ pub fn verbose_by_slice<T: Copy + 'static, const N: usize>(
 /// Parser returns `Optional<T>` value, you can add a fallback with [`map`](Parser::map) or turn
 /// missing value info failure with a custom error message with [`parse`](Parser::parse).
 ///
+/// For a `bool` switch with a `--no-` prefixed counterpart use
+/// [`negatable_switch`](NamedArg::negatable_switch) instead.
+///
 /// # Example
 /// ```console
 /// $ app --banana --no-banana --banana --banana
@@ -152,7 +157,7 @@ pub fn toggle_flag<T: Copy + 'static>(
 #[cfg_attr(not(doctest), doc = include_str!("docs2/cargo_helper.md"))]
 ///
 #[must_use]
-pub fn cargo_helper<P, T>(cmd: &'static str, parser: P) -> impl Parser<T>
+pub fn cargo_helper<P, T>(cmd: impl Into<Cow<'static, str>>, parser: P) -> impl Parser<T>
 where
     P: Parser<T>,
 {
@@ -1,12 +1,14 @@
+use crate::{
+    error::ErrorKind,
+    item::{Item, ShortLong},
+    locale::Messages,
+    Meta,
+};
 #[cfg(feature = "docgen")]
 use crate::{
     info::Info,
     meta_help::{HelpItem, HelpItems},
 };
-use crate::{
-    item::{Item, ShortLong},
-    Meta,
-};
 
 mod console;
 mod html;
@@ -14,8 +16,9 @@ mod html;
 mod manpage;
 mod splitter;
 
-pub(crate) use self::console::Color;
 use self::console::MAX_WIDTH;
+pub(crate) use self::console::{terminal_height, terminal_width, MONOCHROME};
+pub use self::console::{ColorChoice, Theme};
 
 #[cfg(feature = "docgen")]
 pub use manpage::Section;
@@ -147,8 +150,13 @@ impl Doc {
 
     pub(crate) fn write_item(&mut self, item: &Item) {
         match item {
-            Item::Positional { metavar, help: _ } => {
-                self.metavar(*metavar);
+            Item::Positional {
+                metavar,
+                help: _,
+                choices: _,
+                passthrough: _,
+            } => {
+                self.metavar(metavar);
             }
             Item::Command {
                 name: _,
@@ -156,6 +164,7 @@ impl Doc {
                 help: _,
                 meta: _,
                 info: _,
+                lazy_help: _,
             } => {
                 self.write_str("COMMAND ...", Style::Metavar);
             }
@@ -163,18 +172,36 @@ impl Doc {
                 name,
                 shorts: _,
                 env: _,
+                config: _,
                 help: _,
-            } => self.write_shortlong(name),
+                negatable,
+            } => match name.as_long() {
+                Some(long) if *negatable => {
+                    self.write_str("--[no-]", Style::Literal);
+                    self.write_str(long, Style::Literal);
+                }
+                _ => self.write_shortlong(name),
+            },
             Item::Argument {
                 name,
                 shorts: _,
                 metavar,
                 env: _,
+                config: _,
                 help: _,
+                choices: _,
+                choice_keys: _,
+                optional_value,
             } => {
                 self.write_shortlong(name);
-                self.write_char('=', Style::Text);
-                self.metavar(*metavar);
+                if *optional_value {
+                    self.write_str("[=", Style::Text);
+                    self.metavar(metavar);
+                    self.write_char(']', Style::Text);
+                } else {
+                    self.write_char('=', Style::Text);
+                    self.metavar(metavar);
+                }
             }
             Item::Any {
                 metavar,
@@ -221,10 +248,13 @@ impl Doc {
                     f.write_str("...", Style::Text);
                 }
 
-                Meta::Adjacent(m) | Meta::Subsection(m, _) | Meta::Suffix(m, _) => {
+                Meta::Adjacent(m)
+                | Meta::Subsection(m, _)
+                | Meta::Suffix(m, _, _)
+                | Meta::Completion(m, _) => {
                     go(m, f);
                 }
-                Meta::Skip => {} // => f.write_str("no parameters expected", Style::Text),
+                Meta::Skip | Meta::Hidden(_) => {} // => f.write_str("no parameters expected", Style::Text),
                 Meta::CustomUsage(_, u) => {
                     f.doc(u);
                 }
@@ -328,12 +358,25 @@ pub struct Doc {
 
     /// string meta info tokens
     tokens: Vec<Token>,
+
+    /// structured version of an error message this document was rendered from
+    pub(crate) error: Option<Box<ErrorKind>>,
+
+    /// picked by user with `color_arg`, if any
+    pub(crate) color: Option<ColorChoice>,
+
+    /// configured with `OptionParser::theme` and `OptionParser::locale`,
+    /// default theme and English when missing
+    pub(crate) style: Option<Box<(Theme, Messages)>>,
+
+    /// versions of this document in other languages
+    translations: Vec<(String, Doc)>,
 }
 
 impl std::fmt::Display for Doc {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let width = f.width().unwrap_or(MAX_WIDTH);
-        f.write_str(&self.render_console(true, Color::Monochrome, width))
+        f.write_str(&self.render_console(true, &MONOCHROME, width))
     }
 }
 
@@ -399,6 +442,29 @@ impl Doc {
     }
 }
 
+impl Doc {
+    /// Add a version of this document in a different language
+    ///
+    /// `bpaf` uses it instead of the original document when parser's
+    /// [`locale`](crate::OptionParser::locale) reports a matching language.
+    /// See [`Catalog`](crate::Catalog) for usage examples
+    #[must_use]
+    pub fn translation<M>(mut self, language: &str, doc: M) -> Self
+    where
+        M: Into<Doc>,
+    {
+        self.translations.push((language.to_owned(), doc.into()));
+        self
+    }
+
+    pub(crate) fn translated(&self, language: &str) -> &Doc {
+        self.translations
+            .iter()
+            .find_map(|(lang, doc)| (lang == language).then(|| doc))
+            .unwrap_or(self)
+    }
+}
+
 impl From<&str> for Doc {
     fn from(value: &str) -> Self {
         let mut buf = Doc::default();
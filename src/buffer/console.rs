@@ -30,97 +30,294 @@
 // margin sets the minimal offset for any new text and retained until new margin is set:
 // "hello" [margin 8] "world" is rendered as "hello   world"
 
+use std::{borrow::Cow, str::FromStr};
+
 use super::{
     splitter::{split, Chunk},
-    Block, Doc, Skip, Token,
+    Block, Doc, Skip, Style, Token,
 };
 
-#[cfg(feature = "color")]
-use super::Style;
-
 const MAX_TAB: usize = 24;
 pub(crate) const MAX_WIDTH: usize = 100;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-/// Default to dull color if colors are enabled,
-#[allow(dead_code)] // not fully used in without colors
-pub(crate) enum Color {
-    Monochrome,
-    #[cfg(feature = "color")]
-    Dull,
-    #[cfg(feature = "color")]
-    Bright,
+/// Colors and text attributes used to render `--help` and error messages
+///
+/// Each [`Style`] maps to a list of ANSI SGR parameters separated by `;`: `"1;32"` is bold green,
+/// `"38;5;208"` picks a color from the 256 color palette, an empty string leaves the text as is.
+/// Default theme depends on enabled cargo features: `dull-color` or `bright-color`.
+///
+/// Themes only apply when colors are enabled: output goes to a terminal according to
+/// `supports-color` with `color` feature or `CLICOLOR_FORCE` is set to something other than `0`.
+/// Setting `NO_COLOR` to a non-empty value disables colors regardless of the theme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    text: Cow<'static, str>,
+    emphasis: Cow<'static, str>,
+    literal: Cow<'static, str>,
+    metavar: Cow<'static, str>,
+    invalid: Cow<'static, str>,
 }
 
-impl Default for Color {
+pub(crate) const MONOCHROME: Theme = Theme {
+    text: Cow::Borrowed(""),
+    emphasis: Cow::Borrowed(""),
+    literal: Cow::Borrowed(""),
+    metavar: Cow::Borrowed(""),
+    invalid: Cow::Borrowed(""),
+};
+
+impl Default for Theme {
     fn default() -> Self {
         #![allow(clippy::let_and_return)]
         #![allow(unused_mut)]
         #![allow(unused_assignments)]
-        let mut res;
-        #[cfg(not(feature = "color"))]
-        {
-            res = Color::Monochrome;
-        }
+        let mut res = MONOCHROME;
 
         #[cfg(feature = "color")]
         {
-            res = Color::Dull;
+            res = Theme::dull();
         }
 
         #[cfg(feature = "bright-color")]
         {
-            res = Color::Bright;
+            res = Theme::bright();
         }
 
         #[cfg(feature = "dull-color")]
         {
-            res = Color::Dull;
+            res = Theme::dull();
         }
+        res
+    }
+}
 
-        #[cfg(feature = "color")]
-        {
-            use supports_color::{on, Stream};
-            if !(on(Stream::Stdout).is_some() && on(Stream::Stderr).is_some()) {
-                res = Color::Monochrome;
-            }
+impl Theme {
+    /// Plain text without any attributes, backticks mark names and values in error messages
+    #[must_use]
+    pub fn monochrome() -> Self {
+        MONOCHROME
+    }
+
+    /// Bold and underlined text, used with `dull-color` feature
+    #[must_use]
+    pub fn dull() -> Self {
+        Theme {
+            text: Cow::Borrowed(""),
+            emphasis: Cow::Borrowed("1;4"),
+            literal: Cow::Borrowed("1"),
+            metavar: Cow::Borrowed("4"),
+            invalid: Cow::Borrowed("1;31"),
+        }
+    }
+
+    /// Bold colored text, used with `bright-color` feature
+    #[must_use]
+    pub fn bright() -> Self {
+        Theme {
+            text: Cow::Borrowed(""),
+            emphasis: Cow::Borrowed("1;33"),
+            literal: Cow::Borrowed("1;32"),
+            metavar: Cow::Borrowed("1;34"),
+            invalid: Cow::Borrowed("1;31"),
+        }
+    }
+
+    /// Use these SGR parameters for text with this style, empty string removes the attributes
+    #[must_use]
+    pub fn style<S>(mut self, style: Style, sgr: S) -> Self
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        let sgr = sgr.into();
+        match style {
+            Style::Text => self.text = sgr,
+            Style::Emphasis => self.emphasis = sgr,
+            Style::Literal => self.literal = sgr,
+            Style::Metavar => self.metavar = sgr,
+            Style::Invalid => self.invalid = sgr,
+        }
+        self
+    }
+
+    fn sgr(&self, style: Style) -> &str {
+        match style {
+            Style::Text => &self.text,
+            Style::Emphasis => &self.emphasis,
+            Style::Literal => &self.literal,
+            Style::Metavar => &self.metavar,
+            Style::Invalid => &self.invalid,
+        }
+    }
+
+    /// No attributes for any of the styles
+    pub(crate) fn is_monochrome(&self) -> bool {
+        self == &MONOCHROME
+    }
+
+    pub(crate) fn push_str(&self, style: Style, res: &mut String, item: &str) {
+        let sgr = self.sgr(style);
+        if sgr.is_empty() {
+            res.push_str(item);
+        } else {
+            res.push_str("\x1b[");
+            res.push_str(sgr);
+            res.push('m');
+            res.push_str(item);
+            res.push_str("\x1b[0m");
         }
-        res
     }
 }
 
-#[cfg(feature = "color")]
-impl Color {
-    pub(crate) fn push_str(self, style: Style, res: &mut String, item: &str) {
-        use owo_colors::OwoColorize;
-        use std::fmt::Write;
-        match self {
-            Color::Monochrome => {
-                res.push_str(item);
-                Ok(())
-            }
-            Color::Dull => match style {
-                Style::Text => {
-                    res.push_str(item);
-                    Ok(())
-                }
-                Style::Emphasis => write!(res, "{}", item.underline().bold()),
-                Style::Literal => write!(res, "{}", item.bold()),
-                Style::Metavar => write!(res, "{}", item.underline()),
-                Style::Invalid => write!(res, "{}", item.bold().red()),
-            },
-            Color::Bright => match style {
-                Style::Text => {
-                    res.push_str(item);
-                    Ok(())
-                }
-                Style::Emphasis => write!(res, "{}", item.yellow().bold()),
-                Style::Literal => write!(res, "{}", item.green().bold()),
-                Style::Metavar => write!(res, "{}", item.blue().bold()),
-                Style::Invalid => write!(res, "{}", item.red().bold()),
-            },
+/// When to use colors in help and error messages
+///
+/// Users can pick it with [`color_arg`](crate::OptionParser::color_arg), values are `auto`,
+/// `always` and `never`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Use colors if output goes to a terminal, respects `NO_COLOR` and `CLICOLOR_FORCE`
+    Auto,
+    /// Always use colors
+    Always,
+    /// Never use colors
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(String::from("expected auto, always or never")),
+        }
+    }
+}
+
+impl ColorChoice {
+    /// Theme to use for this choice, `theme` if colors are enabled or no colors otherwise
+    pub(crate) fn pick(self, theme: &Theme) -> &Theme {
+        let enabled = match self {
+            ColorChoice::Auto => colors_enabled(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        };
+        if enabled {
+            theme
+        } else {
+            &MONOCHROME
         }
-        .unwrap();
+    }
+}
+
+/// Width of the terminal `stdout` or `stderr` is connected to, if any
+///
+/// Asks the terminal on Linux with glibc and uses `COLUMNS` environment variable if that fails or
+/// elsewhere
+pub(crate) fn terminal_width(stderr: bool) -> Option<usize> {
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    if let Some((_, width)) = ioctl_size(if stderr { 2 } else { 1 }) {
+        return Some(width);
+    }
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    let _ = stderr;
+
+    let width = std::env::var("COLUMNS")
+        .ok()?
+        .trim()
+        .parse::<usize>()
+        .ok()?;
+    if width > 0 {
+        Some(width)
+    } else {
+        None
+    }
+}
+
+/// Number of lines in the terminal `stdout` is connected to, `None` if it's not a terminal
+///
+/// Only Linux with glibc is supported at the moment
+pub(crate) fn terminal_height() -> Option<usize> {
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    {
+        ioctl_size(1).map(|(rows, _)| rows)
+    }
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    {
+        None
+    }
+}
+
+/// Rows and columns of a terminal connected to a file descriptor
+///
+/// Declaration of `ioctl` matches glibc, other C libraries disagree on the type of `request`
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn ioctl_size(fd: std::os::raw::c_int) -> Option<(usize, usize)> {
+    use std::os::raw::{c_int, c_ulong, c_ushort};
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct WinSize {
+        row: c_ushort,
+        col: c_ushort,
+        xpixel: c_ushort,
+        ypixel: c_ushort,
+    }
+
+    #[cfg(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc64"
+    ))]
+    const TIOCGWINSZ: c_ulong = 0x4008_7468;
+    #[cfg(not(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc64"
+    )))]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+
+    extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    let mut size = WinSize::default();
+    // SAFETY: TIOCGWINSZ only writes a `winsize` struct to the pointer it gets
+    let res = unsafe { ioctl(fd, TIOCGWINSZ, &mut size as *mut WinSize) };
+    if res == 0 && size.col > 0 {
+        Some((usize::from(size.row), usize::from(size.col)))
+    } else {
+        None
+    }
+}
+
+/// Decide if output should use colors
+///
+/// `NO_COLOR` disables colors, `CLICOLOR_FORCE` enables them even when output is not a terminal,
+/// otherwise colors are used if both `stdout` and `stderr` are terminals that support them.
+pub(crate) fn colors_enabled() -> bool {
+    fn set(name: &str) -> Option<std::ffi::OsString> {
+        std::env::var_os(name).filter(|v| !v.is_empty())
+    }
+    if set("NO_COLOR").is_some() {
+        return false;
+    }
+    if set("CLICOLOR_FORCE").map_or(false, |v| v != "0") {
+        return true;
+    }
+    #[cfg(feature = "color")]
+    {
+        use supports_color::{on, Stream};
+        on(Stream::Stdout).is_some() && on(Stream::Stderr).is_some()
+    }
+    #[cfg(not(feature = "color"))]
+    {
+        false
     }
 }
 
@@ -133,11 +330,33 @@ impl Doc {
     /// difference for rendered help message, otherwise you can pass `true`.
     #[must_use]
     pub fn monochrome(&self, full: bool) -> String {
-        self.render_console(full, Color::Monochrome, MAX_WIDTH)
+        self.render_console(full, &MONOCHROME, MAX_WIDTH)
+    }
+
+    pub(crate) fn render_console(&self, full: bool, theme: &Theme, max_width: usize) -> String {
+        self.render_console_tab(full, theme, max_width, MAX_TAB)
+    }
+
+    /// Render for a narrow terminal that is `max_width` wide
+    ///
+    /// Narrow terminals can't afford a wide term column, at least two thirds are left to the text
+    pub(crate) fn render_console_narrow(
+        &self,
+        full: bool,
+        theme: &Theme,
+        max_width: usize,
+    ) -> String {
+        self.render_console_tab(full, theme, max_width, MAX_TAB.min(max_width / 3))
     }
 
     #[allow(clippy::too_many_lines)] // it's a big ass match statement
-    pub(crate) fn render_console(&self, full: bool, color: Color, max_width: usize) -> String {
+    fn render_console_tab(
+        &self,
+        full: bool,
+        theme: &Theme,
+        max_width: usize,
+        max_tab: usize,
+    ) -> String {
         let mut res = String::new();
         let mut tabstop = 0;
         let mut byte_pos = 0;
@@ -159,7 +378,7 @@ impl Doc {
                     }
                     Token::BlockEnd(Block::ItemTerm) => {
                         in_term = false;
-                        if current > tabstop && current <= MAX_TAB {
+                        if current > tabstop && current <= max_tab {
                             tabstop = current;
                         }
                     }
@@ -224,7 +443,7 @@ impl Doc {
                                     char_pos = margin;
                                     pushed = missing;
                                 }
-                                if pending_margin && char_pos >= MAX_TAB + 4 && pushed < 2 {
+                                if pending_margin && char_pos >= max_tab + 4 && pushed < 2 {
                                     let missing = 2 - pushed;
                                     res.push_str(&PADDING[..missing]);
                                     char_pos += missing;
@@ -234,16 +453,7 @@ impl Doc {
                                 pending_blank_line = false;
                                 pending_margin = false;
 
-                                #[cfg(feature = "color")]
-                                {
-                                    color.push_str(style, &mut res, s);
-                                }
-                                #[cfg(not(feature = "color"))]
-                                {
-                                    let _ = style;
-                                    let _ = color;
-                                    res.push_str(s);
-                                }
+                                theme.push_str(style, &mut res, s);
                                 char_pos += w;
                             }
                             Chunk::Paragraph => {
@@ -291,7 +501,7 @@ impl Doc {
                         }
                         Block::DefinitionList | Block::Meta | Block::Mono => {}
                         Block::TermRef => {
-                            if color == Color::Monochrome {
+                            if theme.is_monochrome() {
                                 res.push('`');
                                 char_pos += 1;
                             }
@@ -321,7 +531,7 @@ impl Doc {
                             pending_blank_line = true;
                         }
                         Block::TermRef => {
-                            if color == Color::Monochrome {
+                            if theme.is_monochrome() {
                                 res.push('`');
                                 char_pos += 1;
                             }
@@ -339,6 +549,76 @@ impl Doc {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_styles() {
+        let mut doc = Doc::default();
+        doc.text("use ");
+        doc.literal("--fast");
+        doc.text(" or ");
+        doc.invalid("--slow");
+
+        let theme = Theme::monochrome().style(Style::Literal, "1;38;5;208");
+        let r = doc.render_console(true, &theme, MAX_WIDTH);
+        assert_eq!(r, "use \x1b[1;38;5;208m--fast\x1b[0m or --slow");
+
+        let r = doc.render_console(true, &Theme::dull(), MAX_WIDTH);
+        assert_eq!(r, "use \x1b[1m--fast\x1b[0m or \x1b[1;31m--slow\x1b[0m");
+
+        assert!(Theme::dull()
+            .style(Style::Invalid, "")
+            .style(Style::Literal, "")
+            .style(Style::Emphasis, "")
+            .style(Style::Metavar, "")
+            .is_monochrome());
+    }
+
+    #[test]
+    fn narrow_terminal_shrinks_term_column() {
+        use crate::{construct, long, short, ParseFailure, Parser};
+        let a = long("output-format")
+            .help("Format of the report printed at the end of the run")
+            .argument::<String>("FMT");
+        let b = short('q').help("Be quiet").switch();
+        let parser = construct!(a, b).to_options();
+        let doc = match parser.run_inner(&["--help"]).unwrap_err() {
+            ParseFailure::Stdout(doc, _) => doc,
+            _ => unreachable!(),
+        };
+
+        // at 40 columns long names no longer define the term column
+        let r = doc.render_console_narrow(true, &MONOCHROME, 40);
+        let expected = "\
+Usage: --output-format=FMT [-q]
+
+Available options:
+        --output-format=FMT  Format of
+                the report printed at
+                the end of the run
+    -q          Be quiet
+    -h, --help  Prints help information
+";
+        assert_eq!(r, expected);
+
+        // wide terminals are not affected
+        let r = doc.render_console_narrow(true, &MONOCHROME, MAX_WIDTH);
+        assert_eq!(r, doc.render_console(true, &MONOCHROME, MAX_WIDTH));
+    }
+
+    #[test]
+    fn color_env_vars() {
+        std::env::set_var("CLICOLOR_FORCE", "1");
+        assert!(colors_enabled());
+        std::env::set_var("NO_COLOR", "1");
+        assert!(!colors_enabled());
+        std::env::remove_var("NO_COLOR");
+        std::env::remove_var("CLICOLOR_FORCE");
+    }
+}
+
 /*
 #[cfg(test)]
 mod test {
@@ -10,7 +10,6 @@ use crate::{
 use crate::{
     buffer::{extract_sections, Info, Meta},
     meta_help::render_help,
-    Parser,
 };
 
 #[inline(never)]
@@ -52,8 +51,9 @@ fn collect_html(app: String, meta: &Meta, info: &Info) -> Doc {
             &section.path,
             section.info,
             section.meta,
-            &section.info.meta(),
+            &section.info.help_meta(&info.messages),
             false,
+            &info.messages,
         );
         buf.doc(&b);
     }
@@ -64,13 +64,13 @@ impl<T> OptionParser<T> {
     /// Render command line documentation for the app into html/markdown mix
     #[cfg(feature = "docgen")]
     pub fn render_html(&self, app: impl Into<String>) -> String {
-        collect_html(app.into(), &self.inner.meta(), &self.info).render_html(true, false)
+        collect_html(app.into(), &self.documented_meta(), &self.info).render_html(true, false)
     }
 
     /// Render command line documentation for the app into Markdown
     #[cfg(feature = "docgen")]
     pub fn render_markdown(&self, app: impl Into<String>) -> String {
-        collect_html(app.into(), &self.inner.meta(), &self.info).render_markdown(true)
+        collect_html(app.into(), &self.documented_meta(), &self.info).render_markdown(true)
     }
 }
 
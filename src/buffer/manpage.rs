@@ -1,6 +1,11 @@
 use crate::{
-    buffer::{extract_sections, manpage::escape::Apostrophes, Block, HelpItems, Style, Token},
-    Doc, OptionParser, Parser,
+    buffer::{
+        extract_sections, manpage::escape::Apostrophes, Block, DocSection, HelpItem, HelpItems,
+        Style, Token,
+    },
+    locale::{Messages, Phrase},
+    meta_help::HiTy,
+    Doc, OptionParser,
 };
 
 mod escape;
@@ -58,7 +63,7 @@ impl<T> OptionParser<T> {
         application_title: Option<&str>,
     ) -> String {
         let mut sections = Vec::new();
-        let root = self.inner.meta();
+        let root = self.documented_meta();
         let mut path = vec![app.as_ref().to_string()];
 
         extract_sections(&root, &self.info, &mut path, &mut sections);
@@ -68,7 +73,7 @@ impl<T> OptionParser<T> {
         if sections.len() > 1 {
             buf.token(Token::BlockStart(Block::Block));
             buf.token(Token::BlockStart(Block::Header));
-            buf.text("SYNOPSIS");
+            buf.text(self.info.messages.get(Phrase::ManSynopsis));
             buf.token(Token::BlockEnd(Block::Header));
             buf.token(Token::BlockEnd(Block::Block));
 
@@ -102,16 +107,16 @@ impl<T> OptionParser<T> {
 
             if let Some(descr) = &section.info.descr {
                 buf.token(Token::BlockStart(Block::Header));
-                buf.text("NAME");
+                buf.text(self.info.messages.get(Phrase::ManName));
                 buf.token(Token::BlockEnd(Block::Header));
 
                 buf.text(app.as_ref());
                 buf.text(" - ");
-                buf.doc(descr);
+                buf.doc(self.info.messages.pick(descr));
             }
 
             buf.token(Token::BlockStart(Block::Header));
-            buf.text("SYNOPSIS");
+            buf.text(self.info.messages.get(Phrase::ManSynopsis));
             buf.token(Token::BlockEnd(Block::Header));
             buf.write_path(&section.path);
             buf.write_meta(section.meta, true);
@@ -124,9 +129,9 @@ impl<T> OptionParser<T> {
 
             let mut items = HelpItems::default();
             items.append_meta(section.meta);
-            let help_meta = section.info.meta();
+            let help_meta = section.info.help_meta(&self.info.messages);
             items.append_meta(&help_meta);
-            buf.write_help_item_groups(items, false);
+            buf.write_help_item_groups(items, false, &self.info.messages);
 
             if let Some(footer) = &section.info.footer {
                 buf.token(Token::BlockStart(Block::Block));
@@ -151,6 +156,172 @@ impl<T> OptionParser<T> {
 
         buf.render_roff(manpage)
     }
+
+    /// Render command line documentation for the app and every subcommand into separate manpages
+    ///
+    /// Returns pairs of file name and manpage contents. Page for the app itself is called
+    /// `app.1` (or whatever `section` is), pages for subcommands are named after the full
+    /// path to them: `app-cmd.1`, `app-cmd-subcmd.1` and so on. Each page describes a single
+    /// command, lists environment variables it uses and refers to parent and child pages.
+    /// `last_update_date`, `vendor` and `application_title` go into the header of every page, same
+    /// as with [`render_manpage`](OptionParser::render_manpage).
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # use bpaf::doc::Section;
+    /// let commit = short('m')
+    ///     .help("Commit message")
+    ///     .argument::<String>("MSG")
+    ///     .to_options()
+    ///     .descr("Record changes to the repository")
+    ///     .command("commit");
+    /// let pages = commit
+    ///     .to_options()
+    ///     .render_manpages("git", Section::General, None, None, Some("Git Manual"));
+    /// let names = pages.iter().map(|p| p.0.as_str()).collect::<Vec<_>>();
+    /// assert_eq!(names, ["git.1", "git-commit.1"]);
+    /// ```
+    pub fn render_manpages(
+        &self,
+        app: impl AsRef<str>,
+        section: Section,
+        last_update_date: Option<&str>,
+        vendor: Option<&str>,
+        application_title: Option<&str>,
+    ) -> Vec<(String, String)> {
+        let mut sections = Vec::new();
+        let root = self.documented_meta();
+        let mut path = vec![app.as_ref().to_string()];
+
+        extract_sections(&root, &self.info, &mut path, &mut sections);
+
+        let title = [
+            last_update_date.unwrap_or("-"),
+            vendor.unwrap_or("-"),
+            application_title.unwrap_or(""),
+        ];
+        sections
+            .iter()
+            .map(|page| {
+                let name = page.path.join("-");
+                let messages = &self.info.messages;
+                let roff = render_page(page, &sections, &name, section, title, messages);
+                (format!("{}.{}", name, section.as_str()), roff)
+            })
+            .collect()
+    }
+}
+
+/// Render a single command from [`render_manpages`](OptionParser::render_manpages)
+fn render_page(
+    page: &DocSection,
+    sections: &[DocSection],
+    name: &str,
+    section: Section,
+    // date, vendor and title for the page header
+    title: [&str; 3],
+    messages: &Messages,
+) -> String {
+    let mut buf = Doc::default();
+
+    // NAME
+    header(&mut buf, messages.get(Phrase::ManName));
+    buf.text(name);
+    if let Some(descr) = &page.info.descr {
+        buf.text(" - ");
+        buf.doc(messages.pick(descr));
+    }
+
+    // SYNOPSIS
+    header(&mut buf, messages.get(Phrase::ManSynopsis));
+    buf.write_path(&page.path);
+    buf.write_meta(page.meta, true);
+
+    if let Some(t) = &page.info.header {
+        buf.token(Token::BlockStart(Block::Block));
+        buf.doc(t);
+        buf.token(Token::BlockEnd(Block::Block));
+    }
+
+    let mut items = HelpItems::default();
+    items.append_meta(page.meta);
+    let help_meta = page.info.help_meta(messages);
+    items.append_meta(&help_meta);
+
+    // ENVIRONMENT, collected before items are consumed by the OPTIONS section
+    let mut envs: Vec<(&str, Option<&Doc>)> = Vec::new();
+    for item in &items.items {
+        if let HelpItem::Flag { env, help, .. } | HelpItem::Argument { env, help, .. } = item {
+            if let Some(env) = env {
+                if envs.iter().all(|e| e.0 != *env) {
+                    envs.push((env, *help));
+                }
+            }
+        }
+    }
+
+    // OPTIONS, items are listed right in the section, without subsections `--help` uses
+    header(&mut buf, messages.get(Phrase::ManOptions));
+    buf.write_custom_groups(&mut items, false, messages);
+    buf.write_help_items(&items, HiTy::Positional, None, false, messages);
+    buf.write_help_items(&items, HiTy::Flag, None, false, messages);
+
+    // COMMANDS
+    if items.items_of_ty(HiTy::Command).next().is_some() {
+        header(&mut buf, messages.get(Phrase::ManCommands));
+        buf.write_help_items(&items, HiTy::Command, None, false, messages);
+    }
+
+    if let Some(footer) = &page.info.footer {
+        buf.token(Token::BlockStart(Block::Block));
+        buf.doc(footer);
+        buf.token(Token::BlockEnd(Block::Block));
+    }
+
+    if !envs.is_empty() {
+        header(&mut buf, messages.get(Phrase::ManEnvironment));
+        buf.token(Token::BlockStart(Block::DefinitionList));
+        for (env, help) in envs {
+            buf.token(Token::BlockStart(Block::ItemTerm));
+            buf.literal(env);
+            buf.token(Token::BlockEnd(Block::ItemTerm));
+            if let Some(help) = help {
+                buf.token(Token::BlockStart(Block::ItemBody));
+                buf.doc(messages.pick(help));
+                buf.token(Token::BlockEnd(Block::ItemBody));
+            }
+        }
+        buf.token(Token::BlockEnd(Block::DefinitionList));
+    }
+
+    // SEE ALSO - parent page followed by pages for immediate subcommands
+    let depth = page.path.len();
+    let related = sections.iter().filter(|other| {
+        let len = other.path.len();
+        (len + 1 == depth && page.path.starts_with(&other.path))
+            || (len == depth + 1 && other.path.starts_with(&page.path))
+    });
+    for (ix, other) in related.enumerate() {
+        if ix == 0 {
+            header(&mut buf, messages.get(Phrase::ManSeeAlso));
+        } else {
+            buf.text(", ");
+        }
+        buf.literal(&other.path.join("-"));
+        buf.text(&format!("({})", section.as_str()));
+    }
+
+    let mut manpage = Roff::new();
+    let [date, vendor, app_title] = title;
+    let th = [name, section.as_str(), date, vendor, app_title];
+    manpage.control("TH", th.iter().copied());
+    buf.render_roff(manpage)
+}
+
+fn header(buf: &mut Doc, title: &str) {
+    buf.token(Token::BlockStart(Block::Header));
+    buf.text(title);
+    buf.token(Token::BlockEnd(Block::Header));
 }
 
 impl From<Style> for Font {
@@ -0,0 +1,501 @@
+//! Compatibility check between two versions of a parser, see
+//! [`OptionParser::check_compatibility`]
+
+use crate::{
+    schema::{Command, Described, Kind},
+    OptionParser,
+};
+
+/// How bad a [`CliChange`] is for existing users of the command line interface
+///
+/// Levels are ordered so you can filter changes with a comparison:
+/// `change.severity() >= Severity::Breaking`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Change doesn't affect existing invocations: a new optional item or a new subcommand
+    Info,
+    /// Existing invocations still parse but might behave differently: changed environment
+    /// variable or config key
+    Warning,
+    /// Existing invocations can fail to parse: removed or renamed item, item that became
+    /// required and so on
+    Breaking,
+}
+
+/// What exactly changed between two versions of a parser, see [`CliChange`]
+///
+/// Named items are referred to by their long name if present or short name otherwise,
+/// positional items - by their metavariable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChangeKind {
+    /// Item is no longer accepted
+    ItemRemoved(String),
+    /// New item is accepted, `Breaking` if it is required
+    ItemAdded {
+        /// Item name
+        name: String,
+        /// Item must be present
+        required: bool,
+    },
+    /// Named item changed its long name but kept the short one
+    LongRenamed {
+        /// Old long name, with dashes
+        from: String,
+        /// New long name, with dashes
+        to: String,
+    },
+    /// Named item changed or lost its short name, gaining a short name is `Info`
+    ShortChanged {
+        /// Item name
+        name: String,
+        /// Old short name
+        from: Option<char>,
+        /// New short name
+        to: Option<char>,
+    },
+    /// Item changed between a flag and an argument, or between a positional and `any`
+    KindChanged(String),
+    /// Optional item became required
+    BecameRequired(String),
+    /// Required item became optional
+    BecameOptional(String),
+    /// Item that used to accept several values now accepts only one
+    NoLongerRepeated(String),
+    /// Item that used to take value from an environment variable now takes it from a different
+    /// one or none at all
+    EnvChanged {
+        /// Item name
+        name: String,
+        /// Old variable name
+        from: Option<String>,
+        /// New variable name
+        to: Option<String>,
+    },
+    /// Item that used to take value from a config source now uses a different key or none at all
+    ConfigChanged {
+        /// Item name
+        name: String,
+        /// Old key
+        from: Option<String>,
+        /// New key
+        to: Option<String>,
+    },
+    /// Set of values accepted by [`choice`](crate::parsers::NamedArg::choice) changed,
+    /// `Info` if no value was removed and the item was already restricted
+    ChoicesChanged {
+        /// Item name
+        name: String,
+        /// Old values, empty if any value was accepted
+        from: Vec<String>,
+        /// New values, empty if any value is accepted
+        to: Vec<String>,
+    },
+    /// Flag gained or lost the `--no-` form, gaining it is `Info`
+    NegatableChanged {
+        /// Item name
+        name: String,
+        /// Old flag was negatable
+        from: bool,
+        /// New flag is negatable
+        to: bool,
+    },
+    /// Argument value became optional or required, in both cases some of the existing
+    /// invocations stop parsing: `--color` without a value or `--color never` with a separate one
+    OptionalValueChanged {
+        /// Item name
+        name: String,
+        /// Old value was optional
+        from: bool,
+        /// New value is optional
+        to: bool,
+    },
+    /// Positional item started or stopped capturing everything after it as is, `Warning` when
+    /// it started
+    PassthroughChanged {
+        /// Item name
+        name: String,
+        /// Old item was a passthrough
+        from: bool,
+        /// New item is a passthrough
+        to: bool,
+    },
+    /// Subcommand is no longer accepted
+    CommandRemoved(String),
+    /// New subcommand is accepted
+    CommandAdded(String),
+}
+
+/// A single difference between two versions of a parser, see
+/// [`check_compatibility`](OptionParser::check_compatibility)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliChange {
+    /// Names of subcommands leading to the change, empty for the top level parser
+    pub path: Vec<String>,
+    /// What changed
+    pub kind: ChangeKind,
+}
+
+impl CliChange {
+    /// How bad this change is for existing users
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        match &self.kind {
+            ChangeKind::ItemAdded {
+                required: false, ..
+            }
+            | ChangeKind::ShortChanged { from: None, .. }
+            | ChangeKind::BecameOptional(_)
+            | ChangeKind::NegatableChanged { to: true, .. }
+            | ChangeKind::CommandAdded(_) => Severity::Info,
+            ChangeKind::ChoicesChanged { from, to, .. }
+                if !from.is_empty() && from.iter().all(|f| to.contains(f)) =>
+            {
+                Severity::Info
+            }
+            ChangeKind::EnvChanged { .. }
+            | ChangeKind::ConfigChanged { .. }
+            | ChangeKind::PassthroughChanged { to: true, .. } => Severity::Warning,
+            ChangeKind::ItemRemoved(_)
+            | ChangeKind::ItemAdded { required: true, .. }
+            | ChangeKind::LongRenamed { .. }
+            | ChangeKind::ShortChanged { .. }
+            | ChangeKind::KindChanged(_)
+            | ChangeKind::BecameRequired(_)
+            | ChangeKind::NoLongerRepeated(_)
+            | ChangeKind::ChoicesChanged { .. }
+            | ChangeKind::NegatableChanged { .. }
+            | ChangeKind::OptionalValueChanged { .. }
+            | ChangeKind::PassthroughChanged { .. }
+            | ChangeKind::CommandRemoved(_) => Severity::Breaking,
+        }
+    }
+}
+
+impl std::fmt::Display for CliChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn short(s: &Option<char>) -> String {
+            s.map_or_else(|| "nothing".to_owned(), |s| format!("-{}", s))
+        }
+        fn var(v: &Option<String>) -> &str {
+            v.as_deref().unwrap_or("nothing")
+        }
+        fn values(v: &[String]) -> String {
+            if v.is_empty() {
+                "any value".to_owned()
+            } else {
+                v.join(", ")
+            }
+        }
+        fn not(b: bool) -> &'static str {
+            if b {
+                ""
+            } else {
+                "not "
+            }
+        }
+        write!(f, "{:?}: ", self.severity())?;
+        for cmd in &self.path {
+            write!(f, "{} ", cmd)?;
+        }
+        match &self.kind {
+            ChangeKind::ItemRemoved(name) => write!(f, "`{}` was removed", name),
+            ChangeKind::ItemAdded { name, required } => {
+                let req = if *required { "required" } else { "optional" };
+                write!(f, "{} `{}` was added", req, name)
+            }
+            ChangeKind::LongRenamed { from, to } => write!(f, "`{}` was renamed to `{}`", from, to),
+            ChangeKind::ShortChanged { name, from, to } => {
+                write!(
+                    f,
+                    "`{}` short name changed from {} to {}",
+                    name,
+                    short(from),
+                    short(to)
+                )
+            }
+            ChangeKind::KindChanged(name) => write!(f, "`{}` changed its kind", name),
+            ChangeKind::BecameRequired(name) => write!(f, "`{}` became required", name),
+            ChangeKind::BecameOptional(name) => write!(f, "`{}` became optional", name),
+            ChangeKind::NoLongerRepeated(name) => {
+                write!(f, "`{}` no longer accepts several values", name)
+            }
+            ChangeKind::EnvChanged { name, from, to } => write!(
+                f,
+                "`{}` environment variable changed from {} to {}",
+                name,
+                var(from),
+                var(to)
+            ),
+            ChangeKind::ConfigChanged { name, from, to } => write!(
+                f,
+                "`{}` config key changed from {} to {}",
+                name,
+                var(from),
+                var(to)
+            ),
+            ChangeKind::ChoicesChanged { name, from, to } => write!(
+                f,
+                "`{}` accepted values changed from {} to {}",
+                name,
+                values(from),
+                values(to)
+            ),
+            ChangeKind::NegatableChanged { name, to, .. } => {
+                write!(f, "`{}` is {}negatable now", name, not(*to))
+            }
+            ChangeKind::OptionalValueChanged { name, to, .. } => {
+                write!(f, "`{}` value is {}optional now", name, not(*to))
+            }
+            ChangeKind::PassthroughChanged { name, to, .. } => {
+                write!(f, "`{}` is {}a passthrough now", name, not(*to))
+            }
+            ChangeKind::CommandRemoved(name) => write!(f, "command `{}` was removed", name),
+            ChangeKind::CommandAdded(name) => write!(f, "command `{}` was added", name),
+        }
+    }
+}
+
+impl Described {
+    fn is_named(&self) -> bool {
+        matches!(self.kind, Kind::Flag | Kind::Argument)
+    }
+
+    fn name(&self) -> String {
+        match (&self.long, self.short, &self.metavar) {
+            (Some(long), _, _) => format!("--{}", long),
+            (None, Some(short), _) => format!("-{}", short),
+            (None, None, Some(metavar)) => metavar.clone(),
+            (None, None, None) => String::new(),
+        }
+    }
+
+    /// Check if item in the new version is the same item as `self` in the old one
+    fn same_named(&self, new: &Described) -> bool {
+        match self.long {
+            Some(_) => self.long == new.long,
+            None => new.long.is_none() && self.short == new.short,
+        }
+    }
+}
+
+struct Changes<'a> {
+    path: &'a [String],
+    res: &'a mut Vec<CliChange>,
+}
+
+impl Changes<'_> {
+    fn push(&mut self, kind: ChangeKind) {
+        self.res.push(CliChange {
+            path: self.path.to_vec(),
+            kind,
+        });
+    }
+
+    /// Changes in properties of an item present in both versions
+    fn compare_item(&mut self, old: &Described, new: &Described) {
+        let name = old.name();
+        if old.kind != new.kind {
+            self.push(ChangeKind::KindChanged(name.clone()));
+        }
+        if old.short != new.short {
+            self.push(ChangeKind::ShortChanged {
+                name: name.clone(),
+                from: old.short,
+                to: new.short,
+            });
+        }
+        if !old.required && new.required {
+            self.push(ChangeKind::BecameRequired(name.clone()));
+        }
+        if old.required && !new.required {
+            self.push(ChangeKind::BecameOptional(name.clone()));
+        }
+        if old.repeated && !new.repeated {
+            self.push(ChangeKind::NoLongerRepeated(name.clone()));
+        }
+        if old.env != new.env {
+            self.push(ChangeKind::EnvChanged {
+                name: name.clone(),
+                from: old.env.clone(),
+                to: new.env.clone(),
+            });
+        }
+        if old.config != new.config {
+            self.push(ChangeKind::ConfigChanged {
+                name: name.clone(),
+                from: old.config.clone(),
+                to: new.config.clone(),
+            });
+        }
+        if old.choices != new.choices {
+            self.push(ChangeKind::ChoicesChanged {
+                name: name.clone(),
+                from: old.choices.clone(),
+                to: new.choices.clone(),
+            });
+        }
+        if old.negatable != new.negatable {
+            self.push(ChangeKind::NegatableChanged {
+                name: name.clone(),
+                from: old.negatable,
+                to: new.negatable,
+            });
+        }
+        if old.optional_value != new.optional_value {
+            self.push(ChangeKind::OptionalValueChanged {
+                name: name.clone(),
+                from: old.optional_value,
+                to: new.optional_value,
+            });
+        }
+        if old.passthrough != new.passthrough {
+            self.push(ChangeKind::PassthroughChanged {
+                name,
+                from: old.passthrough,
+                to: new.passthrough,
+            });
+        }
+    }
+
+    fn compare_named(&mut self, old: &Command, new: &Command) {
+        let old_items = old
+            .items
+            .iter()
+            .filter(|i| i.is_named())
+            .collect::<Vec<_>>();
+        let new_items = new
+            .items
+            .iter()
+            .filter(|i| i.is_named())
+            .collect::<Vec<_>>();
+        let mut matched = vec![false; new_items.len()];
+
+        for o in &old_items {
+            if let Some(ix) = new_items.iter().position(|n| o.same_named(n)) {
+                matched[ix] = true;
+                self.compare_item(o, new_items[ix]);
+                continue;
+            }
+
+            // same short name, different long name, and the new long name is not taken by
+            // some other item in the old version
+            let renamed = new_items.iter().position(|n| {
+                o.short.is_some()
+                    && n.short == o.short
+                    && n.long.is_some()
+                    && !old_items.iter().any(|oo| oo.long == n.long)
+            });
+            match renamed {
+                Some(ix) => {
+                    matched[ix] = true;
+                    self.push(ChangeKind::LongRenamed {
+                        from: o.name(),
+                        to: new_items[ix].name(),
+                    });
+                    let mut renamed = new_items[ix].clone();
+                    renamed.long = o.long.clone();
+                    self.compare_item(o, &renamed);
+                }
+                None => self.push(ChangeKind::ItemRemoved(o.name())),
+            }
+        }
+
+        for (n, _) in new_items.iter().zip(matched).filter(|(_, m)| !m) {
+            self.push(ChangeKind::ItemAdded {
+                name: n.name(),
+                required: n.required,
+            });
+        }
+    }
+
+    /// Positional items are matched by their position
+    fn compare_positional(&mut self, old: &Command, new: &Command) {
+        let old_items = old.items.iter().filter(|i| !i.is_named());
+        let mut new_items = new.items.iter().filter(|i| !i.is_named());
+        for o in old_items {
+            match new_items.next() {
+                Some(n) => self.compare_item(o, n),
+                None => self.push(ChangeKind::ItemRemoved(o.name())),
+            }
+        }
+        for n in new_items {
+            self.push(ChangeKind::ItemAdded {
+                name: n.name(),
+                required: n.required,
+            });
+        }
+    }
+
+    fn compare_commands(&mut self, old: &Command, new: &Command) {
+        for o in &old.commands {
+            match new.commands.iter().find(|n| n.name == o.name) {
+                Some(n) => {
+                    if o.short != n.short {
+                        self.push(ChangeKind::ShortChanged {
+                            name: o.name.clone(),
+                            from: o.short,
+                            to: n.short,
+                        });
+                    }
+                    let mut path = self.path.to_vec();
+                    path.push(o.name.clone());
+                    Changes {
+                        path: &path,
+                        res: self.res,
+                    }
+                    .compare(o, n);
+                }
+                None => self.push(ChangeKind::CommandRemoved(o.name.clone())),
+            }
+        }
+        for n in &new.commands {
+            if !old.commands.iter().any(|o| o.name == n.name) {
+                self.push(ChangeKind::CommandAdded(n.name.clone()));
+            }
+        }
+    }
+
+    fn compare(&mut self, old: &Command, new: &Command) {
+        self.compare_named(old, new);
+        self.compare_positional(old, new);
+        self.compare_commands(old, new);
+    }
+}
+
+impl<T> OptionParser<T> {
+    /// Compare this parser with a previous version of it and report the differences
+    ///
+    /// Changes are reported for the parser itself and for all the nested subcommands, each
+    /// change comes with a [`Severity`] so you can check for breaking changes in a unit test:
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let old = short('v').long("verbose").switch().to_options();
+    /// let new = short('v').long("loud").switch().to_options();
+    ///
+    /// let changes = new.check_compatibility(&old);
+    /// assert_eq!(changes.len(), 1);
+    /// assert_eq!(changes[0].severity(), Severity::Breaking);
+    /// assert_eq!(
+    ///     changes[0].to_string(),
+    ///     "Breaking: `--verbose` was renamed to `--loud`"
+    /// );
+    /// ```
+    ///
+    /// Comparison is based on the same information as
+    /// [`render_json_schema`](OptionParser::render_json_schema): named items are matched by
+    /// their names, positional items - by their position.
+    #[must_use]
+    pub fn check_compatibility<P>(&self, previous: &OptionParser<P>) -> Vec<CliChange> {
+        let old = Command::new(String::new(), &previous.inner.meta(), &previous.info);
+        let new = Command::new(String::new(), &self.inner.meta(), &self.info);
+        let mut res = Vec::new();
+        Changes {
+            path: &[],
+            res: &mut res,
+        }
+        .compare(&old, &new);
+        res
+    }
+}
@@ -14,14 +14,18 @@
 //
 // complete short names to long names if possible
 
+use std::borrow::Cow;
+
 use crate::{
     args::{Arg, State},
-    complete_shell::{render_bash, render_fish, render_simple, render_test, render_zsh},
+    complete_shell::{
+        render_bash, render_fish, render_nu, render_pwsh, render_simple, render_test, render_zsh,
+    },
     item::ShortLong,
     parsers::NamedArg,
     Doc, ShellComp,
 };
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 
 #[derive(Clone, Debug)]
 pub(crate) struct Complete {
@@ -32,6 +36,10 @@ pub(crate) struct Complete {
     /// don't try to suggest any more positional items after there's a positional item failure
     /// or parsing in progress
     pub(crate) no_pos_ahead: bool,
+
+    /// Looking for values to suggest in an error message rather than completing, only
+    /// in-process completers run, see [`State::value_probe`]
+    pub(crate) probe: bool,
 }
 
 impl Complete {
@@ -40,6 +48,7 @@ impl Complete {
             comps: Vec::new(),
             output_rev,
             no_pos_ahead: false,
+            probe: false,
         }
     }
 }
@@ -63,7 +72,7 @@ impl State {
     }
 
     /// Add a new completion hint for an argument, if needed
-    pub(crate) fn push_argument(&mut self, named: &NamedArg, metavar: &'static str) {
+    pub(crate) fn push_argument(&mut self, named: &NamedArg, metavar: Cow<'static, str>) {
         let depth = self.depth();
         if let Some(comp) = self.comp_mut() {
             if let Ok(name) = ShortLong::try_from(named) {
@@ -86,7 +95,7 @@ impl State {
     /// when meta
     pub(crate) fn push_metavar(
         &mut self,
-        meta: &'static str,
+        meta: Cow<'static, str>,
         help: &Option<Doc>,
         is_argument: bool,
     ) {
@@ -106,10 +115,53 @@ impl State {
         }
     }
 
+    /// Add completion hints for values of an argument with a fixed set of values
+    pub(crate) fn push_choices(
+        &mut self,
+        choices: &[Cow<'static, str>],
+        prefix: &OsStr,
+        meta: Cow<'static, str>,
+        help: &Option<Doc>,
+        is_argument: bool,
+    ) {
+        let prefix = prefix.to_string_lossy();
+        let matching = choices
+            .iter()
+            .filter(|c| c.starts_with(prefix.as_ref()))
+            .collect::<Vec<_>>();
+        // same as with `complete` - metavar is dropped when there's a single good suggestion
+        if matching.len() != 1 {
+            self.push_metavar(meta, help, is_argument);
+        }
+        let depth = self.depth();
+        if let Some(comp) = self.comp_mut() {
+            for choice in matching {
+                comp.push_value(choice.to_string(), None, None, depth, is_argument);
+            }
+        }
+    }
+
+    /// Values offered for the item being completed, ignoring everything else
+    pub(crate) fn value_candidates(&self) -> Vec<String> {
+        let comps = match self.comp_ref() {
+            Some(comp) => &comp.comps,
+            None => return Vec::new(),
+        };
+        let max_depth = comps.iter().map(Comp::depth).max().unwrap_or(0);
+        comps
+            .iter()
+            .filter(|c| c.depth() == max_depth)
+            .filter_map(|c| match c {
+                Comp::Value { body, .. } => Some(body.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Add a new completion hint for command, if needed
     pub(crate) fn push_command(
         &mut self,
-        name: &'static str,
+        name: Cow<'static, str>,
         short: Option<char>,
         help: &Option<Doc>,
     ) {
@@ -196,6 +248,59 @@ impl Complete {
         self.comps.push(comp);
     }
 
+    /// Ask `program` to complete `words` and add candidates it produces
+    ///
+    /// Candidates are exchanged in revision 0 format and rendered for the shell together with
+    /// everything else, shell completions requested by `program` are not passed through
+    pub(crate) fn push_delegated(&mut self, program: &OsStr, words: &[OsString], depth: usize) {
+        let output = match std::process::Command::new(program)
+            .arg("--bpaf-complete-rev=0")
+            .args(words)
+            .stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .output()
+        {
+            Ok(output) => output,
+            Err(_) => return,
+        };
+        let output = String::from_utf8_lossy(&output.stdout);
+
+        // a single candidate is printed as is, without a newline
+        if !output.is_empty() && !output.contains('\n') {
+            self.push_value(output.into_owned(), None, None, depth, false);
+            return;
+        }
+
+        // otherwise it's a table of candidates followed by an empty line, a line without
+        // tabs means there's nothing to suggest
+        for line in output.lines().take_while(|l| !l.is_empty()) {
+            let mut fields = line.split('\t');
+            let (subst, pretty, group, help) =
+                match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                    (Some(s), Some(p), Some(g), Some(h)) => (s, p, g, h),
+                    _ => continue,
+                };
+            let extra = CompExtra {
+                depth,
+                group: Some(group).filter(|g| !g.is_empty()).map(str::to_owned),
+                help: Some(help).filter(|h| !h.is_empty()).map(str::to_owned),
+            };
+            self.comps.push(if subst.is_empty() {
+                Comp::Metavariable {
+                    extra,
+                    meta: Cow::Owned(pretty.to_owned()),
+                    is_argument: false,
+                }
+            } else {
+                Comp::Value {
+                    extra,
+                    body: subst.to_owned(),
+                    is_argument: false,
+                }
+            });
+        }
+    }
+
     pub(crate) fn extend_comps(&mut self, comps: Vec<Comp>) {
         self.comps.extend(comps);
     }
@@ -233,13 +338,13 @@ pub(crate) enum Comp {
     Argument {
         extra: CompExtra,
         name: ShortLong,
-        metavar: &'static str,
+        metavar: Cow<'static, str>,
     },
 
     ///
     Command {
         extra: CompExtra,
-        name: &'static str,
+        name: Cow<'static, str>,
         short: Option<char>,
     },
 
@@ -255,7 +360,7 @@ pub(crate) enum Comp {
 
     Metavariable {
         extra: CompExtra,
-        meta: &'static str,
+        meta: Cow<'static, str>,
         is_argument: bool,
     },
 
@@ -403,6 +508,8 @@ impl State {
             7 => render_zsh(&items, &shell, full_lit),
             8 => render_bash(&items, &shell, full_lit),
             9 => render_fish(&items, &shell, full_lit, self.path[0].as_str()),
+            10 => render_nu(&items, &shell, full_lit),
+            11 => render_pwsh(&items, &shell, full_lit),
             unk => {
                 #[cfg(debug_assertions)]
                 {
@@ -419,7 +526,7 @@ impl State {
 }
 
 /// Try to expand short string names into long names if possible
-fn preferred_name(name: ShortLong) -> String {
+fn preferred_name(name: &ShortLong) -> String {
     match name {
         ShortLong::Short(s) => format!("-{}", s),
         ShortLong::Long(l) | ShortLong::Both(_, l) => format!("--{}", l),
@@ -427,7 +534,7 @@ fn preferred_name(name: ShortLong) -> String {
 }
 
 // check if argument can possibly match the argument passed in and returns a preferrable replacement
-fn arg_matches(arg: &str, name: ShortLong) -> Option<String> {
+fn arg_matches(arg: &str, name: &ShortLong) -> Option<String> {
     // "" and "-" match any flag
     if arg.is_empty() || arg == "-" {
         return Some(preferred_name(name));
@@ -442,7 +549,7 @@ fn arg_matches(arg: &str, name: ShortLong) -> Option<String> {
         ShortLong::Short(s) | ShortLong::Both(s, _) => {
             can_match |= arg
                 .strip_prefix('-')
-                .and_then(|a| a.strip_prefix(s))
+                .and_then(|a| a.strip_prefix(*s))
                 .map_or(false, str::is_empty);
         }
     }
@@ -461,7 +568,7 @@ fn arg_matches(arg: &str, name: ShortLong) -> Option<String> {
         None
     }
 }
-fn cmd_matches(arg: &str, name: &'static str, short: Option<char>) -> Option<&'static str> {
+fn cmd_matches<'a>(arg: &str, name: &'a str, short: Option<char>) -> Option<&'a str> {
     // partial long name and exact short name match anything
     if name.starts_with(arg)
         || short.map_or(false, |s| {
@@ -533,7 +640,7 @@ impl Complete {
                 }
 
                 Comp::Flag { name, extra } => {
-                    if let Some(long) = arg_matches(arg, *name) {
+                    if let Some(long) = arg_matches(arg, name) {
                         items.push(ShowComp {
                             pretty: long.clone(),
                             subst: long,
@@ -547,7 +654,7 @@ impl Complete {
                     metavar,
                     extra,
                 } => {
-                    if let Some(long) = arg_matches(arg, *name) {
+                    if let Some(long) = arg_matches(arg, name) {
                         items.push(ShowComp {
                             pretty: format!("{}={}", long, metavar),
                             subst: long,
@@ -582,7 +689,7 @@ impl Complete {
                     }
                     items.push(ShowComp {
                         subst: String::new(),
-                        pretty: (*meta).to_string(),
+                        pretty: meta.to_string(),
                         extra,
                     });
                 }
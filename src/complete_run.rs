@@ -65,6 +65,43 @@ set edit:completion:arg-completer[{name}] = {{ |@args| var args = $args[1..];
     );
 }
 
+// nushell supports only one external completer so this one wraps whatever was configured
+// before and passes the control to it for other commands
+fn dump_nu_completer(name: &str) {
+    println!(
+        r#"$env.config.completions.external.enable = true
+$env.config.completions.external.completer = do {{|previous|
+    {{|spans|
+        if ($spans | first) == "{name}" {{
+            ^{name} --bpaf-complete-rev={rev} ...($spans | skip 1) | from json
+        }} else if $previous != null {{
+            do $previous $spans
+        }}
+    }}
+}} $env.config.completions.external.completer"#,
+        name = name,
+        rev = 10,
+    );
+}
+
+fn dump_pwsh_completer(name: &str) {
+    println!(
+        r#"Register-ArgumentCompleter -Native -CommandName {name} -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $words = @($commandAst.CommandElements |
+        Where-Object {{ $_.Extent.StartOffset -lt $cursorPosition }} |
+        Select-Object -Skip 1 |
+        ForEach-Object {{ $_.Extent.Text }})
+    if ($wordToComplete -eq '') {{
+        $words += ''
+    }}
+    & {name} --bpaf-complete-rev={rev} @words | Out-String | Invoke-Expression
+}}"#,
+        name = name,
+        rev = 11,
+    );
+}
+
 #[derive(Debug)]
 pub(crate) struct ArgScanner<'a> {
     pub(crate) revision: Option<usize>,
@@ -85,6 +122,8 @@ impl ArgScanner<'_> {
                 "--bpaf-complete-style-bash" => dump_bash_completer(name),
                 "--bpaf-complete-style-fish" => dump_fish_completer(name),
                 "--bpaf-complete-style-elvish" => dump_elvish_completer(name),
+                "--bpaf-complete-style-nu" => dump_nu_completer(name),
+                "--bpaf-complete-style-pwsh" => dump_pwsh_completer(name),
                 _ => {
                     matched = false;
                 }
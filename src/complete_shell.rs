@@ -1,6 +1,6 @@
-use crate::{complete_gen::ShowComp, Error, Meta, Parser, State};
+use crate::{complete_gen::ShowComp, meta::CompHint, Error, Meta, Parser, State};
 
-struct Shell<'a>(&'a str);
+pub(crate) struct Shell<'a>(pub(crate) &'a str);
 
 impl std::fmt::Display for Shell<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -18,6 +18,45 @@ impl std::fmt::Display for Shell<'_> {
     }
 }
 
+struct Pwsh<'a>(&'a str);
+
+impl std::fmt::Display for Pwsh<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use std::fmt::Write;
+        f.write_char('\'')?;
+        for c in self.0.chars() {
+            if c == '\'' {
+                f.write_str("''")
+            } else {
+                f.write_char(c)
+            }?
+        }
+        f.write_char('\'')?;
+        Ok(())
+    }
+}
+
+struct Json<'a>(&'a str);
+
+impl std::fmt::Display for Json<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use std::fmt::Write;
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\""),
+                '\\' => f.write_str("\\\\"),
+                '\n' => f.write_str("\\n"),
+                '\t' => f.write_str("\\t"),
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32),
+                c => f.write_char(c),
+            }?;
+        }
+        f.write_char('"')?;
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 /// Shell specific completion
 #[non_exhaustive]
@@ -59,6 +98,16 @@ pub enum ShellComp {
         /// This raw string will be used for `elvish` shell
         /// <https://elv.sh/ref/edit.html#completion-api>
         elvish: &'static str,
+
+        /// This raw string will be used for `nu` shell, it should contain comma separated
+        /// completion records in JSON format, such as `{"value": "foo", "description": "bar"}`
+        /// <https://www.nushell.sh/cookbook/external_completers.html>
+        nu: &'static str,
+
+        /// This raw string will be used for `pwsh` shell, it should produce
+        /// `CompletionResult` values, current word is available as `$wordToComplete`
+        /// <https://learn.microsoft.com/en-us/powershell/module/microsoft.powershell.core/register-argumentcompleter>
+        pwsh: &'static str,
     },
 
     /// Don't produce anything at all from this parser - can be useful if you want to compose
@@ -107,7 +156,57 @@ where
     }
 
     fn meta(&self) -> Meta {
-        self.inner.meta()
+        Meta::Completion(
+            Box::new(self.inner.meta()),
+            Box::new(CompHint::Shell(self.op)),
+        )
+    }
+}
+
+/// Parser that delegates completion of everything it consumes to another program
+#[cfg(feature = "autocomplete")]
+pub struct ParseCompDelegate<P> {
+    pub(crate) inner: P,
+    pub(crate) program: std::ffi::OsString,
+}
+
+#[cfg(feature = "autocomplete")]
+impl<P, T> Parser<T> for ParseCompDelegate<P>
+where
+    P: Parser<T> + Sized,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        // nested program is not asked for values to suggest in error messages
+        if !args.is_comp() || args.is_probe() {
+            return self.inner.eval(args);
+        }
+
+        let before = args.clone();
+        let mut comp_items = Vec::new();
+        args.swap_comps_with(&mut comp_items);
+
+        let res = self.inner.eval(args);
+
+        args.swap_comps_with(&mut comp_items);
+
+        // delegate only if the word being completed belongs to the inner parser,
+        // otherwise keep whatever inner parser suggested
+        let taken = args.taken_since(&before);
+        let depth = args.depth();
+        let last = args.items.len().checked_sub(1);
+        if last.is_some() && taken.last().copied() == last {
+            let words = args.words(&taken);
+            if let Some(comp) = args.comp_mut() {
+                comp.push_delegated(&self.program, &words, depth);
+            }
+        } else if let Some(comp) = args.comp_mut() {
+            comp.extend_comps(comp_items);
+        }
+        res
+    }
+
+    fn meta(&self) -> Meta {
+        Meta::Completion(Box::new(self.inner.meta()), Box::new(CompHint::Dynamic))
     }
 }
 
@@ -292,6 +391,111 @@ pub(crate) fn render_fish(
     Ok(res)
 }
 
+pub(crate) fn render_nu(
+    items: &[ShowComp],
+    ops: &[ShellComp],
+    full_lit: &str,
+) -> Result<String, std::fmt::Error> {
+    use std::fmt::Write;
+
+    if items.is_empty() && ops.is_empty() {
+        return Ok(format!("[{{\"value\": {}}}]\n", Json(full_lit)));
+    }
+
+    let mut records = Vec::new();
+    let mut files = false;
+    for op in ops {
+        match op {
+            // nushell can't complete files from an external completer, but it falls back to
+            // file completion if completer returns nothing. Masks are not supported.
+            ShellComp::File { .. } | ShellComp::Dir { .. } => files = true,
+            ShellComp::Raw { nu, .. } if !nu.is_empty() => records.push((*nu).to_owned()),
+            ShellComp::Raw { .. } | ShellComp::Nothing => {}
+        }
+    }
+
+    for item in items.iter().filter(|i| !i.subst.is_empty()) {
+        let mut record = format!("{{\"value\": {}", Json(&item.subst));
+        if let Some(help) = item.extra.help.as_deref() {
+            let help = help.split('\n').next().unwrap_or("");
+            write!(record, ", \"description\": {}", Json(help))?;
+        }
+        record.push('}');
+        records.push(record);
+    }
+
+    if records.is_empty() && files {
+        return Ok("null\n".to_owned());
+    }
+    Ok(format!("[{}]\n", records.join(", ")))
+}
+
+pub(crate) fn render_pwsh(
+    items: &[ShowComp],
+    ops: &[ShellComp],
+    full_lit: &str,
+) -> Result<String, std::fmt::Error> {
+    use std::fmt::Write;
+    const RESULT: &str = "[System.Management.Automation.CompletionResult]::new";
+    const FILES: &str =
+        "[System.Management.Automation.CompletionCompleters]::CompleteFilename($wordToComplete)";
+    const IS_DIR: &str = "$_.ResultType -eq 'ProviderContainer'";
+
+    if items.is_empty() && ops.is_empty() {
+        return Ok(format!("{}({})\n", RESULT, Pwsh(full_lit)));
+    }
+
+    let mut res = String::new();
+    for op in ops {
+        match op {
+            ShellComp::File { mask: None } => writeln!(res, "{}", FILES),
+            ShellComp::File { mask: Some(mask) } => writeln!(
+                res,
+                "{} | Where-Object {{ {} -or $_.ListItemText -like {} }}",
+                FILES,
+                IS_DIR,
+                Pwsh(mask)
+            ),
+            ShellComp::Dir { mask: None } => {
+                writeln!(res, "{} | Where-Object {{ {} }}", FILES, IS_DIR)
+            }
+            ShellComp::Dir { mask: Some(mask) } => writeln!(
+                res,
+                "{} | Where-Object {{ {} -and $_.ListItemText -like {} }}",
+                FILES,
+                IS_DIR,
+                Pwsh(mask)
+            ),
+            ShellComp::Raw { pwsh, .. } => writeln!(res, "{}", pwsh),
+            ShellComp::Nothing => Ok(()),
+        }?;
+    }
+
+    for item in items.iter().filter(|i| !i.subst.is_empty()) {
+        let kind = if item.subst.starts_with('-') {
+            "ParameterName"
+        } else {
+            "ParameterValue"
+        };
+        // tooltip must not be empty
+        let tooltip = match item.extra.help.as_deref() {
+            Some(help) if !help.is_empty() => help,
+            _ => item.pretty.as_str(),
+        };
+        writeln!(
+            res,
+            "{}({}, {}, '{}', {})",
+            RESULT,
+            Pwsh(&item.subst),
+            Pwsh(&item.pretty),
+            kind,
+            Pwsh(tooltip),
+        )?;
+    }
+
+    Ok(res)
+}
+
 pub(crate) fn render_simple(items: &[ShowComp]) -> Result<String, std::fmt::Error> {
     use std::fmt::Write;
     let mut res = String::new();
@@ -0,0 +1,524 @@
+//! Static shell completion scripts, see [`OptionParser::render_static_completion`]
+//!
+//! Unlike dynamic completion static scripts contain all the flags, arguments and commands
+//! collected from [`Meta`] and don't need to run the app on every keypress. Only items
+//! decorated with [`complete`](crate::Parser::complete) still call the app.
+
+use std::{borrow::Cow, fmt::Write};
+
+use crate::{
+    complete_shell::Shell as Quote, item::ShortLong, locale::Messages, meta::CompHint, Doc, Meta,
+    OptionParser, ShellComp,
+};
+
+#[cfg(doc)]
+use crate::Parser;
+
+/// Shell to render static completion script for, see [`OptionParser::render_static_completion`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompletionShell {
+    /// `bash`, script can be sourced from `~/.bash_completion`
+    Bash,
+    /// `zsh`, script should be placed into a file named `_app` somewhere in `$fpath`
+    Zsh,
+    /// `fish`, script should be placed into `~/.config/fish/completions/app.fish`
+    Fish,
+}
+
+/// Named item available in some command context
+struct Opt {
+    short: Option<char>,
+    long: Option<Cow<'static, str>>,
+    help: String,
+    /// `None` for flags, `Some` for arguments, with an optional hint for the value
+    value: Option<Option<CompHint>>,
+}
+
+/// Subcommand available in some command context
+struct Cmd {
+    name: Cow<'static, str>,
+    short: Option<char>,
+    help: String,
+}
+
+/// Everything that can be completed for one command path
+#[derive(Default)]
+struct Scope {
+    /// Command path separated with `/`, empty for the top level
+    ctx: String,
+    opts: Vec<Opt>,
+    cmds: Vec<Cmd>,
+    /// Hint for the first positional item that has one
+    positional: Option<CompHint>,
+}
+
+fn help_line(help: &Option<Doc>) -> String {
+    match help {
+        Some(help) => help
+            .monochrome(false)
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_owned(),
+        None => String::new(),
+    }
+}
+
+fn collect(
+    meta: &Meta,
+    hint: Option<CompHint>,
+    scope: &mut Scope,
+    scopes: &mut Vec<Scope>,
+    messages: &Messages,
+) {
+    use crate::item::Item;
+    match meta {
+        Meta::And(xs) | Meta::Or(xs) => {
+            for x in xs {
+                collect(x, hint, scope, scopes, messages);
+            }
+        }
+        Meta::Optional(m)
+        | Meta::Required(m)
+        | Meta::Adjacent(m)
+        | Meta::Many(m)
+        | Meta::Subsection(m, _)
+        | Meta::Suffix(m, _, _)
+        | Meta::CustomUsage(m, _)
+        | Meta::Strict(m) => collect(m, hint, scope, scopes, messages),
+        Meta::Completion(m, hint) => collect(m, Some(**hint), scope, scopes, messages),
+        Meta::Skip | Meta::Hidden(_) => {}
+        Meta::Item(item) => match item.as_ref() {
+            Item::Positional { .. } | Item::Any { .. } => {
+                if scope.positional.is_none() {
+                    scope.positional = hint;
+                }
+            }
+            Item::Flag { name, help, .. } | Item::Argument { name, help, .. } => {
+                let (short, long) = match name {
+                    ShortLong::Short(s) => (Some(*s), None),
+                    ShortLong::Long(l) => (None, Some(l.clone())),
+                    ShortLong::Both(s, l) => (Some(*s), Some(l.clone())),
+                };
+                let value = match item.as_ref() {
+                    // value can only be attached: `--color=never`
+                    Item::Argument {
+                        optional_value: true,
+                        ..
+                    } => None,
+                    Item::Argument { .. } => Some(hint),
+                    _ => None,
+                };
+                let negated = match item.as_ref() {
+                    Item::Flag {
+                        negatable: true, ..
+                    } => long.as_ref().map(|long| format!("no-{}", long)),
+                    _ => None,
+                };
+                scope.opts.push(Opt {
+                    short,
+                    long,
+                    help: help_line(help),
+                    value,
+                });
+                if let Some(negated) = negated {
+                    scope.opts.push(Opt {
+                        short: None,
+                        long: Some(Cow::Owned(negated)),
+                        help: help_line(help),
+                        value: None,
+                    });
+                }
+            }
+            Item::Command {
+                name,
+                short,
+                help,
+                meta,
+                info,
+                lazy_help: _,
+            } => {
+                scope.cmds.push(Cmd {
+                    name: name.clone(),
+                    short: *short,
+                    help: help_line(help),
+                });
+                let mut inner = Scope {
+                    ctx: format!("{}/{}", scope.ctx, name),
+                    ..Scope::default()
+                };
+                collect(meta, None, &mut inner, scopes, messages);
+                collect(
+                    &info.help_meta(messages),
+                    None,
+                    &mut inner,
+                    scopes,
+                    messages,
+                );
+                scopes.push(inner);
+            }
+        },
+    }
+}
+
+/// Names for an option, `-o` and `--output`
+fn names(opt: &Opt) -> Vec<String> {
+    let mut res = Vec::new();
+    if let Some(l) = &opt.long {
+        res.push(format!("--{}", l));
+    }
+    if let Some(s) = opt.short {
+        res.push(format!("-{}", s));
+    }
+    res
+}
+
+/// `case` pattern for switching command context in bash and zsh
+fn ctx_switches(res: &mut String, scopes: &[Scope]) -> std::fmt::Result {
+    for scope in scopes {
+        for cmd in &scope.cmds {
+            let target = format!("{}/{}", scope.ctx, cmd.name);
+            write!(res, "            {}", Quote(&target))?;
+            if let Some(s) = cmd.short {
+                write!(res, " | {}", Quote(&format!("{}/{}", scope.ctx, s)))?;
+            }
+            writeln!(res, ") ctx={} ;;", Quote(&target))?;
+        }
+    }
+    Ok(())
+}
+
+/// `case` patterns for `"$ctx $prev"` that match arguments expecting a value
+fn arg_patterns(scope: &Scope, opt: &Opt) -> String {
+    names(opt)
+        .iter()
+        .map(|n| Quote(&format!("{} {}", scope.ctx, n)).to_string())
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Dynamic completion also takes care of the flags so there's nothing else left to do
+fn dynamic_return(res: &mut String, hint: CompHint) -> std::fmt::Result {
+    match hint {
+        CompHint::Dynamic => writeln!(res, "                return"),
+        CompHint::Shell(_) => Ok(()),
+    }
+}
+
+fn bash_hint(res: &mut String, hint: CompHint, indent: &str) -> std::fmt::Result {
+    match hint {
+        CompHint::Dynamic => {
+            writeln!(
+                res,
+                "{i}local line=\"${{COMP_WORDS[0]}} --bpaf-complete-rev=8 ${{COMP_WORDS[@]:1}}\"
+{i}if [[ ${{COMP_WORDS[-1]}} == \"\" ]]; then
+{i}    line=\"${{line}} \\\"\\\"\"
+{i}fi
+{i}source <( eval ${{line}} )",
+                i = indent
+            )
+        }
+        CompHint::Shell(op) => match op {
+            ShellComp::File { mask: None } => {
+                writeln!(res, "{}COMPREPLY+=( $(compgen -f -- \"$cur\") )", indent)
+            }
+            ShellComp::File { mask: Some(mask) } => writeln!(
+                res,
+                "{}COMPREPLY+=( $(compgen -d -- \"$cur\") $(compgen -f -X {} -- \"$cur\") )",
+                indent,
+                Quote(&format!("!{}", mask))
+            ),
+            ShellComp::Dir { mask: None } => {
+                writeln!(res, "{}COMPREPLY+=( $(compgen -d -- \"$cur\") )", indent)
+            }
+            ShellComp::Dir { mask: Some(mask) } => writeln!(
+                res,
+                "{}COMPREPLY+=( $(compgen -d -X {} -- \"$cur\") )",
+                indent,
+                Quote(&format!("!{}", mask))
+            ),
+            ShellComp::Raw { bash, .. } => writeln!(res, "{}{}", indent, bash),
+            ShellComp::Nothing => Ok(()),
+        },
+    }
+}
+
+fn render_bash(app: &str, fname: &str, scopes: &[Scope]) -> Result<String, std::fmt::Error> {
+    let mut res = String::new();
+    writeln!(res, "_bpaf_static_{}() {{", fname)?;
+    writeln!(res, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"")?;
+    writeln!(res, "    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"")?;
+    writeln!(res, "    local ctx='' i")?;
+    writeln!(res, "    for ((i = 1; i < COMP_CWORD; i++)); do")?;
+    writeln!(res, "        case \"$ctx/${{COMP_WORDS[i]}}\" in")?;
+    ctx_switches(&mut res, scopes)?;
+    writeln!(res, "        esac")?;
+    writeln!(res, "    done")?;
+
+    writeln!(res, "    case \"$ctx $prev\" in")?;
+    for scope in scopes {
+        for opt in &scope.opts {
+            if let Some(hint) = opt.value {
+                writeln!(res, "        {})", arg_patterns(scope, opt))?;
+                if let Some(hint) = hint {
+                    bash_hint(&mut res, hint, "            ")?;
+                }
+                writeln!(res, "            return ;;")?;
+            }
+        }
+    }
+    writeln!(res, "    esac")?;
+
+    writeln!(res, "    local words=''")?;
+    writeln!(res, "    case \"$ctx\" in")?;
+    for scope in scopes {
+        let mut words = scope.opts.iter().flat_map(names).collect::<Vec<_>>();
+        words.extend(scope.cmds.iter().map(|c| c.name.to_string()));
+        writeln!(res, "        {})", Quote(&scope.ctx))?;
+        writeln!(res, "            words={}", Quote(&words.join(" ")))?;
+        if let Some(hint) = scope.positional {
+            writeln!(res, "            if [[ $cur != -* ]]; then")?;
+            bash_hint(&mut res, hint, "                ")?;
+            dynamic_return(&mut res, hint)?;
+            writeln!(res, "            fi")?;
+        }
+        writeln!(res, "            ;;")?;
+    }
+    writeln!(res, "    esac")?;
+    writeln!(
+        res,
+        "    COMPREPLY+=( $(compgen -W \"$words\" -- \"$cur\") )"
+    )?;
+    writeln!(res, "}}")?;
+    writeln!(res, "complete -o nosort -F _bpaf_static_{} {}", fname, app)?;
+    Ok(res)
+}
+
+fn zsh_hint(res: &mut String, hint: CompHint, indent: &str) -> std::fmt::Result {
+    match hint {
+        CompHint::Dynamic => {
+            writeln!(
+                res,
+                "{i}local line=\"${{words[1]}} --bpaf-complete-rev=7 ${{words[@]:1}}\"
+{i}if [[ ${{words[-1]}} == \"\" ]]; then
+{i}    line=\"${{line}} \\\"\\\"\"
+{i}fi
+{i}source <(eval ${{line}})",
+                i = indent
+            )
+        }
+        CompHint::Shell(op) => match op {
+            ShellComp::File { mask: None } => writeln!(res, "{}_files", indent),
+            ShellComp::File { mask: Some(mask) } => {
+                writeln!(res, "{}_files -g {}", indent, Quote(mask))
+            }
+            ShellComp::Dir { mask: None } => writeln!(res, "{}_files -/", indent),
+            ShellComp::Dir { mask: Some(mask) } => {
+                writeln!(res, "{}_files -/ -g {}", indent, Quote(mask))
+            }
+            ShellComp::Raw { zsh, .. } => writeln!(res, "{}{}", indent, zsh),
+            ShellComp::Nothing => Ok(()),
+        },
+    }
+}
+
+/// `name:description` entry for zsh `_describe`
+fn zsh_item(name: &str, help: &str) -> String {
+    let name = name.replace(':', "\\:");
+    if help.is_empty() {
+        name
+    } else {
+        format!("{}:{}", name, help)
+    }
+}
+
+fn render_zsh(app: &str, fname: &str, scopes: &[Scope]) -> Result<String, std::fmt::Error> {
+    let mut res = String::new();
+    writeln!(res, "#compdef {}", app)?;
+    writeln!(res)?;
+    writeln!(res, "_bpaf_static_{}() {{", fname)?;
+    writeln!(res, "    local ctx='' prev=\"${{words[CURRENT-1]}}\" i")?;
+    writeln!(res, "    for ((i = 2; i < CURRENT; i++)); do")?;
+    writeln!(res, "        case \"$ctx/${{words[i]}}\" in")?;
+    ctx_switches(&mut res, scopes)?;
+    writeln!(res, "        esac")?;
+    writeln!(res, "    done")?;
+
+    writeln!(res, "    case \"$ctx $prev\" in")?;
+    for scope in scopes {
+        for opt in &scope.opts {
+            if let Some(hint) = opt.value {
+                writeln!(res, "        {})", arg_patterns(scope, opt))?;
+                if let Some(hint) = hint {
+                    zsh_hint(&mut res, hint, "            ")?;
+                }
+                writeln!(res, "            return ;;")?;
+            }
+        }
+    }
+    writeln!(res, "    esac")?;
+
+    writeln!(res, "    local -a items")?;
+    writeln!(res, "    case \"$ctx\" in")?;
+    for scope in scopes {
+        let mut items = Vec::new();
+        for opt in &scope.opts {
+            for name in names(opt) {
+                items.push(Quote(&zsh_item(&name, &opt.help)).to_string());
+            }
+        }
+        for cmd in &scope.cmds {
+            items.push(Quote(&zsh_item(&cmd.name, &cmd.help)).to_string());
+        }
+        writeln!(res, "        {})", Quote(&scope.ctx))?;
+        writeln!(res, "            items=({})", items.join(" "))?;
+        if let Some(hint) = scope.positional {
+            writeln!(res, "            if [[ ${{words[CURRENT]}} != -* ]]; then")?;
+            zsh_hint(&mut res, hint, "                ")?;
+            dynamic_return(&mut res, hint)?;
+            writeln!(res, "            fi")?;
+        }
+        writeln!(res, "            ;;")?;
+    }
+    writeln!(res, "    esac")?;
+    writeln!(res, "    _describe -V {} items", Quote(app))?;
+    writeln!(res, "}}")?;
+    writeln!(res)?;
+    writeln!(res, "_bpaf_static_{} \"$@\"", fname)?;
+    Ok(res)
+}
+
+fn fish_hint(res: &mut String, hint: CompHint, app: &str) -> std::fmt::Result {
+    match hint {
+        CompHint::Dynamic => write!(
+            res,
+            " -a '({} --bpaf-complete-rev=1 (commandline -opc)[2..-1] (commandline -ct))'",
+            app
+        ),
+        CompHint::Shell(op) => match op {
+            ShellComp::File { mask: None } => write!(res, " -F"),
+            ShellComp::File { mask: Some(mask) } => match mask.strip_prefix('*') {
+                Some(suffix) if suffix.starts_with('.') => {
+                    write!(res, " -a '(__fish_complete_suffix {})'", suffix)
+                }
+                _ => write!(res, " -F"),
+            },
+            ShellComp::Dir { .. } => write!(res, " -a '(__fish_complete_directories)'"),
+            ShellComp::Raw { fish, .. } => write!(res, " -a {}", Quote(fish)),
+            ShellComp::Nothing => Ok(()),
+        },
+    }
+}
+
+fn render_fish(app: &str, fname: &str, scopes: &[Scope]) -> Result<String, std::fmt::Error> {
+    let mut res = String::new();
+    writeln!(res, "function __bpaf_static_{}_ctx", fname)?;
+    writeln!(res, "    set -l ctx ''")?;
+    writeln!(res, "    for word in (commandline -opc)[2..-1]")?;
+    writeln!(res, "        switch \"$ctx/$word\"")?;
+    for scope in scopes {
+        for cmd in &scope.cmds {
+            let target = format!("{}/{}", scope.ctx, cmd.name);
+            write!(res, "            case {}", Quote(&target))?;
+            if let Some(s) = cmd.short {
+                write!(res, " {}", Quote(&format!("{}/{}", scope.ctx, s)))?;
+            }
+            writeln!(res)?;
+            writeln!(res, "                set ctx {}", Quote(&target))?;
+        }
+    }
+    writeln!(res, "        end")?;
+    writeln!(res, "    end")?;
+    writeln!(res, "    test \"$ctx\" = \"$argv[1]\"")?;
+    writeln!(res, "end")?;
+    writeln!(res)?;
+    writeln!(res, "complete -c {} -f", app)?;
+
+    for scope in scopes {
+        let cond = Quote(&format!("__bpaf_static_{}_ctx \"{}\"", fname, scope.ctx)).to_string();
+        for opt in &scope.opts {
+            write!(res, "complete -c {} -n {}", app, cond)?;
+            if let Some(s) = opt.short {
+                write!(res, " -s {}", s)?;
+            }
+            if let Some(l) = &opt.long {
+                write!(res, " -l {}", l)?;
+            }
+            if let Some(hint) = opt.value {
+                write!(res, " -r")?;
+                if let Some(hint) = hint {
+                    fish_hint(&mut res, hint, app)?;
+                }
+            }
+            if !opt.help.is_empty() {
+                write!(res, " -d {}", Quote(&opt.help))?;
+            }
+            writeln!(res)?;
+        }
+        for cmd in &scope.cmds {
+            write!(res, "complete -c {} -n {} -a {}", app, cond, cmd.name)?;
+            if !cmd.help.is_empty() {
+                write!(res, " -d {}", Quote(&cmd.help))?;
+            }
+            writeln!(res)?;
+        }
+        if let Some(hint) = scope.positional {
+            write!(res, "complete -c {} -n {}", app, cond)?;
+            fish_hint(&mut res, hint, app)?;
+            writeln!(res)?;
+        }
+    }
+    Ok(res)
+}
+
+impl<T> OptionParser<T> {
+    /// Render a self-contained shell completion script for the app
+    ///
+    /// Script contains all the commands, flags and arguments known to the parser along with
+    /// their help and [`ShellComp`] hints given with [`complete_shell`](Parser::complete_shell),
+    /// so unlike dynamic completion it doesn't need to run the app on every keypress.
+    /// Values for items decorated with [`complete`](Parser::complete) are still produced by
+    /// calling the app so it must be in `$PATH` for those to work.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = short('v').long("verbose").help("Be verbose").switch().to_options();
+    /// let script = parser.render_static_completion("app", CompletionShell::Fish);
+    /// assert!(script.contains("complete -c app -n '__bpaf_static_app_ctx \"\"' -s v -l verbose -d 'Be verbose'"));
+    /// ```
+    #[must_use]
+    pub fn render_static_completion(&self, app: &str, shell: CompletionShell) -> String {
+        let messages = &self.info.messages;
+        let mut root = Scope::default();
+        let mut scopes = Vec::new();
+        collect(
+            &self.documented_meta(),
+            None,
+            &mut root,
+            &mut scopes,
+            messages,
+        );
+        collect(
+            &self.info.help_meta(messages),
+            None,
+            &mut root,
+            &mut scopes,
+            messages,
+        );
+        scopes.insert(0, root);
+
+        let fname = app
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+
+        match shell {
+            CompletionShell::Bash => render_bash(app, &fname, &scopes),
+            CompletionShell::Zsh => render_zsh(app, &fname, &scopes),
+            CompletionShell::Fish => render_fish(app, &fname, &scopes),
+        }
+        .expect("Writing to a String should never fail")
+    }
+}
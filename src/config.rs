@@ -0,0 +1,97 @@
+//! Configuration file support, see [`OptionParser::config_source`]
+
+use std::{borrow::Cow, collections::HashMap, ffi::OsString, rc::Rc};
+
+#[cfg(doc)]
+use crate::{parsers::NamedArg, OptionParser};
+
+/// A source of values for named items annotated with [`config`](NamedArg::config)
+///
+/// `bpaf` doesn't read or parse configuration files by itself, instead it asks the source for a
+/// value using a key given to [`config`](NamedArg::config), usually something like
+/// `"section.key"`. How keys map to the file structure is up to the implementation.
+///
+/// Values are consulted after the command line and environment variables but before any
+/// [`fallback`](crate::Parser::fallback): CLI > env > config > fallback.
+///
+/// `ConfigSource` is implemented for closures and for a `HashMap<String, String>` so in most
+/// cases you don't need to implement it manually:
+///
+/// ```rust
+/// # use bpaf::*;
+/// # use std::collections::HashMap;
+/// let mut config = HashMap::new();
+/// config.insert("net.port".to_owned(), "8080".to_owned());
+///
+/// let parser = long("port")
+///     .config("net.port")
+///     .argument::<u16>("PORT")
+///     .to_options()
+///     .config_source(config);
+///
+/// assert_eq!(parser.run_inner(&[]).unwrap(), 8080);
+/// assert_eq!(parser.run_inner(&["--port", "80"]).unwrap(), 80);
+/// ```
+pub trait ConfigSource {
+    /// Get a value associated with a key, if present
+    fn get(&self, key: &str) -> Option<String>;
+}
+
+impl<F> ConfigSource for F
+where
+    F: Fn(&str) -> Option<String>,
+{
+    fn get(&self, key: &str) -> Option<String> {
+        self(key)
+    }
+}
+
+impl ConfigSource for HashMap<String, String> {
+    fn get(&self, key: &str) -> Option<String> {
+        HashMap::get(self, key).cloned()
+    }
+}
+
+/// Shared handle to a [`ConfigSource`] stored in [`Info`](crate::info::Info) and
+/// [`State`](crate::State)
+#[derive(Clone)]
+pub(crate) struct ConfigProvider(Rc<dyn ConfigSource>);
+
+impl ConfigProvider {
+    pub(crate) fn new<C: ConfigSource + 'static>(source: C) -> Self {
+        Self(Rc::new(source))
+    }
+
+    /// Look up the first present key, returns the key and its value
+    pub(crate) fn lookup<'a>(
+        &self,
+        keys: &'a [Cow<'static, str>],
+    ) -> Option<(&'a Cow<'static, str>, OsString)> {
+        keys.iter()
+            .find_map(|key| Some((key, OsString::from(self.0.get(key)?))))
+    }
+
+    /// Look up the first present key and interpret it as a flag, returns the key if flag is set
+    ///
+    /// Value is considered present unless it's empty or one of `false`, `0`, `no` or `off`
+    pub(crate) fn lookup_flag<'a>(
+        &self,
+        keys: &'a [Cow<'static, str>],
+    ) -> Option<&'a Cow<'static, str>> {
+        let (key, val) = keys.iter().find_map(|key| Some((key, self.0.get(key)?)))?;
+        if matches!(
+            val.trim().to_ascii_lowercase().as_str(),
+            "" | "false" | "0" | "no" | "off"
+        ) {
+            None
+        } else {
+            Some(key)
+        }
+    }
+}
+
+impl std::fmt::Debug for ConfigProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ConfigProvider")
+    }
+}
@@ -1,11 +1,15 @@
-use std::ops::Range;
+use std::{borrow::Cow, ops::Range, rc::Rc};
 
 use crate::{
     args::{Arg, State},
-    buffer::{Block, Color, Doc, Style, Token},
+    buffer::{Block, ColorChoice, Doc, Style, Theme, Token},
     item::{Item, ShortLong},
+    locale::{Messages, Phrase},
     meta_help::Metavar,
-    meta_youmean::{Suggestion, Variant},
+    meta_youmean::{suggest_choice, Suggestion, Variant},
+    params::PairError,
+    response::ResponseError,
+    structs::ValueSource,
     Meta,
 };
 
@@ -23,13 +27,13 @@ impl Error {
 pub(crate) enum Message {
     // those can be caught ---------------------------------------------------------------
     /// Tried to consume an env variable with no fallback, variable was not set
-    NoEnv(&'static str),
+    NoEnv(Cow<'static, str>),
 
     /// User specified an error message on some
-    ParseSome(&'static str),
+    ParseSome(Cow<'static, str>),
 
     /// User asked for parser to fail explicitly
-    ParseFail(&'static str),
+    ParseFail(Cow<'static, str>),
 
     /// pure_with failed to parse a value
     PureFailed(String),
@@ -50,7 +54,13 @@ pub(crate) enum Message {
     ParseFailed(Option<usize>, String),
 
     /// Parser provided by user failed to validate a value
-    GuardFailed(Option<usize>, &'static str),
+    GuardFailed(Option<usize>, Cow<'static, str>),
+
+    /// Value is not one of the values accepted by `choice`
+    InvalidChoice(Option<usize>, String, Rc<[Cow<'static, str>]>),
+
+    /// Value of `key_value` argument is not a valid pair or one of the halves failed to parse
+    KeyValueFailed(Option<usize>, Box<PairError>),
 
     /// Argument requres a value but something else was passed,
     /// required: --foo <BAR>
@@ -73,11 +83,24 @@ pub(crate) enum Message {
     /// --release --dev
     Conflict(/* winner */ usize, usize),
 
+    /// Two items are mutually exclusive and at least one of them got its value from an
+    /// environment variable or a config source
+    /// STDIN=1 with --file
+    SourceConflict(Box<(/* winner */ ValueSource, ValueSource)>),
+
+    /// Argument needs some other item that is not present, index is missing if requirement
+    /// doesn't come from the command line
+    /// --sign (without --key)
+    Requires(Option<usize>, Box<Doc>),
+
     /// Expected one or more items in the scope, got someting else if any
     Expected(Vec<Item>, Option<usize>),
 
     /// Parameter is accepted but only once
     OnlyOnce(/* winner */ usize, usize),
+
+    /// Response file can't be read or parsed
+    ResponseFile(ResponseError),
 }
 
 impl Message {
@@ -91,13 +114,18 @@ impl Message {
             Message::StrictPos(_, _)
             | Message::ParseFailed(_, _)
             | Message::GuardFailed(_, _)
+            | Message::InvalidChoice(_, _, _)
+            | Message::KeyValueFailed(_, _)
             | Message::Unconsumed(_)
             | Message::Ambiguity(_, _)
             | Message::Suggestion(_, _)
             | Message::Conflict(_, _)
+            | Message::SourceConflict(_)
+            | Message::Requires(_, _)
             | Message::ParseFailure(_)
             | Message::Expected(_, _)
             | Message::OnlyOnce(_, _)
+            | Message::ResponseFile(_)
             | Message::NoArgument(_, _) => false,
         }
     }
@@ -201,6 +229,36 @@ impl ParseFailure {
         }
     }
 
+    /// Returns structured information about the parsing error, if available
+    ///
+    /// Only [`Stderr`](ParseFailure::Stderr) failures produced by `bpaf` itself contain it, see
+    /// [`ErrorKind`] for details.
+    #[must_use]
+    pub fn error_kind(&self) -> Option<&ErrorKind> {
+        match self {
+            Self::Stderr(doc) => doc.error.as_deref(),
+            Self::Completion(..) | Self::Stdout(..) => None,
+        }
+    }
+
+    /// Remember color choice user made on the command line along with theme and locale
+    /// configured for the parser
+    pub(crate) fn with_style(
+        mut self,
+        color: Option<ColorChoice>,
+        messages: &Messages,
+        theme: &Theme,
+    ) -> Self {
+        match &mut self {
+            ParseFailure::Stdout(doc, _) | ParseFailure::Stderr(doc) => {
+                doc.color = color;
+                doc.style = Some(Box::new((theme.clone(), messages.clone())));
+            }
+            ParseFailure::Completion(_) => {}
+        }
+        self
+    }
+
     /// Returns the exit code for the failure
     #[allow(clippy::must_use_candidate)]
     pub fn exit_code(self) -> i32 {
@@ -212,34 +270,412 @@ impl ParseFailure {
 
     /// Prints a message to `stdout` or `stderr` appropriate to the failure.
     pub fn print_mesage(&self, max_width: usize) {
-        let color = Color::default();
+        self.print_message_in(max_width, None, false, false);
+    }
+
+    /// Prints a message to `stdout` or `stderr` using colors according to `color`
+    ///
+    /// Overrides the choice user made with [`color_arg`](crate::OptionParser::color_arg), if any.
+    pub fn print_message_with(&self, max_width: usize, color: ColorChoice) {
+        self.print_message_in(max_width, Some(color), false, false);
+    }
+
+    /// `narrow` is set when `max_width` comes from a terminal narrower than the configured width
+    pub(crate) fn print_message_in(
+        &self,
+        max_width: usize,
+        color: Option<ColorChoice>,
+        pager: bool,
+        narrow: bool,
+    ) {
+        let text = self.render_message(max_width, color, narrow);
         match self {
-            ParseFailure::Stdout(msg, full) => {
-                println!("{}", msg.render_console(*full, color, max_width));
+            ParseFailure::Stdout(..) => {
+                if !(pager && crate::pager::page(&text)) {
+                    println!("{}", text);
+                }
             }
-            ParseFailure::Completion(s) => {
-                print!("{}", s);
+            ParseFailure::Completion(_) => print!("{}", text),
+            ParseFailure::Stderr(_) => eprintln!("{}", text),
+        }
+    }
+
+    /// Render the message the way it gets printed, using theme and locale saved in the failure
+    pub(crate) fn render_message(
+        &self,
+        max_width: usize,
+        color: Option<ColorChoice>,
+        narrow: bool,
+    ) -> String {
+        let default = (Theme::default(), Messages::default());
+        let (picked, style) = match self {
+            ParseFailure::Stdout(doc, _) | ParseFailure::Stderr(doc) => {
+                (doc.color, doc.style.as_deref())
             }
+            ParseFailure::Completion(_) => (None, None),
+        };
+        let (theme, messages) = style.unwrap_or(&default);
+        let theme = color.or(picked).unwrap_or(ColorChoice::Auto).pick(theme);
+        let render = |msg: &Doc, full| {
+            if narrow {
+                msg.render_console_narrow(full, theme, max_width)
+            } else {
+                msg.render_console(full, theme, max_width)
+            }
+        };
+        match self {
+            ParseFailure::Stdout(msg, full) => render(msg, *full),
+            ParseFailure::Completion(s) => s.clone(),
             ParseFailure::Stderr(msg) => {
-                #[allow(unused_mut)]
-                let mut error;
-                #[cfg(not(feature = "color"))]
-                {
-                    error = "Error: ";
-                }
+                let mut error = String::new();
+                theme.push_str(
+                    Style::Invalid,
+                    &mut error,
+                    messages.get(Phrase::ErrorPrefix),
+                );
+                error.push_str(&render(msg, true));
+                error
+            }
+        }
+    }
+}
 
-                #[cfg(feature = "color")]
-                {
-                    error = String::new();
-                    color.push_str(Style::Invalid, &mut error, "Error: ");
-                }
+/// Structured description of a parsing error, see [`ParseFailure::error_kind`]
+///
+/// Indices refer to positions in the list of arguments passed to the parser, not counting
+/// the program name. A single argument can be reported by several items: in `--foo=bar` both
+/// `--foo` and `bar` refer to the same argument.
+/// With [response files](crate::Args::with_response_files) indices refer to arguments after
+/// the expansion.
+///
+/// ```rust
+/// # use bpaf::*;
+/// let parser = long("verbose").switch().to_options();
+/// let failure = parser.run_inner(&["--verbos"]).unwrap_err();
+/// match failure.error_kind() {
+///     Some(ErrorKind::Suggestion { index, suggestion, .. }) => {
+///         assert_eq!(*index, 0);
+///         assert_eq!(suggestion, "--verbose");
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Environment variable required by the parser is not set
+    NoEnv {
+        /// Variable name
+        name: Cow<'static, str>,
+    },
+
+    /// Message from [`some`](crate::Parser::some), [`fail`](crate::fail) or
+    /// [`pure_with`](crate::pure_with)
+    Custom {
+        /// User provided message
+        message: String,
+    },
+
+    /// Strictly positional item was present on the left side of `--`
+    StrictPos {
+        /// Argument index
+        index: usize,
+        /// Metavariable of the positional item
+        metavar: Cow<'static, str>,
+    },
+
+    /// Parser provided by user failed to parse a value
+    ParseFailed {
+        /// Argument index, if value came from the command line
+        index: Option<usize>,
+        /// Message produced by the parser
+        message: String,
+    },
+
+    /// Parser provided by user failed to validate a value
+    GuardFailed {
+        /// Argument index, if value came from the command line
+        index: Option<usize>,
+        /// Message given to [`guard`](crate::Parser::guard)
+        message: Cow<'static, str>,
+    },
+
+    /// Value is not one of the values accepted by [`choice`](crate::parsers::NamedArg::choice)
+    InvalidChoice {
+        /// Argument index, if value came from the command line
+        index: Option<usize>,
+        /// Value that was passed
+        value: String,
+        /// Values accepted by the parser
+        choices: Vec<String>,
+        /// Closest accepted value, if any is close enough
+        suggestion: Option<String>,
+    },
+
+    /// Value of [`key_value`](crate::parsers::ParseArgument::key_value) argument is not a valid
+    /// pair
+    KeyValueFailed {
+        /// Argument index, if value came from the command line
+        index: Option<usize>,
+        /// Part of the pair that is wrong
+        part: KeyValuePart,
+        /// Message produced by the parser for the key or the value, empty if `=` is missing
+        message: String,
+    },
+
+    /// Named argument requires a value but none was given
+    NoArgument {
+        /// Argument index of the name
+        index: usize,
+        /// Metavariable of the expected value
+        metavar: Cow<'static, str>,
+        /// Item that needs the value, if it belongs to the failed parser
+        item: Option<Item>,
+    },
+
+    /// Argument is not expected in this context
+    Unconsumed {
+        /// Argument index
+        index: usize,
+    },
+
+    /// Short argument can be parsed both as a group of flags and as an option with a value
+    Ambiguity {
+        /// Argument index
+        index: usize,
+        /// Short names in the argument
+        name: String,
+    },
+
+    /// Argument is not valid, but there's a close match
+    Suggestion {
+        /// Argument index
+        index: usize,
+        /// Suggested replacement: `--flag`, `-f` or a `command`
+        suggestion: String,
+        /// Suggested item is valid inside of this subcommand only
+        command: Option<String>,
+    },
+
+    /// Two arguments are mutually exclusive
+    Conflict {
+        /// Argument index of the item that was rejected
+        index: usize,
+        /// Argument index of the item that was accepted
+        winner: usize,
+        /// Item that was rejected, if it is a named item of the failed parser
+        item: Option<Item>,
+    },
+
+    /// Two items are mutually exclusive and at least one of them got its value from an
+    /// environment variable or a config source, see [`exactly_one_of`](crate::Parser::exactly_one_of)
+    SourceConflict {
+        /// Where the rejected value came from
+        source: ValueSource,
+        /// Where the accepted value came from
+        winner: ValueSource,
+    },
+
+    /// Argument needs some other item that is not present, see [`requires`](crate::Parser::requires)
+    /// and [`required_if`](crate::Parser::required_if)
+    Requires {
+        /// Argument index of the item that needs the other one, if it came from the command line
+        index: Option<usize>,
+        /// Usage of the missing item
+        requires: String,
+    },
+
+    /// Parser expected one of those items, got something else if anything
+    Expected {
+        /// Items that were expected in this context, use `Display` to render them
+        items: Vec<Item>,
+        /// Argument index of the unexpected argument, if any
+        index: Option<usize>,
+    },
 
-                eprintln!("{}{}", error, msg.render_console(true, color, max_width));
+    /// Argument is accepted only once
+    OnlyOnce {
+        /// Argument index of the repeated item
+        index: usize,
+        /// Argument index of the first item
+        first: usize,
+        /// Repeated item, if it is a named item of the failed parser
+        item: Option<Item>,
+    },
+
+    /// Response file can't be read or parsed, see [`Args::with_response_files`](crate::Args::with_response_files)
+    ResponseFile {
+        /// Path to the file
+        path: String,
+        /// Line number, if the problem is with file contents
+        line: Option<usize>,
+        /// Description of the problem
+        message: String,
+    },
+}
+
+/// Part of a `KEY=VALUE` pair, see [`ErrorKind::KeyValueFailed`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyValuePart {
+    /// There's no `=` between the key and the value
+    Separator,
+    /// Key failed to parse
+    Key,
+    /// Value failed to parse
+    Value,
+}
+
+impl ErrorKind {
+    /// Argument index the error refers to, if any
+    #[must_use]
+    pub fn index(&self) -> Option<usize> {
+        match self {
+            ErrorKind::StrictPos { index, .. }
+            | ErrorKind::NoArgument { index, .. }
+            | ErrorKind::Unconsumed { index }
+            | ErrorKind::Ambiguity { index, .. }
+            | ErrorKind::Suggestion { index, .. }
+            | ErrorKind::Conflict { index, .. }
+            | ErrorKind::OnlyOnce { index, .. } => Some(*index),
+            ErrorKind::ParseFailed { index, .. }
+            | ErrorKind::GuardFailed { index, .. }
+            | ErrorKind::InvalidChoice { index, .. }
+            | ErrorKind::KeyValueFailed { index, .. }
+            | ErrorKind::Requires { index, .. }
+            | ErrorKind::Expected { index, .. } => *index,
+            ErrorKind::SourceConflict { source, .. } => match source {
+                ValueSource::Argv { index, .. } => Some(*index),
+                _ => None,
+            },
+            ErrorKind::NoEnv { .. } | ErrorKind::Custom { .. } | ErrorKind::ResponseFile { .. } => {
+                None
             }
         }
     }
 }
 
+fn suggestion_text(variant: &Variant) -> String {
+    match variant {
+        Variant::CommandLong(name) => name.clone(),
+        Variant::Flag(ShortLong::Long(l) | ShortLong::Both(_, l)) => format!("--{}", l),
+        Variant::Flag(ShortLong::Short(s)) => format!("-{}", s),
+    }
+}
+
+impl Message {
+    /// Structured version of a message, message must be finalized
+    fn error_kind(&self, args: &State, meta: &Meta) -> Option<ErrorKind> {
+        let ix = |ix: usize| args.origin(ix);
+        let item = |ix: usize| meta.named_item(args.items.get(ix)?).cloned();
+        Some(match self {
+            Message::ParseFailure(_) | Message::Missing(_) => return None,
+            Message::NoEnv(name) => ErrorKind::NoEnv { name: name.clone() },
+            Message::ParseSome(s) => ErrorKind::Custom {
+                message: s.to_string(),
+            },
+            Message::ParseFail(s) => ErrorKind::Custom {
+                message: s.to_string(),
+            },
+            Message::PureFailed(s) => ErrorKind::Custom { message: s.clone() },
+            Message::StrictPos(index, metavar) => ErrorKind::StrictPos {
+                index: ix(*index),
+                metavar: metavar.0.clone(),
+            },
+            Message::ParseFailed(index, message) => ErrorKind::ParseFailed {
+                index: index.map(ix),
+                message: message.clone(),
+            },
+            Message::GuardFailed(index, message) => ErrorKind::GuardFailed {
+                index: index.map(ix),
+                message: message.clone(),
+            },
+            Message::InvalidChoice(index, value, choices) => ErrorKind::InvalidChoice {
+                index: index.map(ix),
+                value: value.clone(),
+                choices: choices.iter().map(|c| c.to_string()).collect(),
+                suggestion: suggest_choice(value, choices).map(str::to_owned),
+            },
+            Message::KeyValueFailed(index, err) => ErrorKind::KeyValueFailed {
+                index: index.map(ix),
+                part: err.part,
+                message: err.message.clone(),
+            },
+            Message::NoArgument(index, metavar) => ErrorKind::NoArgument {
+                index: ix(*index),
+                metavar: metavar.0.clone(),
+                item: item(*index),
+            },
+            Message::Unconsumed(index) => ErrorKind::Unconsumed { index: ix(*index) },
+            Message::Ambiguity(index, name) => ErrorKind::Ambiguity {
+                index: ix(*index),
+                name: name.clone(),
+            },
+            Message::Suggestion(index, suggestion) => {
+                let (suggestion, command) = match suggestion {
+                    Suggestion::Variant(v) => (suggestion_text(v), None),
+                    Suggestion::MissingDash(name) => (format!("--{}", name), None),
+                    Suggestion::ExtraDash(name) => (format!("-{}", name), None),
+                    Suggestion::Nested(cmd, v) => (suggestion_text(v), Some(cmd.clone())),
+                };
+                ErrorKind::Suggestion {
+                    index: ix(*index),
+                    suggestion,
+                    command,
+                }
+            }
+            Message::Conflict(winner, loser) => ErrorKind::Conflict {
+                index: ix(*loser),
+                winner: ix(*winner),
+                item: item(*loser),
+            },
+            Message::SourceConflict(pair) => ErrorKind::SourceConflict {
+                source: pair.1.clone(),
+                winner: pair.0.clone(),
+            },
+            Message::Requires(index, other) => ErrorKind::Requires {
+                index: index.map(ix),
+                requires: other.monochrome(false),
+            },
+            Message::Expected(items, index) => ErrorKind::Expected {
+                items: items.clone(),
+                index: index.map(ix),
+            },
+            Message::OnlyOnce(first, index) => ErrorKind::OnlyOnce {
+                index: ix(*index),
+                first: ix(*first),
+                item: item(*index),
+            },
+            Message::ResponseFile(e) => ErrorKind::ResponseFile {
+                path: e.file.clone(),
+                line: e.line,
+                message: e.message.clone(),
+            },
+        })
+    }
+}
+
+/// Write a reference to a term such as `--foo` or `FILE`
+fn term_ref(doc: &mut Doc, f: impl FnOnce(&mut Doc)) {
+    doc.token(Token::BlockStart(Block::TermRef));
+    f(doc);
+    doc.token(Token::BlockEnd(Block::TermRef));
+}
+
+/// Write where a value came from: `--foo`, `environment variable FOO` or `config key foo`
+fn write_source(doc: &mut Doc, m: &Messages, source: &ValueSource) {
+    match source {
+        ValueSource::Argv { spelling, .. } => term_ref(doc, |doc| doc.literal(spelling)),
+        ValueSource::Env(name) => doc.write_template(m.get(Phrase::EnvSource), |doc, _| {
+            term_ref(doc, |doc| doc.literal(name));
+        }),
+        ValueSource::Config(key) => doc.write_template(m.get(Phrase::ConfigSource), |doc, _| {
+            term_ref(doc, |doc| doc.literal(key));
+        }),
+        _ => {}
+    }
+}
+
 fn check_conflicts(args: &State) -> Option<Message> {
     let (loser, winner) = args.conflict()?;
     Some(Message::Conflict(winner, loser))
@@ -285,7 +721,9 @@ impl Message {
             _ => {}
         }
 
+        let m = &args.messages;
         let mut doc = Doc::default();
+        doc.error = self.error_kind(args, meta).map(Box::new);
         match self {
             // already rendered
             Message::ParseFailure(f) => return f,
@@ -298,64 +736,105 @@ impl Message {
             // Error: --foo is not expected in this context
             Message::Unconsumed(ix) => {
                 let item = &args.items[ix];
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.write(item, Style::Invalid);
-                doc.token(Token::BlockEnd(Block::TermRef));
-                doc.text(" is not expected in this context");
+                doc.write_template(m.get(Phrase::NotExpected), |doc, _| {
+                    term_ref(doc, |doc| doc.write(item, Style::Invalid));
+                });
             }
 
             // Error: environment variable FOO is not set
             Message::NoEnv(name) => {
-                doc.text("environment variable ");
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.invalid(name);
-                doc.token(Token::BlockEnd(Block::TermRef));
-                doc.text(" is not set");
+                doc.write_template(m.get(Phrase::NoEnv), |doc, _| {
+                    term_ref(doc, |doc| doc.invalid(&name));
+                });
             }
 
             // Error: FOO expected to be  in the right side of --
             Message::StrictPos(_ix, metavar) => {
-                doc.text("expected ");
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.metavar(metavar);
-                doc.token(Token::BlockEnd(Block::TermRef));
-                doc.text(" to be on the right side of ");
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.literal("--");
-                doc.token(Token::BlockEnd(Block::TermRef));
+                doc.write_template(m.get(Phrase::StrictPos), |doc, name| match name {
+                    "metavar" => term_ref(doc, |doc| doc.metavar(&metavar)),
+                    "separator" => term_ref(doc, |doc| doc.literal("--")),
+                    _ => {}
+                });
             }
 
             // Error: <message from some or fail>
-            Message::ParseSome(s) | Message::ParseFail(s) => {
-                doc.text(s);
+            Message::ParseSome(s) => {
+                doc.text(&s);
+            }
+            Message::ParseFail(s) => {
+                doc.text(&s);
             }
 
             // Error: couldn't parse FIELD: <FromStr message>
-            Message::ParseFailed(mix, s) => {
-                doc.text("couldn't parse");
-                if let Some(field) = textual_part(args, mix) {
-                    doc.text(" ");
-                    doc.token(Token::BlockStart(Block::TermRef));
-                    doc.invalid(&field);
-                    doc.token(Token::BlockEnd(Block::TermRef));
+            Message::ParseFailed(mix, s) => match textual_part(args, mix) {
+                Some(field) => {
+                    doc.write_template(m.get(Phrase::ParseFailed), |doc, name| match name {
+                        "arg" => term_ref(doc, |doc| doc.invalid(&field)),
+                        "message" => doc.text(&s),
+                        _ => {}
+                    });
                 }
-                doc.text(": ");
-                doc.text(&s);
-            }
+                None => {
+                    doc.write_template(m.get(Phrase::ParseFailedValue), |doc, _| doc.text(&s));
+                }
+            },
 
             // Error: ( FIELD:  | check failed: ) <message from guard>
-            Message::GuardFailed(mix, s) => {
-                if let Some(field) = textual_part(args, mix) {
-                    doc.token(Token::BlockStart(Block::TermRef));
-                    doc.invalid(&field);
-                    doc.token(Token::BlockEnd(Block::TermRef));
-                    doc.text(": ");
-                } else {
-                    doc.text("check failed: ");
+            Message::GuardFailed(mix, s) => match textual_part(args, mix) {
+                Some(field) => {
+                    doc.write_template(m.get(Phrase::GuardFailed), |doc, name| match name {
+                        "arg" => term_ref(doc, |doc| doc.invalid(&field)),
+                        "message" => doc.text(&s),
+                        _ => {}
+                    });
+                }
+                None => {
+                    doc.write_template(m.get(Phrase::GuardFailedValue), |doc, _| doc.text(&s));
                 }
-                doc.text(s);
+            },
+
+            // Error: fsat is not a valid value, did you mean fast?
+            // Error: xyz is not a valid value, expected one of fast, safe, off
+            // Error: couldn't parse key `K` in `K=V`: <FromStr message>
+            Message::KeyValueFailed(_ix, err) => {
+                let (phrase, half) = match err.part {
+                    KeyValuePart::Separator => (Phrase::KeyValueSeparator, ""),
+                    KeyValuePart::Key => (Phrase::KeyValueKey, err.key.as_str()),
+                    KeyValuePart::Value => (Phrase::KeyValueValue, err.value.as_str()),
+                };
+                doc.write_template(m.get(phrase), |doc, name| match name {
+                    "arg" => term_ref(doc, |doc| doc.literal(&err.input)),
+                    "metavar" => term_ref(doc, |doc| doc.metavar(&err.metavar)),
+                    "key" | "value" => term_ref(doc, |doc| doc.invalid(half)),
+                    "message" => doc.text(&err.message),
+                    _ => {}
+                });
             }
 
+            Message::InvalidChoice(_ix, value, choices) => match suggest_choice(&value, &choices) {
+                Some(suggestion) => {
+                    doc.write_template(m.get(Phrase::InvalidChoice), |doc, name| match name {
+                        "arg" => term_ref(doc, |doc| doc.invalid(&value)),
+                        "suggestion" => term_ref(doc, |doc| doc.literal(suggestion)),
+                        _ => {}
+                    });
+                }
+                None => {
+                    doc.write_template(m.get(Phrase::InvalidChoiceList), |doc, name| match name {
+                        "arg" => term_ref(doc, |doc| doc.invalid(&value)),
+                        "values" => {
+                            for (ix, choice) in choices.iter().enumerate() {
+                                if ix > 0 {
+                                    doc.text(", ");
+                                }
+                                term_ref(doc, |doc| doc.literal(choice));
+                            }
+                        }
+                        _ => {}
+                    });
+                }
+            },
+
             // Error: --foo requires an argument FOO, got a flag --bar, try --foo=-bar to use it as an argument
             // Error: --foo requires an argument FOO
             Message::NoArgument(x, mv) => match args.get(x + 1) {
@@ -363,35 +842,26 @@ impl Message {
                     let arg = &args.items[x];
                     let os = &os.to_string_lossy();
 
-                    doc.token(Token::BlockStart(Block::TermRef));
-                    doc.write(arg, Style::Literal);
-                    doc.token(Token::BlockEnd(Block::TermRef));
-                    doc.text(" requires an argument ");
-                    doc.token(Token::BlockStart(Block::TermRef));
-                    doc.metavar(mv);
-                    doc.token(Token::BlockEnd(Block::TermRef));
-                    doc.text(", got a flag ");
-                    doc.token(Token::BlockStart(Block::TermRef));
-                    doc.write(os, Style::Invalid);
-                    doc.token(Token::BlockEnd(Block::TermRef));
-                    doc.text(", try ");
-                    doc.token(Token::BlockStart(Block::TermRef));
-                    doc.write(arg, Style::Literal);
-                    doc.literal("=");
-                    doc.write(os, Style::Literal);
-                    doc.token(Token::BlockEnd(Block::TermRef));
-                    doc.text(" to use it as an argument");
+                    doc.write_template(m.get(Phrase::NoArgumentGotFlag), |doc, name| match name {
+                        "arg" => term_ref(doc, |doc| doc.write(arg, Style::Literal)),
+                        "metavar" => term_ref(doc, |doc| doc.metavar(&mv)),
+                        "flag" => term_ref(doc, |doc| doc.write(os, Style::Invalid)),
+                        "fix" => term_ref(doc, |doc| {
+                            doc.write(arg, Style::Literal);
+                            doc.literal("=");
+                            doc.write(os, Style::Literal);
+                        }),
+                        _ => {}
+                    });
                 }
                 // "Some" part of this branch is actually unreachable
                 Some(Arg::ArgWord(_) | Arg::Word(_) | Arg::PosWord(_)) | None => {
                     let arg = &args.items[x];
-                    doc.token(Token::BlockStart(Block::TermRef));
-                    doc.write(arg, Style::Literal);
-                    doc.token(Token::BlockEnd(Block::TermRef));
-                    doc.text(" requires an argument ");
-                    doc.token(Token::BlockStart(Block::TermRef));
-                    doc.metavar(mv);
-                    doc.token(Token::BlockEnd(Block::TermRef));
+                    doc.write_template(m.get(Phrase::NoArgument), |doc, name| match name {
+                        "arg" => term_ref(doc, |doc| doc.write(arg, Style::Literal)),
+                        "metavar" => term_ref(doc, |doc| doc.metavar(&mv)),
+                        _ => {}
+                    });
                 }
             },
             // Error: <message from pure_with>
@@ -407,35 +877,31 @@ impl Message {
                 let second = chars.next().unwrap();
                 let s = args.items[ix].os_str().to_str().unwrap();
 
-                if let Some(name) = args.path.first() {
-                    doc.literal(name);
-                    doc.text(" supports ");
-                } else {
-                    doc.text("app supports ");
-                }
-
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.literal("-");
-                doc.write_char(first, Style::Literal);
-                doc.token(Token::BlockEnd(Block::TermRef));
-                doc.text(" as both an option and an option-argument, try to split ");
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.write(s, Style::Literal);
-                doc.token(Token::BlockEnd(Block::TermRef));
-                doc.text(" into individual options (");
-                doc.literal("-");
-                doc.write_char(first, Style::Literal);
-                doc.literal(" -");
-                doc.write_char(second, Style::Literal);
-                doc.literal(" ..");
-                doc.text(") or use ");
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.literal("-");
-                doc.write_char(first, Style::Literal);
-                doc.literal("=");
-                doc.literal(rest);
-                doc.token(Token::BlockEnd(Block::TermRef));
-                doc.text(" syntax to disambiguate");
+                doc.write_template(m.get(Phrase::Ambiguity), |doc, name| match name {
+                    "app" => match args.path.first() {
+                        Some(name) => doc.literal(name),
+                        None => doc.text(m.get(Phrase::UnnamedApp)),
+                    },
+                    "flag" => term_ref(doc, |doc| {
+                        doc.literal("-");
+                        doc.write_char(first, Style::Literal);
+                    }),
+                    "arg" => term_ref(doc, |doc| doc.write(s, Style::Literal)),
+                    "split" => {
+                        doc.literal("-");
+                        doc.write_char(first, Style::Literal);
+                        doc.literal(" -");
+                        doc.write_char(second, Style::Literal);
+                        doc.literal(" ..");
+                    }
+                    "fix" => term_ref(doc, |doc| {
+                        doc.literal("-");
+                        doc.write_char(first, Style::Literal);
+                        doc.literal("=");
+                        doc.literal(rest);
+                    }),
+                    _ => {}
+                });
             }
             // Error: No such (flag|argument|command), did you mean  ...
             Message::Suggestion(ix, suggestion) => {
@@ -443,153 +909,179 @@ impl Message {
                 match suggestion {
                     Suggestion::Variant(v) => {
                         let ty = match &args.items[ix] {
-                            _ if actual.starts_with('-') => "flag",
-                            Arg::Short(_, _, _) | Arg::Long(_, _, _) => "flag",
-                            Arg::ArgWord(_) => "argument value",
-                            Arg::Word(_) | Arg::PosWord(_) => "command or positional",
-                        };
-
-                        doc.text("no such ");
-                        doc.text(ty);
-                        doc.text(": ");
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.invalid(actual);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text(", did you mean ");
-                        doc.token(Token::BlockStart(Block::TermRef));
-
-                        match v {
-                            Variant::CommandLong(name) => doc.literal(name),
-                            Variant::Flag(ShortLong::Long(l) | ShortLong::Both(_, l)) => {
-                                doc.literal("--");
-                                doc.literal(l);
-                            }
-                            Variant::Flag(ShortLong::Short(s)) => {
-                                doc.literal("-");
-                                doc.write_char(s, Style::Literal);
-                            }
+                            _ if actual.starts_with('-') => Phrase::KindFlag,
+                            Arg::Short(_, _, _) | Arg::Long(_, _, _) => Phrase::KindFlag,
+                            Arg::ArgWord(_) => Phrase::KindArgumentValue,
+                            Arg::Word(_) | Arg::PosWord(_) => Phrase::KindCommandOrPositional,
                         };
 
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text("?");
+                        doc.write_template(m.get(Phrase::NoSuchItem), |doc, name| match name {
+                            "kind" => doc.text(m.get(ty)),
+                            "arg" => term_ref(doc, |doc| doc.invalid(actual)),
+                            "suggestion" => term_ref(doc, |doc| match &v {
+                                Variant::CommandLong(name) => doc.literal(name),
+                                Variant::Flag(ShortLong::Long(l) | ShortLong::Both(_, l)) => {
+                                    doc.literal("--");
+                                    doc.literal(l);
+                                }
+                                Variant::Flag(ShortLong::Short(s)) => {
+                                    doc.literal("-");
+                                    doc.write_char(*s, Style::Literal);
+                                }
+                            }),
+                            _ => {}
+                        });
                     }
                     Suggestion::MissingDash(name) => {
-                        doc.text("no such flag: ");
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.literal("-");
-                        doc.literal(name);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text(" (with one dash), did you mean ");
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.literal("--");
-                        doc.literal(name);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text("?");
+                        doc.write_template(m.get(Phrase::MissingDash), |doc, ph| match ph {
+                            "arg" => term_ref(doc, |doc| {
+                                doc.literal("-");
+                                doc.literal(&name);
+                            }),
+                            "suggestion" => term_ref(doc, |doc| {
+                                doc.literal("--");
+                                doc.literal(&name);
+                            }),
+                            _ => {}
+                        });
                     }
                     Suggestion::ExtraDash(name) => {
-                        doc.text("no such flag: ");
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.literal("--");
-                        doc.write_char(name, Style::Literal);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text(" (with two dashes), did you mean ");
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.literal("-");
-                        doc.write_char(name, Style::Literal);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text("?");
+                        doc.write_template(m.get(Phrase::ExtraDash), |doc, ph| match ph {
+                            "arg" => term_ref(doc, |doc| {
+                                doc.literal("--");
+                                doc.write_char(name, Style::Literal);
+                            }),
+                            "suggestion" => term_ref(doc, |doc| {
+                                doc.literal("-");
+                                doc.write_char(name, Style::Literal);
+                            }),
+                            _ => {}
+                        });
                     }
                     Suggestion::Nested(x, v) => {
                         let ty = match v {
-                            Variant::CommandLong(_) => "subcommand",
-                            Variant::Flag(_) => "flag",
+                            Variant::CommandLong(_) => Phrase::KindSubcommand,
+                            Variant::Flag(_) => Phrase::KindFlag,
                         };
-                        doc.text(ty);
-                        doc.text(" ");
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.literal(actual);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text(
-                            " is not valid in this context, did you mean to pass it to command ",
-                        );
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.literal(&x);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text("?");
+                        doc.write_template(m.get(Phrase::Nested), |doc, name| match name {
+                            "kind" => doc.text(m.get(ty)),
+                            "arg" => term_ref(doc, |doc| doc.literal(actual)),
+                            "command" => term_ref(doc, |doc| doc.literal(&x)),
+                            _ => {}
+                        });
                     }
                 }
             }
             // Error: Expected (no arguments|--foo), got ..., pass --help
             Message::Expected(exp, actual) => {
-                doc.text("expected ");
-                match exp.len() {
-                    0 => {
-                        doc.text("no arguments");
-                    }
-                    1 => {
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.write_item(&exp[0]);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                    }
-                    2 => {
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.write_item(&exp[0]);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text(" or ");
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.write_item(&exp[1]);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                    }
-                    _ => {
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.write_item(&exp[0]);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text(", ");
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.write_item(&exp[1]);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text(", or more");
-                    }
-                }
-                match actual {
-                    Some(actual) => {
-                        doc.text(", got ");
-                        doc.token(Token::BlockStart(Block::TermRef));
-                        doc.write(&args.items[actual], Style::Invalid);
-                        doc.token(Token::BlockEnd(Block::TermRef));
-                        doc.text(". Pass ");
+                let expected = |doc: &mut Doc| match exp.len() {
+                    0 => doc.text(m.get(Phrase::ExpectedNothing)),
+                    1 => term_ref(doc, |doc| doc.write_item(&exp[0])),
+                    n => {
+                        let phrase = if n == 2 {
+                            Phrase::ExpectedTwo
+                        } else {
+                            Phrase::ExpectedMany
+                        };
+                        doc.write_template(m.get(phrase), |doc, name| match name {
+                            "first" => term_ref(doc, |doc| doc.write_item(&exp[0])),
+                            "second" => term_ref(doc, |doc| doc.write_item(&exp[1])),
+                            _ => {}
+                        });
                     }
-                    None => {
-                        doc.text(", pass ");
+                };
+                let phrase = match actual {
+                    Some(_) => Phrase::Expected,
+                    None => Phrase::ExpectedAtEnd,
+                };
+                doc.write_template(m.get(phrase), |doc, name| match name {
+                    "expected" => expected(doc),
+                    "arg" => {
+                        if let Some(actual) = actual {
+                            term_ref(doc, |doc| doc.write(&args.items[actual], Style::Invalid));
+                        }
                     }
-                }
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.literal("--help");
-                doc.token(Token::BlockEnd(Block::TermRef));
-                doc.text(" for usage information");
+                    "help" => term_ref(doc, |doc| doc.literal("--help")),
+                    _ => {}
+                });
             }
 
             // Error: --intel cannot be used at the same time as --att
             Message::Conflict(winner, loser) => {
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.write(&args.items[loser], Style::Literal);
-                doc.token(Token::BlockEnd(Block::TermRef));
-                doc.text(" cannot be used at the same time as ");
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.write(&args.items[winner], Style::Literal);
-                doc.token(Token::BlockEnd(Block::TermRef));
+                doc.write_template(m.get(Phrase::Conflict), |doc, name| match name {
+                    "arg" => term_ref(doc, |doc| doc.write(&args.items[loser], Style::Literal)),
+                    "other" => term_ref(doc, |doc| doc.write(&args.items[winner], Style::Literal)),
+                    _ => {}
+                });
+            }
+
+            // Error: environment variable STDIN cannot be used at the same time as --file
+            Message::SourceConflict(pair) => {
+                let (winner, loser) = *pair;
+                doc.write_template(m.get(Phrase::Conflict), |doc, name| match name {
+                    "arg" => write_source(doc, m, &loser),
+                    "other" => write_source(doc, m, &winner),
+                    _ => {}
+                });
+            }
+
+            // Error: --sign requires --key=KEY
+            Message::Requires(Some(ix), other) => {
+                doc.write_template(m.get(Phrase::Requires), |doc, name| match name {
+                    "arg" => term_ref(doc, |doc| doc.write(&args.items[ix], Style::Literal)),
+                    "other" => term_ref(doc, |doc| doc.doc(&other)),
+                    _ => {}
+                });
+            }
+
+            // Error: expected --key=KEY, pass --help for usage information
+            Message::Requires(None, other) => {
+                doc.write_template(m.get(Phrase::ExpectedAtEnd), |doc, name| match name {
+                    "expected" => term_ref(doc, |doc| doc.doc(&other)),
+                    "help" => term_ref(doc, |doc| doc.literal("--help")),
+                    _ => {}
+                });
             }
 
             // Error: argument FOO cannot be used multiple times in this context
             Message::OnlyOnce(_winner, loser) => {
-                doc.text("argument ");
-                doc.token(Token::BlockStart(Block::TermRef));
-                doc.write(&args.items[loser], Style::Literal);
-                doc.token(Token::BlockEnd(Block::TermRef));
-                doc.text(" cannot be used multiple times in this context");
+                doc.write_template(m.get(Phrase::OnlyOnce), |doc, _| {
+                    term_ref(doc, |doc| doc.write(&args.items[loser], Style::Literal));
+                });
+            }
+
+            // Error: couldn't read response file args.txt: No such file or directory
+            // Error: args.txt:3: unterminated quote
+            Message::ResponseFile(e) => {
+                let (phrase, line) = match e.line {
+                    Some(line) => (Phrase::ResponseFileLine, line.to_string()),
+                    None => (Phrase::ResponseFile, String::new()),
+                };
+                doc.write_template(m.get(phrase), |doc, name| match name {
+                    "path" => term_ref(doc, |doc| doc.literal(&e.file)),
+                    "line" => doc.text(&line),
+                    "message" => doc.text(&e.message),
+                    _ => {}
+                });
             }
         };
 
+        // Error: --foo is not expected in this context (from args.txt:3)
+        if let Some(source) = doc
+            .error
+            .as_deref()
+            .and_then(ErrorKind::index)
+            .and_then(|ix| args.source(ix))
+        {
+            let line = source.line.to_string();
+            doc.text(" ");
+            doc.write_template(m.get(Phrase::FromResponseFile), |doc, name| match name {
+                "path" => term_ref(doc, |doc| doc.literal(&source.file)),
+                "line" => doc.text(&line),
+                _ => {}
+            });
+        }
+
         ParseFailure::Stderr(doc)
     }
 }
@@ -602,7 +1094,7 @@ pub(crate) fn summarize_missing(items: &[MissingItem], inner: &Meta, args: &Stat
         .max_by_key(|item| (item.position, item.scope.start))
     {
         Some(x) => x,
-        None => return Message::ParseSome("parser requires an extra flag, argument or parameter, but its name is hidden by the author"),
+        None => return Message::PureFailed(args.messages.get(Phrase::HiddenMissing).to_owned()),
     };
 
     let mut best_scope = best_item.scope.clone();
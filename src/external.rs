@@ -0,0 +1,285 @@
+//! Subcommands implemented by separate executables, `git` and `cargo` style
+//!
+//! Executables are discovered in `PATH` by a common prefix: with prefix `myapp-` file
+//! `myapp-frob` becomes subcommand `frob`.
+
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    ffi::{OsStr, OsString},
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    rc::Rc,
+    time::Duration,
+};
+
+use crate::{
+    error::{Message, MissingItem},
+    info::Info,
+    item::{Item, LazyHelp},
+    Doc, Error, Meta, Parser, State,
+};
+
+/// External subcommand selected by the user, created with [`external_commands`]
+///
+/// [`external_commands`]: crate::external_commands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalCommand {
+    /// Subcommand name, file name without the prefix
+    pub name: String,
+    /// Full path to the executable
+    pub path: PathBuf,
+    /// Unconsumed command line arguments that follow the subcommand name
+    pub args: Vec<OsString>,
+}
+
+impl ExternalCommand {
+    /// [`Command`] that runs the executable with forwarded arguments
+    #[must_use]
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.path);
+        cmd.args(&self.args);
+        cmd
+    }
+}
+
+#[derive(Debug)]
+struct Plugin {
+    name: String,
+    path: PathBuf,
+    /// First line of `--help` output, fetched on demand
+    descr: RefCell<Option<Option<String>>>,
+}
+
+impl Plugin {
+    fn description(&self) -> Option<Doc> {
+        self.descr
+            .borrow_mut()
+            .get_or_insert_with(|| describe_executable(&self.path))
+            .as_deref()
+            .map(Doc::from)
+    }
+}
+
+/// Getting a description means running an external command, this only happens when
+/// documentation is actually rendered
+fn plugin_item(plugins: &Rc<[Plugin]>, ix: usize) -> Item {
+    let described = plugins.clone();
+    Item::Command {
+        name: Cow::Owned(plugins[ix].name.clone()),
+        short: None,
+        help: None,
+        meta: Box::new(Meta::Skip),
+        info: Box::new(Info::default()),
+        lazy_help: Some(LazyHelp(Rc::new(move || described[ix].description()))),
+    }
+}
+
+/// Parser for subcommands implemented by external executables, created with
+/// [`external_commands`]
+///
+/// [`external_commands`]: crate::external_commands
+#[derive(Clone)]
+pub struct ParseExternal {
+    pub(crate) prefix: Cow<'static, str>,
+    pub(crate) search_path: Option<OsString>,
+    found: Rc<RefCell<Option<Rc<[Plugin]>>>>,
+}
+
+pub(crate) fn build_external(prefix: Cow<'static, str>) -> ParseExternal {
+    ParseExternal {
+        prefix,
+        search_path: None,
+        found: Rc::default(),
+    }
+}
+
+impl ParseExternal {
+    /// Look for executables in these directories instead of `PATH`
+    ///
+    /// Value uses the same format as `PATH` environment variable
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let plugins = external_commands("myapp-").search_path("/usr/lib/myapp/plugins");
+    /// ```
+    #[must_use]
+    pub fn search_path(mut self, dirs: impl Into<OsString>) -> Self {
+        self.search_path = Some(dirs.into());
+        self.found = Rc::default();
+        self
+    }
+
+    fn plugins(&self) -> Rc<[Plugin]> {
+        self.found
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                let path = match &self.search_path {
+                    Some(path) => Some(path.clone()),
+                    None => std::env::var_os("PATH"),
+                };
+                match path {
+                    Some(path) => scan(&self.prefix, &path).into(),
+                    None => Rc::new([]),
+                }
+            })
+            .clone()
+    }
+}
+
+/// Find all executables starting with `prefix`, first one in `path` wins
+fn scan(prefix: &str, path: &OsStr) -> Vec<Plugin> {
+    let mut res = Vec::<Plugin>::new();
+    for dir in std::env::split_paths(path) {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let file = entry.file_name();
+            let name = match file
+                .to_str()
+                .and_then(|f| f.strip_prefix(prefix))
+                .and_then(|f| f.strip_suffix(std::env::consts::EXE_SUFFIX))
+            {
+                Some(name) if !name.is_empty() => name,
+                _ => continue,
+            };
+            let path = entry.path();
+            if res.iter().any(|p| p.name == name) || !is_executable(&path) {
+                continue;
+            }
+            res.push(Plugin {
+                name: name.to_owned(),
+                path,
+                descr: RefCell::new(None),
+            });
+        }
+    }
+    res.sort_by(|a, b| a.name.cmp(&b.name));
+    res
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).map_or(false, |m| {
+        m.is_file() && m.permissions().mode() & 0o111 != 0
+    })
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    std::fs::metadata(path).map_or(false, |m| m.is_file())
+}
+
+/// How long to wait for `--help` output of an executable before giving up on its description
+const DESCRIBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Use the first line of `--help` output as a description, unless it's a usage line
+fn describe_executable(path: &Path) -> Option<String> {
+    let mut child = Command::new(path)
+        .arg("--help")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // output is read on a separate thread so a chatty executable can't block on a full pipe
+    let mut pipe = child.stdout.take()?;
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = pipe.read_to_end(&mut output);
+        let _ = tx.send(output);
+    });
+    let output = rx.recv_timeout(DESCRIBE_TIMEOUT);
+    if output.is_err() {
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+    let output = output.ok()?;
+    let stdout = String::from_utf8_lossy(&output);
+    let line = stdout.lines().map(str::trim).find(|l| !l.is_empty())?;
+    if line.starts_with("Usage") {
+        None
+    } else {
+        Some(line.to_owned())
+    }
+}
+
+/// Ask external executable to complete the rest of the command line, output is passed to the
+/// shell unchanged
+#[cfg(feature = "autocomplete")]
+fn delegate_completion(path: &Path, rev: usize, args: &[OsString]) -> String {
+    Command::new(path)
+        .arg(format!("--bpaf-complete-rev={}", rev))
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        .unwrap_or_default()
+}
+
+impl Parser<ExternalCommand> for ParseExternal {
+    fn eval(&self, args: &mut State) -> Result<ExternalCommand, Error> {
+        // values to suggest in error messages don't come from plugins, don't look for them
+        #[cfg(feature = "autocomplete")]
+        if args.is_probe() {
+            return Err(Error(Message::Missing(Vec::new())));
+        }
+        let plugins = self.plugins();
+        for plugin in plugins.iter() {
+            if !args.take_cmd(&plugin.name) {
+                continue;
+            }
+
+            #[cfg(feature = "autocomplete")]
+            if args.touching_last_remove() {
+                args.clear_comps();
+                args.push_command(Cow::Owned(plugin.name.clone()), None, &None);
+                return Err(Error(Message::Missing(Vec::new())));
+            }
+
+            let rest = args.take_rest();
+
+            #[cfg(feature = "autocomplete")]
+            if let Some(rev) = args.comp_ref().map(|c| c.output_rev) {
+                let comp = delegate_completion(&plugin.path, rev, &rest);
+                return Err(Error(Message::ParseFailure(
+                    crate::ParseFailure::Completion(comp),
+                )));
+            }
+
+            return Ok(ExternalCommand {
+                name: plugin.name.clone(),
+                path: plugin.path.clone(),
+                args: rest,
+            });
+        }
+
+        #[cfg(feature = "autocomplete")]
+        for plugin in plugins.iter() {
+            args.push_command(Cow::Owned(plugin.name.clone()), None, &None);
+        }
+
+        let missing = (0..plugins.len())
+            .map(|ix| MissingItem {
+                item: plugin_item(&plugins, ix),
+                position: args.scope().start,
+                scope: args.scope(),
+            })
+            .collect();
+        Err(Error(Message::Missing(missing)))
+    }
+
+    fn meta(&self) -> Meta {
+        let plugins = self.plugins();
+        (0..plugins.len())
+            .map(|ix| Meta::from(plugin_item(&plugins, ix)))
+            .fold(Meta::Skip, Meta::or)
+    }
+}
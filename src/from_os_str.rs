@@ -1,6 +1,6 @@
 use std::{
     any::{Any, TypeId},
-    ffi::OsString,
+    ffi::{OsStr, OsString},
     path::PathBuf,
     str::FromStr,
 };
@@ -23,3 +23,31 @@ where
         }
     }
 }
+
+/// Split `KEY=VALUE` at the first `=` without decoding either half
+pub(crate) fn split_pair(os: &OsStr) -> Option<(OsString, OsString)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::{OsStrExt, OsStringExt};
+        let bytes = os.as_bytes();
+        let ix = bytes.iter().position(|b| *b == b'=')?;
+        let key = OsString::from_vec(bytes[..ix].to_vec());
+        let value = OsString::from_vec(bytes[ix + 1..].to_vec());
+        Some((key, value))
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::{OsStrExt, OsStringExt};
+        let wide = os.encode_wide().collect::<Vec<_>>();
+        let ix = wide.iter().position(|c| *c == u16::from(b'='))?;
+        Some((
+            OsString::from_wide(&wide[..ix]),
+            OsString::from_wide(&wide[ix + 1..]),
+        ))
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let (key, value) = os.to_str()?.split_once('=')?;
+        Some((key.into(), value.into()))
+    }
+}
@@ -2,12 +2,26 @@
 
 use crate::{
     args::{Args, State},
+    buffer::{terminal_width, ColorChoice, Theme},
+    config::{ConfigProvider, ConfigSource},
     error::Message,
+    locale::{Locale, Messages, Phrase},
     meta_help::render_help,
-    parsers::NamedArg,
+    parsers::{NamedArg, ParseOptionalValue},
     short, Doc, Error, Meta, ParseFailure, Parser,
 };
 
+#[cfg(feature = "autocomplete")]
+use crate::meta_youmean::suggest_choice;
+#[cfg(feature = "autocomplete")]
+use std::borrow::Cow;
+
+#[cfg(feature = "prompt")]
+use crate::{
+    prompt::{PromptProvider, PromptState, Prompter, Session},
+    ErrorKind,
+};
+
 /// Information about the parser
 ///
 /// No longer public, users are only interacting with it via [`OptionParser`]
@@ -28,6 +42,23 @@ pub struct Info {
     pub version_arg: NamedArg,
     pub help_if_no_args: bool,
     pub max_width: usize,
+    /// Values for items with config keys, see [`config_source`][OptionParser::config_source]
+    pub(crate) config: Option<ConfigProvider>,
+    /// Translations for built-in messages, see [`locale`][OptionParser::locale]
+    pub(crate) messages: Messages,
+    /// Expand `@path` arguments, see [`response_files`][OptionParser::response_files]
+    pub(crate) response_files: bool,
+    /// Colors for help and error messages, see [`theme`][OptionParser::theme]
+    pub(crate) theme: Theme,
+    /// Lets user pick when to use colors, see [`color_arg`][OptionParser::color_arg]
+    pub(crate) color_arg: Option<NamedArg>,
+    /// Fit messages into the terminal, see [`auto_width`][OptionParser::auto_width]
+    pub(crate) auto_width: bool,
+    /// Show long help through a pager, see [`pager`][OptionParser::pager]
+    pub(crate) pager: bool,
+    /// Ask for missing values, see [`interactive`][OptionParser::interactive]
+    #[cfg(feature = "prompt")]
+    pub(crate) prompter: Option<PromptProvider>,
 }
 
 impl Default for Info {
@@ -38,12 +69,19 @@ impl Default for Info {
             header: None,
             footer: None,
             usage: None,
-            help_arg: short('h').long("help").help("Prints help information"),
-            version_arg: short('V')
-                .long("version")
-                .help("Prints version information"),
+            help_arg: short('h').long("help"),
+            version_arg: short('V').long("version"),
             help_if_no_args: false,
             max_width: 100,
+            config: None,
+            messages: Messages::default(),
+            response_files: false,
+            theme: Theme::default(),
+            color_arg: None,
+            auto_width: false,
+            pager: false,
+            #[cfg(feature = "prompt")]
+            prompter: None,
         }
     }
 }
@@ -90,7 +128,17 @@ impl<T> OptionParser<T> {
         match self.run_inner(Args::current_args()) {
             Ok(t) => t,
             Err(err) => {
-                err.print_mesage(self.info.max_width);
+                let info = &self.info;
+                let mut width = info.max_width;
+                let mut narrow = false;
+                if info.auto_width {
+                    let stderr = matches!(err, ParseFailure::Stderr(_));
+                    if let Some(term) = terminal_width(stderr) {
+                        narrow = term < width;
+                        width = width.min(term);
+                    }
+                }
+                err.print_message_in(width, None, info.pager, narrow);
                 std::process::exit(err.exit_code())
             }
         }
@@ -201,21 +249,100 @@ impl<T> OptionParser<T> {
         // prepare available short flags and arguments for disambiguation
         let mut short_flags = Vec::new();
         let mut short_args = Vec::new();
-        self.inner
-            .meta()
-            .collect_shorts(&mut short_flags, &mut short_args);
+        let meta = self.inner.meta();
+        meta.collect_shorts(&mut short_flags, &mut short_args);
         short_flags.extend(&self.info.help_arg.short);
         short_flags.extend(&self.info.version_arg.short);
-        let args = args.into();
+        if let Some(color) = &self.info.color_arg {
+            short_args.extend(&color.short);
+        }
+        let mut args = args.into();
+        if self.info.response_files {
+            args = args.with_response_files();
+        }
         let mut err = None;
         let mut state = State::construct(args, &short_flags, &short_args, &mut err);
+        state.messages = self.info.messages.clone();
+        let style =
+            |err: ParseFailure, color| err.with_style(color, &self.info.messages, &self.info.theme);
 
         // this only handles disambiguation failure in construct
         if let Some(msg) = err {
-            return Err(msg.render(&state, &self.inner.meta()));
+            return Err(style(msg.render(&state, &meta), None));
+        }
+
+        // passthrough tail must be out of reach for every parser, including the ones that run
+        // before the passthrough parser itself
+        if let Some((start, commands)) = meta.passthrough_start(&state) {
+            let mut path = state.path.clone();
+            path.extend(commands);
+            state.reserve_tail(start, path);
+        }
+
+        let color = match self.info.mk_color_parser(&state.messages) {
+            Some(parser) => {
+                // `--color` belongs to the top level parser, subcommands can define their own
+                let scope = state.scope();
+                let end = meta.first_command(&state).unwrap_or(scope.end);
+                state.set_scope(scope.start..end);
+                let res = parser.eval(&mut state);
+                state.set_scope(scope);
+                match res {
+                    Ok(color) => Some(color),
+                    Err(Error(Message::Missing(_))) => None,
+                    Err(Error(err)) => return Err(style(err.render(&state, &meta), None)),
+                }
+            }
+            None => None,
+        };
+
+        #[cfg(feature = "prompt")]
+        if let Some(prompter) = &self.info.prompter {
+            // don't ask anything if user wants to see --help or --version
+            if self.info.eval(&mut state.clone()).is_err() {
+                return self
+                    .run_interactive(state, prompter)
+                    .map_err(|err| style(err, color));
+            }
         }
 
         self.run_subparser(&mut state)
+            .map_err(|err| style(err, color))
+    }
+
+    /// Run the parser asking for missing values, answers that fail validation are asked again
+    #[cfg(feature = "prompt")]
+    fn run_interactive(
+        &self,
+        mut state: State,
+        prompter: &PromptProvider,
+    ) -> Result<T, ParseFailure> {
+        let session = std::rc::Rc::new(Session::new(prompter.clone()));
+        state.prompt = Some(PromptState {
+            session: session.clone(),
+            probe: false,
+            defer: false,
+            deferred: false,
+            choices: None,
+        });
+        loop {
+            let res = self.run_subparser(&mut state.clone());
+            if let Err(failure) = &res {
+                if let (
+                    Some(
+                        ErrorKind::ParseFailed { index: None, .. }
+                        | ErrorKind::GuardFailed { index: None, .. },
+                    ),
+                    ParseFailure::Stderr(doc),
+                ) = (failure.error_kind(), failure)
+                {
+                    if session.retry(doc.monochrome(true)) {
+                        continue;
+                    }
+                }
+            }
+            return res;
+        }
     }
 
     /// Run subparser, implementation detail
@@ -232,18 +359,32 @@ impl<T> OptionParser<T> {
         //
         // outer parser gets value in ParseFailure format
 
+        if let Some(config) = &self.info.config {
+            args.config = Some(config.clone());
+        }
+        if self.info.messages.is_custom() {
+            args.messages = self.info.messages.clone();
+        }
+
         if self.info.help_if_no_args && args.is_empty() {
             let buffer = render_help(
                 &args.path,
                 &self.info,
-                &self.inner.meta(),
-                &self.info.meta(),
+                &self.documented_meta(),
+                &self.info.help_meta(&args.messages),
                 true,
+                &args.messages,
             );
             return Err(ParseFailure::Stdout(buffer, false));
         };
 
+        let outer = args.enter_subparser();
         let res = self.inner.eval(args);
+        #[cfg(feature = "autocomplete")]
+        let res = self.suggest_value(args, res);
+        args.release_tail();
+        args.leave_subparser(outer);
+
         if let Err(Error(Message::ParseFailure(failure))) = res {
             return Err(failure);
         }
@@ -272,17 +413,19 @@ impl<T> OptionParser<T> {
                     render_help(
                         &args.path,
                         &self.info,
-                        &self.inner.meta(),
-                        &self.info.meta(),
+                        &self.documented_meta(),
+                        &self.info.help_meta(&args.messages),
                         true,
+                        &args.messages,
                     )
                 }
                 ExtraParams::Version(v) => {
                     use crate::buffer::{Block, Token};
                     let mut buffer = Doc::default();
                     buffer.token(Token::BlockStart(Block::Block));
-                    buffer.text("Version: ");
-                    buffer.doc(&v);
+                    buffer.write_template(args.messages.get(Phrase::Version), |buffer, _| {
+                        buffer.doc(args.messages.pick(&v));
+                    });
                     buffer.token(Token::BlockEnd(Block::Block));
                     buffer
                 }
@@ -292,6 +435,45 @@ impl<T> OptionParser<T> {
         Err(err.render(args, &self.inner.meta()))
     }
 
+    /// Replace a value that failed to parse with a close match shell completion would offer
+    ///
+    /// Completion runs only after a failure, with the mistyped value left out
+    #[cfg(feature = "autocomplete")]
+    fn suggest_value(&self, args: &State, res: Result<T, Error>) -> Result<T, Error> {
+        let ix = match &res {
+            Err(Error(Message::ParseFailed(Some(ix), _))) if !args.is_comp() => *ix,
+            _ => return res,
+        };
+        let value = match args.items[ix].os_str().to_str() {
+            Some(value) => value,
+            None => return res,
+        };
+        let mut probe = match args.value_probe(ix) {
+            Some(probe) => probe,
+            None => return res,
+        };
+        // only completion candidates matter here, not the result
+        let _ = self.inner.eval(&mut probe);
+        let candidates = probe.value_candidates();
+        if suggest_choice(value, &candidates).is_none() {
+            return res;
+        }
+        let choices = candidates.into_iter().map(Cow::Owned).collect::<Vec<_>>();
+        let value = value.to_owned();
+        Err(Error(Message::InvalidChoice(
+            Some(ix),
+            value,
+            choices.into(),
+        )))
+    }
+
+    /// Parser metadata with all the help fetched, used to render documentation
+    pub(crate) fn documented_meta(&self) -> Meta {
+        let mut meta = self.inner.meta();
+        meta.fetch_help();
+        meta
+    }
+
     /// Get first line of description if Available
     ///
     /// Used internally to avoid duplicating description for [`command`].
@@ -611,7 +793,8 @@ impl<T> OptionParser<T> {
     /// Customize parser for `--help`
     ///
     /// By default `bpaf` displays help when program is called with either `--help` or `-h`, you
-    /// can customize those names and description in the help message
+    /// can customize those names and description in the help message. If `parser` has no
+    /// description `bpaf` uses the default one.
     ///
     /// Note, `--help` is something user expects to work
     #[cfg_attr(not(doctest), doc = include_str!("docs2/custom_help_version.md"))]
@@ -624,7 +807,8 @@ impl<T> OptionParser<T> {
     /// Customize parser for `--version`
     ///
     /// By default `bpaf` displays version information when program is called with either `--version`
-    /// or `-V` (and version is available), you can customize those names and description in the help message
+    /// or `-V` (and version is available), you can customize those names and description in the help message.
+    /// If `parser` has no description `bpaf` uses the default one.
     ///
     /// Note, `--version` is something user expects to work
     #[cfg_attr(not(doctest), doc = include_str!("docs2/custom_help_version.md"))]
@@ -634,6 +818,41 @@ impl<T> OptionParser<T> {
         self
     }
 
+    /// Let user pick when to use colors with an argument such as `--color[=WHEN]`
+    ///
+    /// Argument takes `auto`, `always` or `never`, bare name means `always`. The choice applies to
+    /// `--help` and error messages and overrides `NO_COLOR`, `CLICOLOR_FORCE` and terminal
+    /// detection. Only the top level parser handles this argument and only before the first
+    /// subcommand, so subcommands can define their own. If `parser` has no description `bpaf`
+    /// uses the default one.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = short('v')
+    ///     .help("Verbose output")
+    ///     .switch()
+    ///     .to_options()
+    ///     .color_arg(long("color"));
+    ///
+    /// assert_eq!(parser.run_inner(&["--color=never", "-v"]).unwrap(), true);
+    ///
+    /// let help = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// let expected = "\
+    /// Usage: [-v]
+    ///
+    /// Available options:
+    ///     -v                  Verbose output
+    ///         --color[=WHEN]  When to use colors: auto, always or never
+    ///     -h, --help          Prints help information
+    /// ";
+    /// assert_eq!(help, expected);
+    /// ```
+    #[must_use]
+    pub fn color_arg(mut self, parser: NamedArg) -> Self {
+        self.info.color_arg = Some(parser);
+        self
+    }
+
     /// Print help if app was called with no parameters
     ///
     /// By default `bpaf` tries to parse command line options and displays the best possible
@@ -665,42 +884,230 @@ impl<T> OptionParser<T> {
         self.info.max_width = width;
         self
     }
+
+    /// Fit help and error messages into the terminal width
+    ///
+    /// `bpaf` asks the terminal for its width on Linux with glibc and checks `COLUMNS`
+    /// environment variable if that fails or elsewhere, [`max_width`](OptionParser::max_width) stays the upper limit. On narrow
+    /// terminals the column with names of flags and arguments gets narrower as well.
+    /// Has no effect on output of [`run_inner`](OptionParser::run_inner), only on
+    /// [`run`](OptionParser::run).
+    #[must_use]
+    pub fn auto_width(mut self) -> Self {
+        self.info.auto_width = true;
+        self
+    }
+
+    /// Show long `--help` output through a pager
+    ///
+    /// When `stdout` is a terminal and help doesn't fit on the screen `bpaf` pipes it through the
+    /// program in `PAGER` environment variable or `less -R` if it's not set, colors are kept.
+    /// Detailed help from `--help --help` is paged as well. Only works on Linux with glibc at the moment and
+    /// only affects [`run`](OptionParser::run).
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = short('v').switch().to_options().pager(true);
+    /// ```
+    #[must_use]
+    pub fn pager(mut self, enabled: bool) -> Self {
+        self.info.pager = enabled;
+        self
+    }
+
+    /// Use translations for built-in help and error messages
+    ///
+    /// `bpaf` uses [`Locale`] to translate [phrases](crate::Phrase) it generates by itself and to
+    /// pick [translations](Doc::translation) of documentation you provide. Subcommands inherit
+    /// the locale from their parent unless they specify their own.
+    ///
+    /// Descriptions for `--help` and `--version` are translated unless you set them explicitly
+    /// with [`help_parser`](OptionParser::help_parser) or
+    /// [`version_parser`](OptionParser::version_parser).
+    ///
+    /// See [`Catalog`](crate::Catalog) for usage examples.
+    #[must_use]
+    pub fn locale<L>(mut self, locale: L) -> Self
+    where
+        L: Locale + 'static,
+    {
+        self.info.messages = Messages::new(locale);
+        self
+    }
+
+    /// Use custom colors for help and error messages
+    ///
+    /// Theme of the top level parser is used for the whole application, including
+    /// subcommands. Colors are only used when output goes to a terminal or `CLICOLOR_FORCE` is set,
+    /// `NO_COLOR` disables them. Without `color` feature `bpaf` can't tell if output goes to a
+    /// terminal and uses the theme only with `CLICOLOR_FORCE`. See [`Theme`](crate::Theme) for
+    /// details.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// use bpaf::doc::Style;
+    /// let theme = Theme::bright()
+    ///     .style(Style::Literal, "1;38;5;208")
+    ///     .style(Style::Invalid, "1;7;31");
+    /// let parser = short('v').help("Verbose output").switch().to_options().theme(theme);
+    /// ```
+    #[must_use]
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.info.theme = theme;
+        self
+    }
+
+    /// Use a configuration source for named items with [`config`](NamedArg::config) keys
+    ///
+    /// Config values are used when an item is absent from both the command line and the
+    /// environment, but before any [`fallback`](Parser::fallback). Subcommands inherit the
+    /// config source from their parent unless they specify their own.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = long("name")
+    ///     .env("APP_NAME")
+    ///     .config("user.name")
+    ///     .argument::<String>("NAME")
+    ///     .fallback("anonymous".to_owned())
+    ///     .to_options()
+    ///     .config_source(|key: &str| match key {
+    ///         "user.name" => Some("Bob".to_owned()),
+    ///         _ => None,
+    ///     });
+    ///
+    /// assert_eq!(parser.run_inner(&[]).unwrap(), "Bob");
+    /// assert_eq!(parser.run_inner(&["--name", "Alice"]).unwrap(), "Alice");
+    /// ```
+    #[must_use]
+    pub fn config_source<C>(mut self, source: C) -> Self
+    where
+        C: ConfigSource + 'static,
+    {
+        self.info.config = Some(ConfigProvider::new(source));
+        self
+    }
+
+    /// Ask user for missing values on a terminal
+    ///
+    /// When enabled and stdin is a terminal `bpaf` asks for values of items with a
+    /// [`prompt`](crate::parsers::ParseArgument::prompt) instead of failing. Values with
+    /// [`complete`](Parser::complete) candidates are offered as a numbered list to pick from.
+    /// Answers go through the usual validation with [`parse`](Parser::parse) and
+    /// [`guard`](Parser::guard), rejected answers are asked for again. Nothing is asked when user
+    /// passes `--help` or `--version`.
+    ///
+    /// Set this on the top level parser, subcommands share its setting. See
+    /// [`prompter`](OptionParser::prompter) to ask for values some other way.
+    ///
+    /// Terminal detection requires Rust 1.70 or newer.
+    #[cfg(feature = "prompt")]
+    #[must_use]
+    pub fn interactive(mut self, enabled: bool) -> Self {
+        self.info.prompter = if enabled {
+            Some(PromptProvider::new(crate::prompt::Terminal))
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Ask user for missing values using a custom [`Prompter`]
+    ///
+    /// Same as [`interactive`](OptionParser::interactive) but values are obtained from a
+    /// custom prompter, this is useful for GUI frontends and tests.
+    #[cfg(feature = "prompt")]
+    #[must_use]
+    pub fn prompter<P>(mut self, prompter: P) -> Self
+    where
+        P: Prompter + 'static,
+    {
+        self.info.prompter = Some(PromptProvider::new(prompter));
+        self
+    }
+
+    /// Expand `@path` arguments with contents of response files
+    ///
+    /// Useful for tools that might run into command line length limits, see
+    /// [`Args::with_response_files`] for file format details.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = short('v').switch().to_options().response_files(true);
+    /// let r = parser.run_inner(&["@no_such_file.txt"]).unwrap_err().unwrap_stderr();
+    /// assert!(r.starts_with("couldn't read response file `no_such_file.txt`: "));
+    /// ```
+    #[must_use]
+    pub fn response_files(mut self, enabled: bool) -> Self {
+        self.info.response_files = enabled;
+        self
+    }
 }
 
 impl Info {
     #[inline(never)]
-    fn mk_help_parser(&self) -> impl Parser<()> {
-        self.help_arg.clone().req_flag(())
+    fn mk_help_parser(&self, messages: &Messages) -> impl Parser<()> {
+        let mut arg = self.help_arg.clone();
+        if arg.help.is_none() {
+            arg.help = Some(messages.get(Phrase::HelpDescription).into());
+        }
+        arg.req_flag(())
     }
     #[inline(never)]
-    fn mk_version_parser(&self) -> impl Parser<()> {
-        self.version_arg.clone().req_flag(())
+    fn mk_version_parser(&self, messages: &Messages) -> impl Parser<()> {
+        let mut arg = self.version_arg.clone();
+        if arg.help.is_none() {
+            arg.help = Some(messages.get(Phrase::VersionDescription).into());
+        }
+        arg.req_flag(())
+    }
+
+    #[inline(never)]
+    fn mk_color_parser(&self, messages: &Messages) -> Option<ParseOptionalValue<ColorChoice>> {
+        let mut arg = self.color_arg.clone()?;
+        if arg.help.is_none() {
+            arg.help = Some(messages.get(Phrase::ColorDescription).into());
+        }
+        Some(arg.optional_value("WHEN", ColorChoice::Always))
+    }
+
+    /// Meta for `--color`, `--help` and `--version` with descriptions in a given locale
+    pub(crate) fn help_meta(&self, messages: &Messages) -> Meta {
+        let mut items = Vec::new();
+        if let Some(color) = self.mk_color_parser(messages) {
+            items.push(color.meta());
+        }
+        items.push(self.mk_help_parser(messages).meta());
+        if self.version.is_some() {
+            items.push(self.mk_version_parser(messages).meta());
+        }
+        if items.len() == 1 {
+            items.pop().unwrap()
+        } else {
+            Meta::And(items)
+        }
     }
 }
 
 impl Parser<ExtraParams> for Info {
     fn eval(&self, args: &mut State) -> Result<ExtraParams, Error> {
-        let help = self.mk_help_parser();
+        let help = self.mk_help_parser(&self.messages);
         if help.eval(args).is_ok() {
             return Ok(ExtraParams::Help(help.eval(args).is_ok()));
         }
 
         if let Some(version) = &self.version {
-            if self.mk_version_parser().eval(args).is_ok() {
+            if self.mk_version_parser(&self.messages).eval(args).is_ok() {
                 return Ok(ExtraParams::Version(version.clone()));
             }
         }
 
         // error message is not actually used anywhere
-        Err(Error(Message::ParseFail("not a version or help")))
+        Err(Error(Message::ParseFail("not a version or help".into())))
     }
 
     fn meta(&self) -> Meta {
-        let help = self.mk_help_parser().meta();
-        match &self.version {
-            Some(_) => Meta::And(vec![help, self.mk_version_parser().meta()]),
-            None => help,
-        }
+        self.help_meta(&self.messages)
     }
 }
 
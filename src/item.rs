@@ -1,3 +1,5 @@
+use std::{borrow::Cow, rc::Rc};
+
 use crate::{info::Info, meta_help::Metavar, parsers::NamedArg, Doc, Meta};
 
 #[doc(hidden)]
@@ -11,13 +13,22 @@ pub enum Item {
     },
     /// Positional item, consumed from the the front of the arguments
     /// <FILE>
-    Positional { metavar: Metavar, help: Option<Doc> },
+    Positional {
+        metavar: Metavar,
+        help: Option<Doc>,
+        /// valid values, empty if any value is accepted
+        choices: Rc<[Cow<'static, str>]>,
+        /// takes this item and everything after it, see `stop_parsing`
+        passthrough: bool,
+    },
     Command {
-        name: &'static str,
+        name: Cow<'static, str>,
         short: Option<char>,
         help: Option<Doc>,
         meta: Box<Meta>,
         info: Box<Info>,
+        /// help that is expensive to get, fetched only when documentation is rendered
+        lazy_help: Option<LazyHelp>,
     },
     /// short or long name, consumed anywhere
     /// -f
@@ -26,8 +37,11 @@ pub enum Item {
         name: ShortLong,
         /// used for disambiguation
         shorts: Vec<char>,
-        env: Option<&'static str>,
+        env: Option<Cow<'static, str>>,
+        config: Option<Cow<'static, str>>,
         help: Option<Doc>,
+        /// long names are also accepted with `no-` prefix: `--[no-]color`
+        negatable: bool,
     },
     /// Short or long name followed by a value, consumed anywhere
     /// -f <VAL>
@@ -37,11 +51,37 @@ pub enum Item {
         /// used for disambiguation
         shorts: Vec<char>,
         metavar: Metavar,
-        env: Option<&'static str>,
+        env: Option<Cow<'static, str>>,
+        config: Option<Cow<'static, str>>,
         help: Option<Doc>,
+        /// valid values, empty if any value is accepted
+        choices: Rc<[Cow<'static, str>]>,
+        /// `choices` restrict the key of a `KEY=VALUE` pair rather than the whole value
+        choice_keys: bool,
+        /// value can be omitted: `--color[=WHEN]`
+        optional_value: bool,
     },
 }
 
+/// Help for an item computed on demand, see [`Meta::fetch_help`]
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct LazyHelp(pub(crate) Rc<dyn Fn() -> Option<Doc>>);
+
+impl std::fmt::Debug for LazyHelp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LazyHelp")
+    }
+}
+
+impl std::fmt::Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut doc = Doc::default();
+        doc.write_item(self);
+        f.write_str(&doc.monochrome(false))
+    }
+}
+
 impl Item {
     pub(crate) fn is_pos(&self) -> bool {
         match self {
@@ -60,15 +100,15 @@ impl Item {
 }
 
 #[doc(hidden)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub enum ShortLong {
     Short(char),
-    Long(&'static str),
-    Both(char, &'static str),
+    Long(Cow<'static, str>),
+    Both(char, Cow<'static, str>),
 }
 
 impl ShortLong {
-    pub(crate) fn as_long(&self) -> Option<&'static str> {
+    pub(crate) fn as_long(&self) -> Option<&str> {
         match self {
             ShortLong::Long(l) | ShortLong::Both(_, l) => Some(l),
             ShortLong::Short(_) => None,
@@ -109,7 +149,7 @@ impl ShortLong {
                 if short {
                     *self = Self::Short(*s);
                 } else {
-                    *self = Self::Long(l);
+                    *self = Self::Long(l.clone());
                 }
             }
         }
@@ -122,9 +162,9 @@ impl TryFrom<&NamedArg> for ShortLong {
     fn try_from(named: &NamedArg) -> Result<Self, Self::Error> {
         match (named.short.is_empty(), named.long.is_empty()) {
             (true, true) => Err(()),
-            (true, false) => Ok(Self::Long(named.long[0])),
+            (true, false) => Ok(Self::Long(named.long[0].clone())),
             (false, true) => Ok(Self::Short(named.short[0])),
-            (false, false) => Ok(Self::Both(named.short[0], named.long[0])),
+            (false, false) => Ok(Self::Both(named.short[0], named.long[0].clone())),
         }
     }
 }
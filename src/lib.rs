@@ -117,6 +117,8 @@
 //! This requires enabling **autocomplete** cargo feature.
 //!
 //! - [`complete`](Parser::complete) and [`complete_shell`](Parser::complete_shell)
+//! - [`render_static_completion`](OptionParser::render_static_completion) generates completion
+//!   scripts that don't need to call the app for every keypress
 //!
 //! And finally you can generate documentation for command line in markdown, html and manpage
 //! formats using [`render_markdown`](OptionParser::render_markdown),
@@ -155,7 +157,10 @@
 //!    ```
 //!    Disabled by default.
 //!
-//!  - `docgen`: generate documentation from help declaration, see [`OptionParser::render_markdown`] and [`doc`](crate::doc). Disabled by default.
+//!  - `docgen`: generate documentation from help declaration, see [`OptionParser::render_markdown`] and [`doc`](crate::doc), or describe it as JSON with [`OptionParser::render_json_schema`] and compare versions with [`OptionParser::check_compatibility`]. Disabled by default.
+//!
+//!  - `prompt`: ask user for missing values on a terminal, see [`OptionParser::interactive`].
+//!    Raises MSRV to 1.70. Disabled by default.
 
 
 
@@ -169,21 +174,35 @@ mod args;
 #[cfg(feature = "batteries")]
 pub mod batteries;
 mod buffer;
+#[cfg(feature = "docgen")]
+mod compat;
 #[cfg(feature = "autocomplete")]
 mod complete_gen;
 #[cfg(feature = "autocomplete")]
 mod complete_run;
 #[cfg(feature = "autocomplete")]
 mod complete_shell;
+#[cfg(feature = "autocomplete")]
+mod complete_static;
+mod config;
 pub mod doc;
 mod error;
+mod external;
 mod from_os_str;
 mod info;
 mod item;
+mod locale;
 mod meta;
 mod meta_help;
 mod meta_youmean;
+mod pager;
 pub mod params;
+#[cfg(feature = "prompt")]
+mod prompt;
+mod relation;
+mod response;
+#[cfg(feature = "docgen")]
+mod schema;
 mod structs;
 #[cfg(test)]
 mod tests;
@@ -195,49 +214,84 @@ pub mod parsers {
     //! access to documentation
     #[cfg(feature = "autocomplete")]
     #[doc(inline)]
-    pub use crate::complete_shell::ParseCompShell;
+    pub use crate::complete_shell::{ParseCompDelegate, ParseCompShell};
+    #[doc(inline)]
+    pub use crate::external::ParseExternal;
     #[doc(inline)]
     pub use crate::params::{
-        NamedArg, ParseAny, ParseArgument, ParseCommand, ParseFlag, ParsePositional,
+        NamedArg, ParseAny, ParseArgument, ParseCommand, ParseFlag, ParseKeyValue,
+        ParseOptionalValue, ParsePositional, ParseStopParsing,
     };
     #[doc(inline)]
+    pub use crate::relation::{ParseOneOf, ParseRelation};
+    #[cfg(feature = "autocomplete")]
+    #[doc(inline)]
+    pub use crate::structs::ParseComp;
+    #[doc(inline)]
     pub use crate::structs::{
         ParseCollect, ParseCon, ParseCount, ParseFallback, ParseFallbackWith, ParseLast, ParseMany,
-        ParseOptional, ParseSome,
+        ParseOptional, ParseSome, ParseWithSource,
     };
 }
 
 // -------------------------------------------------------------------
 
 #[doc(inline)]
-pub use crate::{args::Args, buffer::Doc, error::ParseFailure, info::OptionParser};
+pub use crate::{
+    args::Args,
+    buffer::{ColorChoice, Doc, Theme},
+    config::ConfigSource,
+    error::{ErrorKind, KeyValuePart, ParseFailure},
+    external::ExternalCommand,
+    info::OptionParser,
+    locale::{Catalog, Locale, Phrase},
+    params::Choice,
+    structs::ValueSource,
+};
 
 #[doc(hidden)]
 // used by construct macro, not part of public API
 pub use crate::{args::State, error::Error, meta::Meta, structs::ParseCon};
 
-use std::{marker::PhantomData, str::FromStr};
+#[doc(hidden)]
+// reachable from ErrorKind::Expected, not part of public API beyond Display and Debug
+pub use crate::item::Item;
+
+use std::{borrow::Cow, marker::PhantomData, str::FromStr};
 
 use crate::{
     buffer::{MetaInfo, Style},
-    item::Item,
+    external::build_external,
     params::build_positional,
-    parsers::{NamedArg, ParseAny, ParseCommand, ParsePositional},
+    parsers::{NamedArg, ParseAny, ParseCommand, ParseExternal, ParsePositional},
+    relation::{ParseOneOf, ParseRelation},
     structs::{
         ParseCollect, ParseCount, ParseFail, ParseFallback, ParseFallbackWith, ParseGroupHelp,
         ParseGuard, ParseHide, ParseLast, ParseMany, ParseMap, ParseOptional, ParseOrElse,
         ParsePure, ParsePureWith, ParseSome, ParseUsage, ParseWith, ParseWithGroupHelp,
+        ParseWithSource,
     },
 };
 
 #[cfg(feature = "autocomplete")]
 pub use crate::complete_shell::ShellComp;
+
+#[cfg(feature = "docgen")]
+#[doc(inline)]
+pub use crate::compat::{ChangeKind, CliChange, Severity};
+
+#[cfg(feature = "autocomplete")]
+pub use crate::complete_static::CompletionShell;
+
+#[cfg(feature = "prompt")]
+#[doc(inline)]
+pub use crate::prompt::{PromptRequest, Prompter};
 #[cfg(feature = "autocomplete")]
 use structs::ParseComp;
 
 #[doc(inline)]
 #[cfg(feature = "bpaf_derive")]
-pub use bpaf_derive::Bpaf;
+pub use bpaf_derive::{Bpaf, Choice};
 
 /// Compose several parsers to produce a single result
 ///
@@ -681,13 +735,13 @@ pub trait Parser<T> {
     /// structure
 
     #[must_use]
-    fn some(self, message: &'static str) -> ParseSome<Self>
+    fn some(self, message: impl Into<Cow<'static, str>>) -> ParseSome<Self>
     where
         Self: Sized + Parser<T>,
     {
         ParseSome {
             inner: self,
-            message,
+            message: message.into(),
             catch: false,
         }
     }
@@ -828,7 +882,7 @@ pub trait Parser<T> {
     #[cfg_attr(not(doctest), doc = include_str!("docs2/guard.md"))]
     ///
     #[must_use]
-    fn guard<F>(self, check: F, message: &'static str) -> ParseGuard<Self, F>
+    fn guard<F>(self, check: F, message: impl Into<Cow<'static, str>>) -> ParseGuard<Self, F>
     where
         Self: Sized + Parser<T>,
         F: Fn(&T) -> bool,
@@ -836,11 +890,295 @@ pub trait Parser<T> {
         ParseGuard {
             inner: self,
             check,
-            message,
+            message: message.into(),
+        }
+    }
+    // }}}
+
+    // {{{ requires
+    /// Fail if this parser is present on the command line and the other one is not
+    ///
+    /// A parser is present if it consumes something from the command line, values coming from
+    /// environment variables or fallbacks don't count. Relation is shown in the `--help` output.
+    /// Check works best with named items: positional items can't tell which word belongs to
+    /// them when looked at on their own.
+    ///
+    /// # Combinatoric usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// #[derive(Debug, Clone)]
+    /// struct Options {
+    ///     sign: bool,
+    ///     key: Option<String>,
+    /// }
+    ///
+    /// fn options() -> OptionParser<Options> {
+    ///     let key = || long("key").help("Signing key").argument::<String>("KEY");
+    ///     let sign = long("sign").help("Sign the release").switch().requires(key());
+    ///     let key = key().optional();
+    ///     construct!(Options { sign, key }).to_options()
+    /// }
+    ///
+    /// let err = options().run_inner(&["--sign"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(err, "`--sign` requires `--key=KEY`");
+    /// assert!(options().run_inner(&["--sign", "--key", "k"]).is_ok());
+    /// ```
+    ///
+    /// # Derive usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// fn key() -> impl Parser<String> {
+    ///     long("key").argument("KEY")
+    /// }
+    ///
+    /// #[derive(Debug, Clone, Bpaf)]
+    /// #[bpaf(options)]
+    /// struct Options {
+    ///     /// Sign the release
+    ///     #[bpaf(requires(key()))]
+    ///     sign: bool,
+    ///     #[bpaf(external(key), optional)]
+    ///     key: Option<String>,
+    /// }
+    /// ```
+    #[must_use]
+    fn requires<Q, U>(self, other: Q) -> ParseRelation<Self>
+    where
+        Self: Sized + Parser<T>,
+        Q: Parser<U> + 'static,
+        U: 'static,
+    {
+        ParseRelation::requires(self, other)
+    }
+    // }}}
+
+    // {{{ conflicts_with
+    /// Fail if both this parser and the other one are present on the command line
+    ///
+    /// Error points at whichever of the two items comes second. See
+    /// [`requires`](Parser::requires) for what counts as present.
+    ///
+    /// # Combinatoric usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// fn options() -> OptionParser<(bool, bool)> {
+    ///     let quiet = || short('q').long("quiet").help("Print nothing").switch();
+    ///     let verbose = short('v')
+    ///         .long("verbose")
+    ///         .help("Print more")
+    ///         .switch()
+    ///         .conflicts_with(quiet());
+    ///     construct!(verbose, quiet()).to_options()
+    /// }
+    ///
+    /// let err = options().run_inner(&["-q", "-v"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(err, "`-v` cannot be used at the same time as `-q`");
+    /// ```
+    ///
+    /// # Derive usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// fn quiet() -> impl Parser<bool> {
+    ///     short('q').long("quiet").switch()
+    /// }
+    ///
+    /// #[derive(Debug, Clone, Bpaf)]
+    /// struct Options {
+    ///     #[bpaf(short, long, conflicts_with(quiet()))]
+    ///     verbose: bool,
+    ///     #[bpaf(external)]
+    ///     quiet: bool,
+    /// }
+    /// ```
+    #[must_use]
+    fn conflicts_with<Q, U>(self, other: Q) -> ParseRelation<Self>
+    where
+        Self: Sized + Parser<T>,
+        Q: Parser<U> + 'static,
+        U: 'static,
+    {
+        ParseRelation::conflicts_with(self, other)
+    }
+    // }}}
+
+    // {{{ required_if
+    /// Fail if the condition holds and this parser is not present on the command line
+    ///
+    /// Condition is a parser that produces `true` when this parser is needed, usually a
+    /// [`switch`](NamedArg::switch). Use it on parsers that can succeed without consuming
+    /// anything, such as [`optional`](Parser::optional) or [`fallback`](Parser::fallback)
+    /// ones. See [`requires`](Parser::requires) for what counts as present.
+    ///
+    /// # Combinatoric usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// fn options() -> OptionParser<(bool, Option<String>)> {
+    ///     let sign = || long("sign").help("Sign the release").switch();
+    ///     let key = long("key")
+    ///         .help("Signing key")
+    ///         .argument::<String>("KEY")
+    ///         .optional()
+    ///         .required_if(sign());
+    ///     let sign = sign();
+    ///     construct!(sign, key).to_options()
+    /// }
+    ///
+    /// let err = options().run_inner(&["--sign"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(err, "`--sign` requires `--key=KEY`");
+    /// ```
+    ///
+    /// # Derive usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// fn signing() -> impl Parser<bool> {
+    ///     long("sign").switch()
+    /// }
+    ///
+    /// #[derive(Debug, Clone, Bpaf)]
+    /// struct Options {
+    ///     #[bpaf(external(signing))]
+    ///     sign: bool,
+    ///     #[bpaf(argument("KEY"), optional, required_if(signing()))]
+    ///     key: Option<String>,
+    /// }
+    /// ```
+    #[must_use]
+    fn required_if<Q>(self, cond: Q) -> ParseRelation<Self>
+    where
+        Self: Sized + Parser<T>,
+        Q: Parser<bool> + 'static,
+    {
+        ParseRelation::required_if(self, cond)
+    }
+    // }}}
+
+    // {{{ at_least_one_of
+    /// Fail unless at least one of the items inside of this parser is present on the command line
+    ///
+    /// Useful with a [`construct!`] of several optional items, accepts any combination of them
+    /// as long as there's at least one. Items that get their values from an environment
+    /// variable or a [config source](OptionParser::config_source) count as present.
+    ///
+    /// # Combinatoric usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// fn options() -> OptionParser<(bool, bool)> {
+    ///     let build = long("build").switch();
+    ///     let test = long("test").switch();
+    ///     construct!(build, test).at_least_one_of().to_options()
+    /// }
+    ///
+    /// let err = options().run_inner(&[]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(err, "expected `--build` or `--test`, pass `--help` for usage information");
+    /// assert_eq!(options().run_inner(&["--build", "--test"]).unwrap(), (true, true));
+    /// ```
+    ///
+    /// # Derive usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// #[derive(Debug, Clone, Bpaf)]
+    /// #[bpaf(at_least_one_of)]
+    /// struct Steps {
+    ///     build: bool,
+    ///     test: bool,
+    /// }
+    /// ```
+    #[must_use]
+    fn at_least_one_of(self) -> ParseOneOf<Self>
+    where
+        Self: Sized + Parser<T>,
+    {
+        ParseOneOf {
+            inner: self,
+            exactly: false,
+        }
+    }
+    // }}}
+
+    // {{{ exactly_one_of
+    /// Fail unless exactly one of the named items inside of this parser is present on the
+    /// command line
+    ///
+    /// Similar to [`at_least_one_of`](Parser::at_least_one_of), but a second item is rejected
+    /// with an error pointing at both of them. Unlike alternatives in [`construct!`] items can
+    /// have different types and all of them are parsed. Values from environment variables and
+    /// config sources count as well: `--file` together with an environment variable for
+    /// `--stdin` is rejected.
+    ///
+    /// # Combinatoric usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// fn options() -> OptionParser<(Option<String>, bool)> {
+    ///     let file = long("file").argument::<String>("FILE").optional();
+    ///     let stdin = long("stdin").switch();
+    ///     construct!(file, stdin).exactly_one_of().to_options()
+    /// }
+    ///
+    /// let err = options()
+    ///     .run_inner(&["--file", "a", "--stdin"])
+    ///     .unwrap_err()
+    ///     .unwrap_stderr();
+    /// assert_eq!(err, "`--stdin` cannot be used at the same time as `--file`");
+    /// ```
+    ///
+    /// # Derive usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// #[derive(Debug, Clone, Bpaf)]
+    /// #[bpaf(exactly_one_of)]
+    /// struct Input {
+    ///     #[bpaf(argument("FILE"))]
+    ///     file: Option<String>,
+    ///     stdin: bool,
+    /// }
+    /// ```
+    #[must_use]
+    fn exactly_one_of(self) -> ParseOneOf<Self>
+    where
+        Self: Sized + Parser<T>,
+    {
+        ParseOneOf {
+            inner: self,
+            exactly: true,
         }
     }
     // }}}
 
+    // {{{ with_source
+    /// Pair the value with a description of where it came from
+    ///
+    /// Source can be a command line argument along with its position and spelling, an
+    /// environment variable, a [config source](OptionParser::config_source) key or a
+    /// [`fallback`](Parser::fallback) value. This is useful for `--print-config` style debugging
+    /// or audit logs. Apply `with_source` after `fallback` to see fallback values as such.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = long("verbose")
+    ///     .short('v')
+    ///     .env("APP_VERBOSE")
+    ///     .argument::<u8>("LEVEL")
+    ///     .fallback(1)
+    ///     .with_source()
+    ///     .to_options();
+    ///
+    /// let (level, source) = parser.run_inner(&["-v", "3"]).unwrap();
+    /// assert_eq!(level, 3);
+    /// assert_eq!(source, ValueSource::Argv { index: 0, spelling: "-v".to_owned() });
+    ///
+    /// let (level, source) = parser.run_inner(&[]).unwrap();
+    /// assert_eq!(level, 1);
+    /// assert_eq!(source, ValueSource::Fallback);
+    /// ```
+    #[must_use]
+    fn with_source(self) -> ParseWithSource<Self>
+    where
+        Self: Sized + Parser<T>,
+    {
+        ParseWithSource { inner: self }
+    }
+    // }}}
+
     // combine
     // {{{ fallback
     /// Use this value as default if the value isn't present on a command line
@@ -863,7 +1201,7 @@ pub trait Parser<T> {
         ParseFallback {
             inner: self,
             value,
-            value_str: String::new(),
+            value_str: None,
         }
     }
     // }}}
@@ -891,7 +1229,7 @@ pub trait Parser<T> {
             inner: self,
             inner_res: PhantomData,
             fallback,
-            value_str: String::new(),
+            value_str: None,
             err: PhantomData,
         }
     }
@@ -1077,6 +1415,10 @@ pub trait Parser<T> {
     /// string if a value isn't available yet so it's best to run `complete` where parsing can't fail:
     /// right after [`argument`](NamedArg::argument) or [`positional`], but this isn't enforced.
     ///
+    /// When a value fails to parse, `bpaf` calls the completer with a value parsed from an empty
+    /// string and suggests the closest candidate in the error message, if there's one. Use
+    /// [`suggest_with`](crate::parsers::ParseComp::suggest_with) for values that can't be parsed from it.
+    ///
     /// # Example
     /// ```console
     /// $ app --name L<TAB>
@@ -1102,8 +1444,9 @@ pub trait Parser<T> {
     {
         ParseComp {
             inner: self,
-            op,
+            op: std::rc::Rc::new(op),
             group: None,
+            suggest: None,
         }
     }
     // }}}
@@ -1158,6 +1501,60 @@ pub trait Parser<T> {
     }
     // }}}
 
+    // {{{ complete_delegate
+    /// Delegate shell completion to another `bpaf` based program
+    ///
+    /// Use this for parsers that forward the rest of the command line to a different program,
+    /// such as wrappers or `cargo` style helpers. When the item being completed belongs to this
+    /// parser `bpaf` runs `program` in completion mode with all the words this parser consumed
+    /// and merges candidates it produces with the rest of the completion.
+    ///
+    /// `program` is looked up in `PATH` unless it's a path.
+    ///
+    /// # Example
+    /// ```console
+    /// $ app check --rel<TAB>
+    /// $ app check --release
+    /// ```
+    ///
+    /// # Combinatoric usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// # use std::ffi::OsString;
+    /// fn check() -> impl Parser<Vec<OsString>> {
+    ///     let args = any::<OsString, _, _>("ARG", Some)
+    ///         .help("Arguments passed to the checker")
+    ///         .many();
+    ///     args.complete_delegate("checker")
+    /// }
+    /// ```
+    ///
+    /// # Derive usage
+    /// ```rust
+    /// # use bpaf::*;
+    /// # use std::ffi::OsString;
+    /// #[derive(Debug, Clone, Bpaf)]
+    /// struct Options {
+    ///     /// Arguments passed to the checker
+    ///     #[bpaf(any("ARG", Some), many, complete_delegate("checker"))]
+    ///     args: Vec<OsString>,
+    /// }
+    /// ```
+    #[cfg(feature = "autocomplete")]
+    fn complete_delegate(
+        self,
+        program: impl Into<std::ffi::OsString>,
+    ) -> crate::complete_shell::ParseCompDelegate<Self>
+    where
+        Self: Sized + Parser<T>,
+    {
+        crate::complete_shell::ParseCompDelegate {
+            inner: self,
+            program: program.into(),
+        }
+    }
+    // }}}
+
     // consume
     // {{{ to_options
     /// Transform `Parser` into [`OptionParser`] to get ready to [`run`](OptionParser::run) it
@@ -1283,9 +1680,9 @@ where
 /// // succeeds
 /// ```
 #[must_use]
-pub fn fail<T>(msg: &'static str) -> ParseFail<T> {
+pub fn fail<T>(msg: impl Into<Cow<'static, str>>) -> ParseFail<T> {
     ParseFail {
-        field1: msg,
+        field1: msg.into(),
         field2: PhantomData,
     }
 }
@@ -1301,6 +1698,7 @@ pub fn short(short: char) -> NamedArg {
     NamedArg {
         short: vec![short],
         env: Vec::new(),
+        config: Vec::new(),
         long: Vec::new(),
         help: None,
     }
@@ -1312,13 +1710,17 @@ pub fn short(short: char) -> NamedArg {
 /// [`env`](NamedArg::env()) for multiple names. You can specify multiple names of the same type,
 ///  `bpaf` would use items past the first one as hidden aliases.
 ///
+/// Names, metavariables and command names can be either `&'static str` or `String`, so parsers
+/// can be built from information only available at runtime.
+///
 #[cfg_attr(not(doctest), doc = include_str!("docs2/short_long_env.md"))]
 #[must_use]
-pub fn long(long: &'static str) -> NamedArg {
+pub fn long(long: impl Into<Cow<'static, str>>) -> NamedArg {
     NamedArg {
         short: Vec::new(),
-        long: vec![long],
+        long: vec![long.into()],
         env: Vec::new(),
+        config: Vec::new(),
         help: None,
     }
 }
@@ -1343,12 +1745,13 @@ pub fn long(long: &'static str) -> NamedArg {
 ///
 #[cfg_attr(not(doctest), doc = include_str!("docs2/short_long_env.md"))]
 #[must_use]
-pub fn env(variable: &'static str) -> NamedArg {
+pub fn env(variable: impl Into<Cow<'static, str>>) -> NamedArg {
     NamedArg {
         short: Vec::new(),
         long: Vec::new(),
         help: None,
-        env: vec![variable],
+        env: vec![variable.into()],
+        config: Vec::new(),
     }
 }
 
@@ -1396,18 +1799,61 @@ pub fn env(variable: &'static str) -> NamedArg {
 /// [`strict`](ParsePositional::strict).
 #[cfg_attr(not(doctest), doc = include_str!("docs2/positional.md"))]
 #[must_use]
-pub fn positional<T>(metavar: &'static str) -> ParsePositional<T> {
-    build_positional(metavar)
+pub fn positional<T>(metavar: impl Into<Cow<'static, str>>) -> ParsePositional<T> {
+    build_positional(metavar.into())
+}
+
+/// Parse a subcommand implemented by an external executable, `git` and `cargo` style
+///
+/// Parser looks for executables in `PATH` with names starting with `prefix` and accepts
+/// them as subcommands: with prefix `"myapp-"` executable `myapp-frob` can be invoked as
+/// `myapp frob`. On success parser consumes all the remaining items and produces an
+/// [`ExternalCommand`] with the rest of the command line, your app decides how to run it.
+///
+/// Discovered subcommands are listed in `--help`, descriptions are taken from the first line of
+/// their `--help` output and only fetched when help is rendered, executables that take longer
+/// than half a second to answer are listed without a description. When dynamic completion is
+/// enabled completion of everything after the subcommand name is delegated to the executable.
+///
+/// ```rust,no_run
+/// # use bpaf::*;
+/// #[derive(Debug, Clone)]
+/// enum Cmd {
+///     Build(bool),
+///     Plugin(ExternalCommand),
+/// }
+///
+/// fn cmd() -> impl Parser<Cmd> {
+///     let build = short('r')
+///         .switch()
+///         .map(Cmd::Build)
+///         .to_options()
+///         .command("build");
+///     let plugin = external_commands("myapp-").map(Cmd::Plugin);
+///     construct!([build, plugin])
+/// }
+///
+/// match cmd().run() {
+///     Cmd::Build(release) => println!("release build: {}", release),
+///     Cmd::Plugin(plugin) => {
+///         let status = plugin.command().status().unwrap();
+///         std::process::exit(status.code().unwrap_or(1));
+///     }
+/// }
+/// ```
+#[must_use]
+pub fn external_commands(prefix: impl Into<Cow<'static, str>>) -> ParseExternal {
+    build_external(prefix.into())
 }
 
 #[doc(hidden)]
 #[deprecated = "You should switch from command(name, sub) to sub.command(name)"]
-pub fn command<T>(name: &'static str, subparser: OptionParser<T>) -> ParseCommand<T>
+pub fn command<T>(name: impl Into<Cow<'static, str>>, subparser: OptionParser<T>) -> ParseCommand<T>
 where
     T: 'static,
 {
     ParseCommand {
-        longs: vec![name],
+        longs: vec![name.into()],
         shorts: Vec::new(),
         help: subparser.short_descr().map(Into::into),
         subparser,
@@ -1485,9 +1931,10 @@ where
 /// [`any`] - a generic version of `literal` that uses function to decide if value is to be parsed
 /// or not.
 #[must_use]
-pub fn literal(val: &'static str) -> ParseAny<()> {
-    any("", move |s: String| if s == val { Some(()) } else { None })
-        .metavar(&[(val, crate::buffer::Style::Literal)][..])
+pub fn literal(val: impl Into<Cow<'static, str>>) -> ParseAny<()> {
+    let val = val.into();
+    let metavar = Doc::from(&[(val.as_ref(), crate::buffer::Style::Literal)][..]);
+    any("", move |s: String| if s == val { Some(()) } else { None }).metavar(metavar)
 }
 
 /// Strip a command name if present at the front when used as a `cargo` command
@@ -1495,7 +1942,7 @@ pub fn literal(val: &'static str) -> ParseAny<()> {
 // this is exactly the same as batteries::cargo_helper, but used by derive macro...
 #[must_use]
 #[doc(hidden)]
-pub fn cargo_helper<P, T>(cmd: &'static str, parser: P) -> impl Parser<T>
+pub fn cargo_helper<P, T>(cmd: impl Into<Cow<'static, str>>, parser: P) -> impl Parser<T>
 where
     T: 'static,
     P: Parser<T>,
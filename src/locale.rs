@@ -0,0 +1,376 @@
+//! Translations for built-in help and error messages, see [`OptionParser::locale`]
+
+use std::{collections::HashMap, rc::Rc};
+
+use crate::Doc;
+
+#[cfg(doc)]
+use crate::OptionParser;
+
+/// Built-in phrase `bpaf` uses in help and error messages
+///
+/// Phrases are templates: parts in curly braces such as `{arg}` are placeholders `bpaf`
+/// replaces with actual values, translation can move them around but should keep all of
+/// them. See [`default_text`](Phrase::default_text) for placeholders each phrase uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Phrase {
+    /// `Prints help information`
+    HelpDescription,
+    /// `Prints version information`
+    VersionDescription,
+    /// `When to use colors: auto, always or never`
+    ColorDescription,
+    /// `Version: {version}`
+    Version,
+    /// `Usage`
+    Usage,
+    /// `Available positional items:`
+    PositionalItems,
+    /// `Available options:`
+    Options,
+    /// `Available commands:`
+    Commands,
+    /// `[default: {value}]`
+    Default,
+    /// `[possible values: {values}]`
+    PossibleValues,
+    /// `[possible keys: {values}]`, for `KEY=VALUE` pairs
+    PossibleKeys,
+    /// `[requires: {other}]`
+    RequiresNote,
+    /// `[conflicts with: {other}]`
+    ConflictsNote,
+    /// `[required if: {other}]`
+    RequiredIfNote,
+    /// `[at least one of: {items}]`
+    AtLeastOneNote,
+    /// `[exactly one of: {items}]`
+    ExactlyOneNote,
+    /// `[env:{name}: set]`
+    EnvSet,
+    /// `[env:{name}: not set]`
+    EnvNotSet,
+    /// `[env:{name} = {value}]`
+    EnvValue,
+    /// `[env:{name}: N/A]`
+    EnvNotAvailable,
+    /// `Uses environment variable {name}`
+    UsesEnv,
+    /// `[config:{key}]`
+    ConfigKey,
+    /// `Uses config key {key}`
+    UsesConfig,
+    /// `NAME`, manpage section
+    ManName,
+    /// `SYNOPSIS`, manpage section
+    ManSynopsis,
+    /// `OPTIONS`, manpage section
+    ManOptions,
+    /// `COMMANDS`, manpage section
+    ManCommands,
+    /// `ENVIRONMENT`, manpage section
+    ManEnvironment,
+    /// `SEE ALSO`, manpage section
+    ManSeeAlso,
+
+    /// `Error: `
+    ErrorPrefix,
+    /// `{arg} is not expected in this context`
+    NotExpected,
+    /// `environment variable {name} is not set`
+    NoEnv,
+    /// `expected {metavar} to be on the right side of {separator}`
+    StrictPos,
+    /// `couldn't parse {arg}: {message}`
+    ParseFailed,
+    /// `couldn't parse: {message}`
+    ParseFailedValue,
+    /// `{arg}: {message}`
+    GuardFailed,
+    /// `check failed: {message}`
+    GuardFailedValue,
+    /// `{arg} requires an argument {metavar}`
+    NoArgument,
+    /// `{arg} requires an argument {metavar}, got a flag {flag}, try {fix} to use it as an argument`
+    NoArgumentGotFlag,
+    /// `{app} supports {flag} as both an option and an option-argument, try to split {arg} into
+    /// individual options ({split}) or use {fix} syntax to disambiguate`
+    Ambiguity,
+    /// `app`, used in place of `{app}` when application name is not known
+    UnnamedApp,
+    /// `no such {kind}: {arg}, did you mean {suggestion}?`
+    NoSuchItem,
+    /// `no such flag: {arg} (with one dash), did you mean {suggestion}?`
+    MissingDash,
+    /// `no such flag: {arg} (with two dashes), did you mean {suggestion}?`
+    ExtraDash,
+    /// `{kind} {arg} is not valid in this context, did you mean to pass it to command {command}?`
+    Nested,
+    /// `flag`, used in place of `{kind}`
+    KindFlag,
+    /// `argument value`, used in place of `{kind}`
+    KindArgumentValue,
+    /// `command or positional`, used in place of `{kind}`
+    KindCommandOrPositional,
+    /// `subcommand`, used in place of `{kind}`
+    KindSubcommand,
+    /// `expected {expected}, got {arg}. Pass {help} for usage information`
+    Expected,
+    /// `expected {expected}, pass {help} for usage information`
+    ExpectedAtEnd,
+    /// `no arguments`, used in place of `{expected}`
+    ExpectedNothing,
+    /// `{first} or {second}`, used in place of `{expected}`
+    ExpectedTwo,
+    /// `{first}, {second}, or more`, used in place of `{expected}`
+    ExpectedMany,
+    /// `{arg} cannot be used at the same time as {other}`
+    Conflict,
+    /// `environment variable {name}`, used in place of `{arg}` or `{other}` in
+    /// [`Conflict`](Phrase::Conflict)
+    EnvSource,
+    /// `config key {key}`, used in place of `{arg}` or `{other}` in
+    /// [`Conflict`](Phrase::Conflict)
+    ConfigSource,
+    /// `{arg} requires {other}`
+    Requires,
+    /// `argument {arg} cannot be used multiple times in this context`
+    OnlyOnce,
+    /// `parser requires an extra flag, argument or parameter, but its name is hidden by the author`
+    HiddenMissing,
+    /// `couldn't read response file {path}: {message}`
+    ResponseFile,
+    /// `{path}:{line}: {message}`, problem with response file contents
+    ResponseFileLine,
+    /// `(from {path}:{line})`, added to errors about arguments read from a response file
+    FromResponseFile,
+    /// `{arg} is not a valid value, did you mean {suggestion}?`
+    InvalidChoice,
+    /// `{arg} is not a valid value, expected one of {values}`
+    InvalidChoiceList,
+    /// `expected {metavar}, got {arg}`, value of a key-value argument without `=`
+    KeyValueSeparator,
+    /// `couldn't parse key {key} in {arg}: {message}`
+    KeyValueKey,
+    /// `couldn't parse value {value} in {arg}: {message}`
+    KeyValueValue,
+}
+
+impl Phrase {
+    /// English text `bpaf` uses by default
+    #[must_use]
+    pub fn default_text(self) -> &'static str {
+        match self {
+            Phrase::HelpDescription => "Prints help information",
+            Phrase::VersionDescription => "Prints version information",
+            Phrase::ColorDescription => "When to use colors: auto, always or never",
+            Phrase::Version => "Version: {version}",
+            Phrase::Usage => "Usage",
+            Phrase::PositionalItems => "Available positional items:",
+            Phrase::Options => "Available options:",
+            Phrase::Commands => "Available commands:",
+            Phrase::Default => "[default: {value}]",
+            Phrase::PossibleValues => "[possible values: {values}]",
+            Phrase::PossibleKeys => "[possible keys: {values}]",
+            Phrase::RequiresNote => "[requires: {other}]",
+            Phrase::ConflictsNote => "[conflicts with: {other}]",
+            Phrase::RequiredIfNote => "[required if: {other}]",
+            Phrase::AtLeastOneNote => "[at least one of: {items}]",
+            Phrase::ExactlyOneNote => "[exactly one of: {items}]",
+            Phrase::EnvSet => "[env:{name}: set]",
+            Phrase::EnvNotSet => "[env:{name}: not set]",
+            Phrase::EnvValue => "[env:{name} = {value}]",
+            Phrase::EnvNotAvailable => "[env:{name}: N/A]",
+            Phrase::UsesEnv => "Uses environment variable {name}",
+            Phrase::ConfigKey => "[config:{key}]",
+            Phrase::UsesConfig => "Uses config key {key}",
+            Phrase::ManName => "NAME",
+            Phrase::ManSynopsis => "SYNOPSIS",
+            Phrase::ManOptions => "OPTIONS",
+            Phrase::ManCommands => "COMMANDS",
+            Phrase::ManEnvironment => "ENVIRONMENT",
+            Phrase::ManSeeAlso => "SEE ALSO",
+            Phrase::ErrorPrefix => "Error: ",
+            Phrase::NotExpected => "{arg} is not expected in this context",
+            Phrase::NoEnv => "environment variable {name} is not set",
+            Phrase::StrictPos => "expected {metavar} to be on the right side of {separator}",
+            Phrase::ParseFailed => "couldn't parse {arg}: {message}",
+            Phrase::ParseFailedValue => "couldn't parse: {message}",
+            Phrase::GuardFailed => "{arg}: {message}",
+            Phrase::GuardFailedValue => "check failed: {message}",
+            Phrase::NoArgument => "{arg} requires an argument {metavar}",
+            Phrase::NoArgumentGotFlag => "{arg} requires an argument {metavar}, got a flag {flag}, try {fix} to use it as an argument",
+            Phrase::Ambiguity => "{app} supports {flag} as both an option and an option-argument, try to split {arg} into individual options ({split}) or use {fix} syntax to disambiguate",
+            Phrase::UnnamedApp => "app",
+            Phrase::NoSuchItem => "no such {kind}: {arg}, did you mean {suggestion}?",
+            Phrase::MissingDash => "no such flag: {arg} (with one dash), did you mean {suggestion}?",
+            Phrase::ExtraDash => "no such flag: {arg} (with two dashes), did you mean {suggestion}?",
+            Phrase::Nested => "{kind} {arg} is not valid in this context, did you mean to pass it to command {command}?",
+            Phrase::KindFlag => "flag",
+            Phrase::KindArgumentValue => "argument value",
+            Phrase::KindCommandOrPositional => "command or positional",
+            Phrase::KindSubcommand => "subcommand",
+            Phrase::Expected => "expected {expected}, got {arg}. Pass {help} for usage information",
+            Phrase::ExpectedAtEnd => "expected {expected}, pass {help} for usage information",
+            Phrase::ExpectedNothing => "no arguments",
+            Phrase::ExpectedTwo => "{first} or {second}",
+            Phrase::ExpectedMany => "{first}, {second}, or more",
+            Phrase::Conflict => "{arg} cannot be used at the same time as {other}",
+            Phrase::EnvSource => "environment variable {name}",
+            Phrase::ConfigSource => "config key {key}",
+            Phrase::Requires => "{arg} requires {other}",
+            Phrase::OnlyOnce => "argument {arg} cannot be used multiple times in this context",
+            Phrase::HiddenMissing => "parser requires an extra flag, argument or parameter, but its name is hidden by the author",
+            Phrase::ResponseFile => "couldn't read response file {path}: {message}",
+            Phrase::ResponseFileLine => "{path}:{line}: {message}",
+            Phrase::FromResponseFile => "(from {path}:{line})",
+            Phrase::InvalidChoice => "{arg} is not a valid value, did you mean {suggestion}?",
+            Phrase::InvalidChoiceList => "{arg} is not a valid value, expected one of {values}",
+            Phrase::KeyValueSeparator => "expected {metavar}, got {arg}",
+            Phrase::KeyValueKey => "couldn't parse key {key} in {arg}: {message}",
+            Phrase::KeyValueValue => "couldn't parse value {value} in {arg}: {message}",
+        }
+    }
+}
+
+/// Translation of built-in phrases, see [`OptionParser::locale`]
+///
+/// In most cases you can use [`Catalog`] instead of implementing this trait directly.
+pub trait Locale {
+    /// Language tag, `bpaf` uses it to pick [translations](Doc::translation) of documentation
+    /// you provide
+    fn language(&self) -> &str;
+
+    /// Translated template for a phrase, `None` means to use [`Phrase::default_text`]
+    fn phrase(&self, phrase: Phrase) -> Option<&str>;
+}
+
+/// Simple [`Locale`] with a set of translated phrases
+///
+/// ```rust
+/// # use bpaf::*;
+/// let german = Catalog::new("de")
+///     .translate(Phrase::Usage, "Verwendung")
+///     .translate(Phrase::Options, "Verfügbare Optionen:")
+///     .translate(Phrase::HelpDescription, "Zeigt die Hilfe an");
+///
+/// let parser = short('v')
+///     .help(Doc::from("Verbose output").translation("de", "Ausführliche Ausgabe"))
+///     .switch()
+///     .to_options()
+///     .locale(german);
+///
+/// let help = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+/// let expected = "\
+/// Verwendung: [-v]
+///
+/// Verfügbare Optionen:
+///     -v          Ausführliche Ausgabe
+///     -h, --help  Zeigt die Hilfe an
+/// ";
+/// assert_eq!(help, expected);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    language: String,
+    phrases: HashMap<Phrase, String>,
+}
+
+impl Catalog {
+    /// Create an empty catalog for a language
+    #[must_use]
+    pub fn new(language: &str) -> Self {
+        Self {
+            language: language.to_owned(),
+            phrases: HashMap::new(),
+        }
+    }
+
+    /// Add a translation for a phrase
+    #[must_use]
+    pub fn translate(mut self, phrase: Phrase, text: &str) -> Self {
+        self.phrases.insert(phrase, text.to_owned());
+        self
+    }
+}
+
+impl Locale for Catalog {
+    fn language(&self) -> &str {
+        &self.language
+    }
+
+    fn phrase(&self, phrase: Phrase) -> Option<&str> {
+        self.phrases.get(&phrase).map(String::as_str)
+    }
+}
+
+/// Shared handle to a [`Locale`] stored in [`Info`](crate::info::Info) and
+/// [`State`](crate::State), English when empty
+#[derive(Clone, Default)]
+pub(crate) struct Messages(Option<Rc<dyn Locale>>);
+
+impl Messages {
+    pub(crate) fn new<L: Locale + 'static>(locale: L) -> Self {
+        Self(Some(Rc::new(locale)))
+    }
+
+    pub(crate) fn is_custom(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Template for a phrase, translated if possible
+    pub(crate) fn get(&self, phrase: Phrase) -> &str {
+        self.0
+            .as_ref()
+            .and_then(|locale| locale.phrase(phrase))
+            .unwrap_or_else(|| phrase.default_text())
+    }
+
+    /// Translated version of a user provided document, if present
+    pub(crate) fn pick<'a>(&self, doc: &'a Doc) -> &'a Doc {
+        match &self.0 {
+            Some(locale) => doc.translated(locale.language()),
+            None => doc,
+        }
+    }
+}
+
+impl std::fmt::Debug for Messages {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(locale) => write!(f, "Messages({:?})", locale.language()),
+            None => f.write_str("Messages"),
+        }
+    }
+}
+
+impl Doc {
+    /// Write a phrase template, `arg` writes values for placeholders
+    pub(crate) fn write_template(&mut self, template: &str, mut arg: impl FnMut(&mut Doc, &str)) {
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let end = match rest[start..].find('}') {
+                Some(len) => start + len,
+                None => break,
+            };
+            if start > 0 {
+                self.text(&rest[..start]);
+            }
+            arg(self, &rest[start + 1..end]);
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() {
+            self.text(rest);
+        }
+    }
+
+    /// Phrase template with placeholders replaced by plain text
+    pub(crate) fn write_template_text(&mut self, template: &str, values: &[(&str, &str)]) {
+        self.write_template(template, |doc, name| {
+            if let Some((_, val)) = values.iter().find(|(n, _)| *n == name) {
+                doc.text(val);
+            }
+        });
+    }
+}
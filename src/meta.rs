@@ -1,4 +1,19 @@
-use crate::{buffer::Doc, item::Item};
+use crate::{
+    args::{Arg, State},
+    buffer::Doc,
+    item::Item,
+    locale::Phrase,
+};
+
+/// Positional items in a scope, see [`Meta::passthrough_start`]
+enum Slots {
+    /// Takes exactly this many positional items, no passthrough
+    Fixed(usize),
+    /// Passthrough item goes after this many positional items
+    Found(usize),
+    /// Can't be decided without running the parsers
+    Unknown,
+}
 
 #[doc(hidden)]
 #[derive(Clone, Debug)]
@@ -21,14 +36,30 @@ pub enum Meta {
     ///
     /// whole set of arguments go into the same section as the first one
     Subsection(Box<Meta>, Box<Doc>),
-    /// Buffer is rendered after
-    Suffix(Box<Meta>, Box<Doc>),
+    /// Default value or some other note rendered after the item using a given phrase
+    Suffix(Box<Meta>, Phrase, Box<Doc>),
     /// This item is not rendered in the help message
     Skip,
+    /// Item is hidden with [`hide`](crate::Parser::hide), treated as [`Skip`](Meta::Skip)
+    /// everywhere apart from the schema export
+    Hidden(Box<Meta>),
     /// TODO make it Option<Box<Doc>>
     CustomUsage(Box<Meta>, Box<Doc>),
     /// this meta must be prefixed with -- in unsage group
     Strict(Box<Meta>),
+    /// Values for this item are completed as described, used by static completion
+    Completion(Box<Meta>, Box<CompHint>),
+}
+
+/// How to complete values for an item in a static completion script
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub enum CompHint {
+    /// Values are produced at runtime by a function passed to `complete`
+    Dynamic,
+    /// Values are produced by the shell itself
+    #[cfg(feature = "autocomplete")]
+    Shell(crate::ShellComp),
 }
 
 // to get std::mem::take to work
@@ -125,8 +156,9 @@ impl Meta {
                 | Meta::CustomUsage(m, _)
                 | Meta::Subsection(m, _)
                 | Meta::Strict(m)
-                | Meta::Suffix(m, _) => go(m, is_pos, v),
-                Meta::Skip => {}
+                | Meta::Suffix(m, _, _)
+                | Meta::Completion(m, _) => go(m, is_pos, v),
+                Meta::Skip | Meta::Hidden(_) => {}
             }
         }
         let mut is_pos = false;
@@ -158,14 +190,15 @@ impl Meta {
         match meta {
             Meta::And(xs) => xs.first().and_then(Self::first_item),
             Meta::Item(item) => Some(item),
-            Meta::Skip | Meta::Or(_) => None,
+            Meta::Skip | Meta::Hidden(_) | Meta::Or(_) => None,
             Meta::Optional(x)
             | Meta::Strict(x)
             | Meta::Required(x)
             | Meta::Adjacent(x)
             | Meta::Many(x)
             | Meta::Subsection(x, _)
-            | Meta::Suffix(x, _)
+            | Meta::Suffix(x, _, _)
+            | Meta::Completion(x, _)
             | Meta::CustomUsage(x, _) => Self::first_item(x),
         }
     }
@@ -266,7 +299,10 @@ impl Meta {
                     *self = Meta::Skip;
                 }
             }
-            Meta::Adjacent(m) | Meta::Subsection(m, _) | Meta::Suffix(m, _) => {
+            Meta::Adjacent(m)
+            | Meta::Subsection(m, _)
+            | Meta::Suffix(m, _, _)
+            | Meta::Completion(m, _) => {
                 m.normalize(for_usage, norm);
                 *self = std::mem::take(m);
             }
@@ -274,6 +310,7 @@ impl Meta {
             Meta::Skip => {
                 // nothing to do with items and skip just bubbles upwards
             }
+            Meta::Hidden(_) => *self = Meta::Skip,
             Meta::CustomUsage(m, u) => {
                 m.normalize(for_usage, norm);
                 // strip CustomUsage if we are not in usage so writer can simply render it
@@ -320,6 +357,228 @@ impl Meta {
         }
     }
 
+    /// Compute help for commands that get it on demand, used before rendering documentation
+    pub(crate) fn fetch_help(&mut self) {
+        match self {
+            Meta::And(xs) | Meta::Or(xs) => xs.iter_mut().for_each(Meta::fetch_help),
+            Meta::Item(item) => {
+                if let Item::Command {
+                    help,
+                    meta,
+                    lazy_help,
+                    ..
+                } = &mut **item
+                {
+                    if let Some(lazy) = lazy_help.take() {
+                        *help = (lazy.0)();
+                    }
+                    meta.fetch_help();
+                }
+            }
+            Meta::CustomUsage(m, _)
+            | Meta::Required(m)
+            | Meta::Optional(m)
+            | Meta::Adjacent(m)
+            | Meta::Subsection(m, _)
+            | Meta::Suffix(m, _, _)
+            | Meta::Completion(m, _)
+            | Meta::Hidden(m)
+            | Meta::Strict(m)
+            | Meta::Many(m) => m.fetch_help(),
+            Meta::Skip => {}
+        }
+    }
+
+    /// Find a named item that accepts `arg` in this scope, subcommands are not visited
+    pub(crate) fn named_item(&self, arg: &Arg) -> Option<&Item> {
+        match self {
+            Meta::And(xs) | Meta::Or(xs) => xs.iter().find_map(|x| x.named_item(arg)),
+            Meta::Item(item) => match &**item {
+                Item::Flag {
+                    name,
+                    shorts,
+                    negatable,
+                    ..
+                } => match arg {
+                    Arg::Short(s, _, _) => shorts.contains(s).then(|| &**item),
+                    Arg::Long(l, _, _) => {
+                        let neg = *negatable && l.strip_prefix("no-") == name.as_long();
+                        (name.as_long() == Some(l) || neg).then(|| &**item)
+                    }
+                    Arg::ArgWord(_) | Arg::Word(_) | Arg::PosWord(_) => None,
+                },
+                Item::Argument { name, shorts, .. } => match arg {
+                    Arg::Short(s, _, _) => shorts.contains(s).then(|| &**item),
+                    Arg::Long(l, _, _) => (name.as_long() == Some(l)).then(|| &**item),
+                    Arg::ArgWord(_) | Arg::Word(_) | Arg::PosWord(_) => None,
+                },
+                Item::Any { .. } | Item::Positional { .. } | Item::Command { .. } => None,
+            },
+            Meta::CustomUsage(m, _)
+            | Meta::Required(m)
+            | Meta::Optional(m)
+            | Meta::Adjacent(m)
+            | Meta::Subsection(m, _)
+            | Meta::Suffix(m, _, _)
+            | Meta::Completion(m, _)
+            | Meta::Hidden(m)
+            | Meta::Strict(m)
+            | Meta::Many(m) => m.named_item(arg),
+            Meta::Skip => None,
+        }
+    }
+
+    /// Find where items taken by a passthrough parser start, together with names of commands
+    /// that lead to it, if this can be decided before running any parsers
+    pub(crate) fn passthrough_start(&self, args: &State) -> Option<(usize, Vec<String>)> {
+        let mut scopes = vec![self];
+        let mut path = Vec::new();
+        let mut positionals = 0;
+        let mut items = args.items_iter().peekable();
+        while let Some((ix, arg)) = items.next() {
+            match arg {
+                Arg::Short(..) | Arg::Long(..) => {
+                    let item = scopes.iter().rev().find_map(|m| m.named_item(arg));
+                    if let Some(Item::Argument {
+                        optional_value: false,
+                        ..
+                    }) = item
+                    {
+                        if let Some((_, Arg::Word(_))) = items.peek() {
+                            items.next();
+                        }
+                    }
+                }
+                Arg::ArgWord(_) => {}
+                Arg::Word(_) | Arg::PosWord(_) => {
+                    let scope = scopes[scopes.len() - 1];
+                    if let Arg::Word(word) = arg {
+                        if let Some((name, meta)) = word.to_str().and_then(|w| scope.command(w)) {
+                            scopes.push(meta);
+                            path.push(name.to_owned());
+                            positionals = 0;
+                            continue;
+                        }
+                    }
+                    match scope.positional_slots() {
+                        Slots::Found(n) if n == positionals => return Some((ix, path)),
+                        Slots::Found(_) => positionals += 1,
+                        Slots::Fixed(_) | Slots::Unknown => return None,
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Find where the first command of this scope starts, values of named arguments are skipped
+    pub(crate) fn first_command(&self, args: &State) -> Option<usize> {
+        let mut items = args.items_iter().peekable();
+        while let Some((ix, arg)) = items.next() {
+            match arg {
+                Arg::Short(..) | Arg::Long(..) => {
+                    if let Some(Item::Argument {
+                        optional_value: false,
+                        ..
+                    }) = self.named_item(arg)
+                    {
+                        if let Some((_, Arg::Word(_))) = items.peek() {
+                            items.next();
+                        }
+                    }
+                }
+                Arg::Word(word) => {
+                    if word.to_str().and_then(|w| self.command(w)).is_some() {
+                        return Some(ix);
+                    }
+                }
+                Arg::ArgWord(_) | Arg::PosWord(_) => {}
+            }
+        }
+        None
+    }
+
+    /// Find a command accepting `word` in this scope, returns its name and meta
+    fn command(&self, word: &str) -> Option<(&str, &Meta)> {
+        match self {
+            Meta::And(xs) | Meta::Or(xs) => xs.iter().find_map(|x| x.command(word)),
+            Meta::Item(item) => match &**item {
+                Item::Command {
+                    name, short, meta, ..
+                } => {
+                    let by_short = short.map_or(false, |s| word.chars().eq(std::iter::once(s)));
+                    (name == word || by_short).then(|| (name.as_ref(), meta.as_ref()))
+                }
+                Item::Any { .. }
+                | Item::Positional { .. }
+                | Item::Flag { .. }
+                | Item::Argument { .. } => None,
+            },
+            Meta::CustomUsage(m, _)
+            | Meta::Required(m)
+            | Meta::Optional(m)
+            | Meta::Adjacent(m)
+            | Meta::Subsection(m, _)
+            | Meta::Suffix(m, _, _)
+            | Meta::Completion(m, _)
+            | Meta::Hidden(m)
+            | Meta::Strict(m)
+            | Meta::Many(m) => m.command(word),
+            Meta::Skip => None,
+        }
+    }
+
+    /// Count positional items consumed before a passthrough one
+    fn positional_slots(&self) -> Slots {
+        match self {
+            Meta::And(xs) => {
+                let mut total = 0;
+                for x in xs {
+                    match x.positional_slots() {
+                        Slots::Fixed(n) => total += n,
+                        Slots::Found(n) => return Slots::Found(total + n),
+                        Slots::Unknown => return Slots::Unknown,
+                    }
+                }
+                Slots::Fixed(total)
+            }
+            Meta::Or(xs) => {
+                if xs
+                    .iter()
+                    .all(|x| matches!(x.positional_slots(), Slots::Fixed(0)))
+                {
+                    Slots::Fixed(0)
+                } else {
+                    Slots::Unknown
+                }
+            }
+            Meta::Item(item) => match &**item {
+                Item::Positional {
+                    passthrough: true, ..
+                } => Slots::Found(0),
+                Item::Positional { .. } => Slots::Fixed(1),
+                Item::Any { .. } => Slots::Unknown,
+                Item::Command { .. } | Item::Flag { .. } | Item::Argument { .. } => Slots::Fixed(0),
+            },
+            // optional positional items are consumed greedily
+            Meta::Optional(m) => m.positional_slots(),
+            Meta::Many(m) => match m.positional_slots() {
+                Slots::Fixed(0) => Slots::Fixed(0),
+                Slots::Found(n) => Slots::Found(n),
+                Slots::Fixed(_) | Slots::Unknown => Slots::Unknown,
+            },
+            Meta::CustomUsage(m, _)
+            | Meta::Required(m)
+            | Meta::Adjacent(m)
+            | Meta::Subsection(m, _)
+            | Meta::Suffix(m, _, _)
+            | Meta::Completion(m, _)
+            | Meta::Hidden(m)
+            | Meta::Strict(m) => m.positional_slots(),
+            Meta::Skip => Slots::Fixed(0),
+        }
+    }
+
     /// collect different kinds of short names for disambiguation
     pub(crate) fn collect_shorts(&self, flags: &mut Vec<char>, args: &mut Vec<char>) {
         match self {
@@ -341,11 +600,12 @@ impl Meta {
             | Meta::Optional(m)
             | Meta::Adjacent(m)
             | Meta::Subsection(m, _)
-            | Meta::Suffix(m, _)
+            | Meta::Suffix(m, _, _)
+            | Meta::Completion(m, _)
             | Meta::Many(m) => {
                 m.collect_shorts(flags, args);
             }
-            Meta::Skip | Meta::Strict(_) => {}
+            Meta::Skip | Meta::Hidden(_) | Meta::Strict(_) => {}
         }
     }
 }
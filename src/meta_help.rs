@@ -1,19 +1,21 @@
-use std::collections::BTreeSet;
+use std::{borrow::Cow, collections::BTreeSet};
 
 use crate::{
     buffer::{Block, Doc, Style, Token},
     info::Info,
     item::{Item, ShortLong},
+    locale::{Messages, Phrase},
     Meta,
 };
 
 #[doc(hidden)]
-#[derive(Debug, Clone, Copy)]
-pub struct Metavar(pub(crate) &'static str);
+#[derive(Debug, Clone)]
+pub struct Metavar(pub(crate) Cow<'static, str>);
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum HelpItem<'a> {
     DecorSuffix {
+        phrase: Phrase,
         help: &'a Doc,
         ty: HiTy,
     },
@@ -30,11 +32,12 @@ pub(crate) enum HelpItem<'a> {
         help: Option<&'a Doc>,
     },
     Positional {
-        metavar: Metavar,
+        metavar: &'a Metavar,
         help: Option<&'a Doc>,
+        choices: &'a [Cow<'static, str>],
     },
     Command {
-        name: &'static str,
+        name: &'a str,
         short: Option<char>,
         help: Option<&'a Doc>,
         meta: &'a Meta,
@@ -42,15 +45,21 @@ pub(crate) enum HelpItem<'a> {
         info: &'a Info,
     },
     Flag {
-        name: ShortLong,
-        env: Option<&'static str>,
+        name: &'a ShortLong,
+        negatable: bool,
+        env: Option<&'a str>,
+        config: Option<&'a str>,
         help: Option<&'a Doc>,
     },
     Argument {
-        name: ShortLong,
-        metavar: Metavar,
-        env: Option<&'static str>,
+        name: &'a ShortLong,
+        optional_value: bool,
+        metavar: &'a Metavar,
+        env: Option<&'a str>,
+        config: Option<&'a str>,
         help: Option<&'a Doc>,
+        choices: &'a [Cow<'static, str>],
+        choice_keys: bool,
     },
     AnywhereStart {
         inner: &'a Meta,
@@ -166,7 +175,7 @@ impl<'a, 'b> Iterator for HelpItemsIter<'a, 'b> {
 
 impl<'a> HelpItems<'a> {
     #[inline(never)]
-    fn items_of_ty(&self, target: HiTy) -> impl Iterator<Item = &HelpItem> {
+    pub(crate) fn items_of_ty(&self, target: HiTy) -> impl Iterator<Item = &HelpItem> {
         HelpItemsIter {
             items: &self.items,
             target,
@@ -185,11 +194,12 @@ impl Meta {
             | Meta::Adjacent(x)
             | Meta::Many(x)
             | Meta::Subsection(x, _)
-            | Meta::Suffix(x, _)
+            | Meta::Suffix(x, _, _)
             | Meta::Strict(x)
+            | Meta::Completion(x, _)
             | Meta::CustomUsage(x, _) => x.peek_front_ty(),
             Meta::Item(i) => Some(HiTy::from(i.as_ref())),
-            Meta::Skip => None,
+            Meta::Skip | Meta::Hidden(_) => None,
         }
     }
 }
@@ -218,9 +228,11 @@ impl<'a> HelpItems<'a> {
                 | Meta::Required(x)
                 | Meta::Optional(x)
                 | Meta::Many(x)
-                | Meta::Strict(x) => go(hi, x, no_ss),
+                | Meta::Strict(x)
+                | Meta::Completion(x, _) => go(hi, x, no_ss),
                 Meta::Item(item) => {
-                    if matches!(item.as_ref(), Item::Positional { help: None, .. }) {
+                    if matches!(item.as_ref(), Item::Positional { help: None, choices, .. } if choices.is_empty())
+                    {
                         return;
                     }
                     hi.items.push(HelpItem::from(item.as_ref()));
@@ -236,13 +248,17 @@ impl<'a> HelpItems<'a> {
                         }
                     }
                 }
-                Meta::Suffix(m, help) => {
+                Meta::Suffix(m, phrase, help) => {
                     if let Some(ty) = m.peek_front_ty() {
                         go(hi, m, no_ss);
-                        hi.items.push(HelpItem::DecorSuffix { help, ty });
+                        hi.items.push(HelpItem::DecorSuffix {
+                            phrase: *phrase,
+                            help,
+                            ty,
+                        });
                     }
                 }
-                Meta::Skip => (),
+                Meta::Skip | Meta::Hidden(_) => (),
             }
         }
         go(self, meta, false);
@@ -280,9 +296,15 @@ impl<'a> From<&'a Item> for HelpItem<'a> {
     // {{{
     fn from(item: &'a Item) -> Self {
         match item {
-            Item::Positional { metavar, help } => Self::Positional {
-                metavar: *metavar,
+            Item::Positional {
+                metavar,
+                help,
+                choices,
+                passthrough: _,
+            } => Self::Positional {
+                metavar,
                 help: help.as_ref(),
+                choices,
             },
             Item::Command {
                 name,
@@ -293,6 +315,7 @@ impl<'a> From<&'a Item> for HelpItem<'a> {
                 info,
                 #[cfg(not(feature = "docgen"))]
                     info: _,
+                lazy_help: _,
             } => Self::Command {
                 name,
                 short: *short,
@@ -304,24 +327,36 @@ impl<'a> From<&'a Item> for HelpItem<'a> {
             Item::Flag {
                 name,
                 env,
+                config,
                 help,
                 shorts: _,
+                negatable,
             } => Self::Flag {
-                name: *name,
-                env: *env,
+                name,
+                negatable: *negatable,
+                env: env.as_deref(),
+                config: config.as_deref(),
                 help: help.as_ref(),
             },
             Item::Argument {
                 name,
                 metavar,
                 env,
+                config,
                 help,
                 shorts: _,
+                choices,
+                choice_keys,
+                optional_value,
             } => Self::Argument {
-                name: *name,
-                metavar: *metavar,
-                env: *env,
+                name,
+                optional_value: *optional_value,
+                metavar,
+                env: env.as_deref(),
+                config: config.as_deref(),
                 help: help.as_ref(),
+                choices,
+                choice_keys: *choice_keys,
             },
             Item::Any {
                 metavar,
@@ -338,28 +373,78 @@ impl<'a> From<&'a Item> for HelpItem<'a> {
 
 impl Doc {
     #[inline(never)]
-    pub(crate) fn metavar(&mut self, metavar: Metavar) {
+    pub(crate) fn metavar(&mut self, metavar: &Metavar) {
         if metavar
             .0
             .chars()
-            .all(|c| c.is_uppercase() || c.is_ascii_digit() || c == '-' || c == '_')
+            .all(|c| c.is_uppercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '='))
         {
-            self.write_str(metavar.0, Style::Metavar);
+            self.write_str(&metavar.0, Style::Metavar);
         } else {
             self.write_char('<', Style::Metavar);
-            self.write_str(metavar.0, Style::Metavar);
+            self.write_str(&metavar.0, Style::Metavar);
             self.write_char('>', Style::Metavar);
         }
     }
 }
 
+/// Env variable or config key goes on its own line below the help, `value` is used
+/// for `--help` output and `uses` for documentation
+fn write_source_line(
+    buf: &mut Doc,
+    continued: bool,
+    include_env: bool,
+    value: (&str, &[(&str, &str)]),
+    uses: (&str, &str),
+) {
+    if continued {
+        buf.token(Token::BlockStart(Block::ItemTerm));
+        buf.token(Token::BlockEnd(Block::ItemTerm));
+    }
+    buf.token(Token::BlockStart(Block::ItemBody));
+    if include_env {
+        buf.write_template_text(value.0, value.1);
+    } else {
+        buf.write_template(uses.0, |buf, _| buf.literal(uses.1));
+    }
+    buf.token(Token::BlockEnd(Block::ItemBody));
+}
+
+/// Values accepted by [`choice`](crate::parsers::NamedArg::choice), if any, `phrase` is
+/// [`PossibleKeys`](Phrase::PossibleKeys) when they restrict keys of `KEY=VALUE` pairs
+fn write_choices(
+    buf: &mut Doc,
+    continued: bool,
+    choices: &[Cow<'static, str>],
+    phrase: Phrase,
+    messages: &Messages,
+) {
+    if choices.is_empty() {
+        return;
+    }
+    if continued {
+        buf.token(Token::BlockStart(Block::ItemTerm));
+        buf.token(Token::BlockEnd(Block::ItemTerm));
+    }
+    buf.token(Token::BlockStart(Block::ItemBody));
+    buf.write_template(messages.get(phrase), |buf, _| {
+        for (ix, choice) in choices.iter().enumerate() {
+            if ix > 0 {
+                buf.text(", ");
+            }
+            buf.literal(choice);
+        }
+    });
+    buf.token(Token::BlockEnd(Block::ItemBody));
+}
+
 #[allow(clippy::too_many_lines)] // lines are _very_ boring
-fn write_help_item(buf: &mut Doc, item: &HelpItem, include_env: bool) {
+fn write_help_item(buf: &mut Doc, item: &HelpItem, include_env: bool, messages: &Messages) {
     match item {
         HelpItem::GroupStart { help, .. } => {
             buf.token(Token::BlockStart(Block::Block));
             buf.token(Token::BlockStart(Block::Section2));
-            buf.em_doc(help);
+            buf.em_doc(messages.pick(help));
             buf.token(Token::BlockEnd(Block::Section2));
             buf.token(Token::BlockStart(Block::DefinitionList));
         }
@@ -367,11 +452,11 @@ fn write_help_item(buf: &mut Doc, item: &HelpItem, include_env: bool) {
             buf.token(Token::BlockEnd(Block::DefinitionList));
             buf.token(Token::BlockEnd(Block::Block));
         }
-        HelpItem::DecorSuffix { help, .. } => {
+        HelpItem::DecorSuffix { phrase, help, .. } => {
             buf.token(Token::BlockStart(Block::ItemTerm));
             buf.token(Token::BlockEnd(Block::ItemTerm));
             buf.token(Token::BlockStart(Block::ItemBody));
-            buf.doc(help);
+            buf.write_template(messages.get(*phrase), |buf, _| buf.doc(help));
             buf.token(Token::BlockEnd(Block::ItemBody));
         }
         HelpItem::Any {
@@ -384,19 +469,30 @@ fn write_help_item(buf: &mut Doc, item: &HelpItem, include_env: bool) {
             buf.token(Token::BlockEnd(Block::ItemTerm));
             if let Some(help) = help {
                 buf.token(Token::BlockStart(Block::ItemBody));
-                buf.doc(help);
+                buf.doc(messages.pick(help));
                 buf.token(Token::BlockEnd(Block::ItemBody));
             }
         }
-        HelpItem::Positional { metavar, help } => {
+        HelpItem::Positional {
+            metavar,
+            help,
+            choices,
+        } => {
             buf.token(Token::BlockStart(Block::ItemTerm));
-            buf.metavar(*metavar);
+            buf.metavar(metavar);
             buf.token(Token::BlockEnd(Block::ItemTerm));
             if let Some(help) = help {
                 buf.token(Token::BlockStart(Block::ItemBody));
-                buf.doc(help);
+                buf.doc(messages.pick(help));
                 buf.token(Token::BlockEnd(Block::ItemBody));
             }
+            write_choices(
+                buf,
+                help.is_some(),
+                choices,
+                Phrase::PossibleValues,
+                messages,
+            );
         }
         HelpItem::Command {
             name,
@@ -415,77 +511,107 @@ fn write_help_item(buf: &mut Doc, item: &HelpItem, include_env: bool) {
             buf.token(Token::BlockEnd(Block::ItemTerm));
             if let Some(help) = help {
                 buf.token(Token::BlockStart(Block::ItemBody));
-                buf.doc(help);
+                buf.doc(messages.pick(help));
                 buf.token(Token::BlockEnd(Block::ItemBody));
             }
         }
-        HelpItem::Flag { name, env, help } => {
+        HelpItem::Flag {
+            name,
+            negatable,
+            env,
+            config,
+            help,
+        } => {
             buf.token(Token::BlockStart(Block::ItemTerm));
-            write_shortlong(buf, *name);
+            write_shortlong(buf, name, *negatable);
             buf.token(Token::BlockEnd(Block::ItemTerm));
             if let Some(help) = help {
                 buf.token(Token::BlockStart(Block::ItemBody));
-                buf.doc(help);
+                buf.doc(messages.pick(help));
                 buf.token(Token::BlockEnd(Block::ItemBody));
             }
             if let Some(env) = env {
                 let val = if std::env::var_os(env).is_some() {
-                    ": set"
+                    Phrase::EnvSet
                 } else {
-                    ": not set"
+                    Phrase::EnvNotSet
                 };
-                if help.is_some() {
-                    buf.token(Token::BlockStart(Block::ItemTerm));
-                    buf.token(Token::BlockEnd(Block::ItemTerm));
-                }
-                buf.token(Token::BlockStart(Block::ItemBody));
-                if include_env {
-                    buf.write_str(&format!("[env:{}{}]", env, val), Style::Text);
-                } else {
-                    buf.text("Uses environment variable ");
-                    buf.literal(env);
-                }
-                buf.token(Token::BlockEnd(Block::ItemBody));
+                write_source_line(
+                    buf,
+                    help.is_some(),
+                    include_env,
+                    (messages.get(val), &[("name", env)]),
+                    (messages.get(Phrase::UsesEnv), env),
+                );
+            }
+            if let Some(key) = config {
+                write_source_line(
+                    buf,
+                    help.is_some() || env.is_some(),
+                    include_env,
+                    (messages.get(Phrase::ConfigKey), &[("key", key)]),
+                    (messages.get(Phrase::UsesConfig), key),
+                );
             }
         }
         HelpItem::Argument {
             name,
+            optional_value,
             metavar,
             env,
+            config,
             help,
+            choices,
+            choice_keys,
         } => {
             buf.token(Token::BlockStart(Block::ItemTerm));
-            write_shortlong(buf, *name);
-            buf.write_str("=", Style::Text);
-            buf.metavar(*metavar);
+            write_shortlong(buf, name, false);
+            if *optional_value {
+                buf.write_str("[=", Style::Text);
+                buf.metavar(metavar);
+                buf.write_str("]", Style::Text);
+            } else {
+                buf.write_str("=", Style::Text);
+                buf.metavar(metavar);
+            }
             buf.token(Token::BlockEnd(Block::ItemTerm));
 
             if let Some(help) = help {
                 buf.token(Token::BlockStart(Block::ItemBody));
-                buf.doc(help);
+                buf.doc(messages.pick(help));
                 buf.token(Token::BlockEnd(Block::ItemBody));
             }
 
+            let phrase = if *choice_keys {
+                Phrase::PossibleKeys
+            } else {
+                Phrase::PossibleValues
+            };
+            write_choices(buf, help.is_some(), choices, phrase, messages);
+            let described = help.is_some() || !choices.is_empty();
+
             if let Some(env) = env {
-                let val = match std::env::var_os(env) {
-                    Some(s) => std::borrow::Cow::from(format!(" = {:?}", s.to_string_lossy())),
-                    None => std::borrow::Cow::Borrowed(": N/A"),
+                let (val, value) = match std::env::var_os(env) {
+                    Some(s) => (Phrase::EnvValue, format!("{:?}", s.to_string_lossy())),
+                    None => (Phrase::EnvNotAvailable, String::new()),
                 };
+                write_source_line(
+                    buf,
+                    described,
+                    include_env,
+                    (messages.get(val), &[("name", env), ("value", &value)]),
+                    (messages.get(Phrase::UsesEnv), env),
+                );
+            }
 
-                if help.is_some() {
-                    buf.token(Token::BlockStart(Block::ItemTerm));
-                    buf.token(Token::BlockEnd(Block::ItemTerm));
-                }
-                buf.token(Token::BlockStart(Block::ItemBody));
-
-                if include_env {
-                    buf.write_str(&format!("[env:{}{}]", env, val), Style::Text);
-                } else {
-                    buf.text("Uses environment variable ");
-                    buf.literal(env);
-                }
-
-                buf.token(Token::BlockEnd(Block::ItemBody));
+            if let Some(key) = config {
+                write_source_line(
+                    buf,
+                    described || env.is_some(),
+                    include_env,
+                    (messages.get(Phrase::ConfigKey), &[("key", key)]),
+                    (messages.get(Phrase::UsesConfig), key),
+                );
             }
         }
         HelpItem::AnywhereStart { inner, .. } => {
@@ -500,21 +626,24 @@ fn write_help_item(buf: &mut Doc, item: &HelpItem, include_env: bool) {
     }
 }
 
-fn write_shortlong(buf: &mut Doc, name: ShortLong) {
+/// Write names of a flag or an argument, `negatable` long name is written as `--[no-]name`
+fn write_shortlong(buf: &mut Doc, name: &ShortLong, negatable: bool) {
+    let dashes = if negatable { "--[no-]" } else { "--" };
     match name {
         ShortLong::Short(s) => {
             buf.write_char('-', Style::Literal);
-            buf.write_char(s, Style::Literal);
+            buf.write_char(*s, Style::Literal);
         }
         ShortLong::Long(l) => {
-            buf.write_str("    --", Style::Literal);
+            buf.write_str("    ", Style::Literal);
+            buf.write_str(dashes, Style::Literal);
             buf.write_str(l, Style::Literal);
         }
         ShortLong::Both(s, l) => {
             buf.write_char('-', Style::Literal);
-            buf.write_char(s, Style::Literal);
+            buf.write_char(*s, Style::Literal);
             buf.write_str(", ", Style::Text);
-            buf.write_str("--", Style::Literal);
+            buf.write_str(dashes, Style::Literal);
             buf.write_str(l, Style::Literal);
         }
     }
@@ -527,21 +656,22 @@ pub(crate) fn render_help(
     parser_meta: &Meta,
     help_meta: &Meta,
     include_env: bool,
+    messages: &Messages,
 ) -> Doc {
     parser_meta.positional_invariant_check(false);
     let mut buf = Doc::default();
 
     if let Some(t) = &info.descr {
         buf.token(Token::BlockStart(Block::Block));
-        buf.doc(t);
+        buf.doc(messages.pick(t));
         buf.token(Token::BlockEnd(Block::Block));
     }
 
     buf.token(Token::BlockStart(Block::Block));
     if let Some(usage) = &info.usage {
-        buf.doc(usage);
+        buf.doc(messages.pick(usage));
     } else {
-        buf.write_str("Usage", Style::Emphasis);
+        buf.write_str(messages.get(Phrase::Usage), Style::Emphasis);
         buf.write_str(": ", Style::Text);
         buf.token(Token::BlockStart(Block::Mono));
         buf.write_path(path);
@@ -552,7 +682,7 @@ pub(crate) fn render_help(
 
     if let Some(t) = &info.header {
         buf.token(Token::BlockStart(Block::Block));
-        buf.doc(t);
+        buf.doc(messages.pick(t));
         buf.token(Token::BlockEnd(Block::Block));
     }
 
@@ -560,11 +690,11 @@ pub(crate) fn render_help(
     items.append_meta(parser_meta);
     items.append_meta(help_meta);
 
-    buf.write_help_item_groups(items, include_env);
+    buf.write_help_item_groups(items, include_env, messages);
 
     if let Some(footer) = &info.footer {
         buf.token(Token::BlockStart(Block::Block));
-        buf.doc(footer);
+        buf.doc(messages.pick(footer));
         buf.token(Token::BlockEnd(Block::Block));
     }
     buf
@@ -591,7 +721,7 @@ impl Dedup {
                 self.keep = self.items.insert(format!("{:?} {:?}", metavar, help));
                 self.keep
             }
-            HelpItem::Positional { metavar, help } => {
+            HelpItem::Positional { metavar, help, .. } => {
                 self.keep = self.items.insert(format!("{:?} {:?}", metavar.0, help));
                 self.keep
             }
@@ -620,38 +750,63 @@ impl Dedup {
 
 impl Doc {
     #[inline(never)]
-    pub(crate) fn write_help_item_groups(&mut self, mut items: HelpItems, include_env: bool) {
+    pub(crate) fn write_help_item_groups(
+        &mut self,
+        mut items: HelpItems,
+        include_env: bool,
+        messages: &Messages,
+    ) {
+        self.write_custom_groups(&mut items, include_env, messages);
+
+        for (ty, name) in [
+            (HiTy::Positional, Phrase::PositionalItems),
+            (HiTy::Flag, Phrase::Options),
+            (HiTy::Command, Phrase::Commands),
+        ] {
+            self.write_help_items(&items, ty, Some(messages.get(name)), include_env, messages);
+        }
+    }
+
+    /// Write items from custom groups and remove them from `items`, groups have their own headers
+    pub(crate) fn write_custom_groups(
+        &mut self,
+        items: &mut HelpItems,
+        include_env: bool,
+        messages: &Messages,
+    ) {
         while let Some(range) = items.find_group() {
             let mut dd = Dedup::default();
             for item in items.items.drain(range) {
                 if dd.check(&item) {
-                    write_help_item(self, &item, include_env);
+                    write_help_item(self, &item, include_env, messages);
                 }
             }
         }
-
-        for (ty, name) in [
-            (HiTy::Positional, "Available positional items:"),
-            (HiTy::Flag, "Available options:"),
-            (HiTy::Command, "Available commands:"),
-        ] {
-            self.write_help_items(&items, ty, name, include_env);
-        }
     }
 
+    /// Write items of a given type, under a header `name` if present
     #[inline(never)]
-    fn write_help_items(&mut self, items: &HelpItems, ty: HiTy, name: &str, include_env: bool) {
+    pub(crate) fn write_help_items(
+        &mut self,
+        items: &HelpItems,
+        ty: HiTy,
+        name: Option<&str>,
+        include_env: bool,
+        messages: &Messages,
+    ) {
         let mut xs = items.items_of_ty(ty).peekable();
         if xs.peek().is_some() {
             self.token(Token::BlockStart(Block::Block));
-            self.token(Token::BlockStart(Block::Section2));
-            self.write_str(name, Style::Emphasis);
-            self.token(Token::BlockEnd(Block::Section2));
+            if let Some(name) = name {
+                self.token(Token::BlockStart(Block::Section2));
+                self.write_str(name, Style::Emphasis);
+                self.token(Token::BlockEnd(Block::Section2));
+            }
             self.token(Token::BlockStart(Block::DefinitionList));
             let mut dd = Dedup::default();
             for item in xs {
                 if dd.check(item) {
-                    write_help_item(self, item, include_env);
+                    write_help_item(self, item, include_env, messages);
                 }
             }
             self.token(Token::BlockEnd(Block::DefinitionList));
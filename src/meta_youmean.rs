@@ -4,9 +4,9 @@ use crate::{
     Meta, State,
 };
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub(crate) enum Variant {
-    CommandLong(&'static str),
+    CommandLong(String),
     Flag(ShortLong),
 }
 
@@ -14,7 +14,7 @@ pub(crate) enum Variant {
 pub(crate) enum Suggestion {
     Variant(Variant),
     /// expected --foo, actual -foo
-    MissingDash(&'static str),
+    MissingDash(String),
     /// expected -f, actual --f
     ExtraDash(char),
     Nested(String, Variant),
@@ -62,7 +62,7 @@ pub(crate) fn suggest(args: &State, meta: &Meta) -> Option<(usize, Suggestion)>
                 // - typo in a short or a long name
                 // - there is a nested command that matches perfectly - try using that
                 let distance = damerau_levenshtein(&actual, name);
-                improve(distance, Variant::CommandLong(name));
+                improve(distance, Variant::CommandLong((*name).to_owned()));
 
                 // scan nested items and look for exact matches only
                 nested.items.clear();
@@ -71,13 +71,13 @@ pub(crate) fn suggest(args: &State, meta: &Meta) -> Option<(usize, Suggestion)>
                     match item {
                         HelpItem::Command { name: nname, .. } => {
                             if *nname == actual {
-                                nest = Some((name, Variant::CommandLong(nname)));
+                                nest = Some((name, Variant::CommandLong((*nname).to_owned())));
                             }
                         }
                         HelpItem::Flag { name: nname, .. }
                         | HelpItem::Argument { name: nname, .. } => {
-                            if *nname == &actual {
-                                nest = Some((name, Variant::Flag(*nname)));
+                            if **nname == actual.as_str() {
+                                nest = Some((name, Variant::Flag((*nname).clone())));
                             }
                         }
                         HelpItem::DecorSuffix { .. }
@@ -93,7 +93,7 @@ pub(crate) fn suggest(args: &State, meta: &Meta) -> Option<(usize, Suggestion)>
             HelpItem::Flag { name, .. } | HelpItem::Argument { name, .. } => {
                 if let Some(long) = name.as_long() {
                     let distance = damerau_levenshtein(&actual, &format!("--{}", long));
-                    improve(distance, Variant::Flag(*name));
+                    improve(distance, Variant::Flag((*name).clone()));
                 }
                 if let Some(short) = name.as_short() {
                     if let Some(act) = actual.strip_prefix("--") {
@@ -124,10 +124,10 @@ pub(crate) fn suggest(args: &State, meta: &Meta) -> Option<(usize, Suggestion)>
         let best_match = best_match?;
 
         // handle missing single dash typos separately
-        if let Variant::Flag(n) = best_match {
+        if let Variant::Flag(n) = &best_match {
             if let Some(long) = n.as_long() {
                 if actual.strip_prefix('-') == Some(long) {
-                    return Some((ix, Suggestion::MissingDash(long)));
+                    return Some((ix, Suggestion::MissingDash(long.to_owned())));
                 }
             }
         }
@@ -135,6 +135,19 @@ pub(crate) fn suggest(args: &State, meta: &Meta) -> Option<(usize, Suggestion)>
     }
 }
 
+/// Closest valid value for a mistyped value of [`choice`](crate::parsers::NamedArg::choice)
+pub(crate) fn suggest_choice<'a, S: AsRef<str>>(value: &str, choices: &'a [S]) -> Option<&'a str> {
+    let choices = choices.iter().map(AsRef::as_ref);
+    if let Some(choice) = choices.clone().find(|c| c.eq_ignore_ascii_case(value)) {
+        return Some(choice);
+    }
+    choices
+        .map(|choice| (damerau_levenshtein(value, choice), choice))
+        .filter(|(dist, _)| *dist > 0 && *dist < 4)
+        .min_by_key(|(dist, _)| *dist)
+        .map(|(_, choice)| choice)
+}
+
 /// Damerau-Levenshtein distance function
 ///
 /// returns `usize::MAX` if there's no common characters at all mostly to avoid
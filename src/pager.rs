@@ -0,0 +1,89 @@
+//! Show long `--help` output through a pager, see [`OptionParser::pager`](crate::OptionParser::pager)
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use crate::buffer::terminal_height;
+
+/// Pager to use if `PAGER` is not set, `-R` keeps the colors
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Show `text` through a pager if `stdout` is a terminal and `text` doesn't fit on the screen
+///
+/// Returns `false` if `text` still needs to be printed: it fits on the screen, output is not a
+/// terminal, pager can't be started or fails.
+pub(crate) fn page(text: &str) -> bool {
+    match terminal_height() {
+        Some(rows) => page_with(text, rows, std::env::var("PAGER").ok()),
+        None => false,
+    }
+}
+
+/// Show `text` through `pager` if it doesn't fit into `rows`
+fn page_with(text: &str, rows: usize, pager: Option<String>) -> bool {
+    // trailing newline println adds takes a line as well
+    if text.lines().count() < rows {
+        return false;
+    }
+
+    let (program, args) = match pager_command(pager) {
+        Some(cmd) => cmd,
+        None => return false,
+    };
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(_) => return false,
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // user can quit the pager before reading everything, that's not an error
+        let _ = writeln!(stdin, "{}", text);
+    }
+    child.wait().map_or(false, |status| status.success())
+}
+
+/// Program and its arguments from `PAGER` value, `less -R` if it's not set
+fn pager_command(var: Option<String>) -> Option<(String, Vec<String>)> {
+    let var = var.filter(|v| !v.trim().is_empty());
+    let cmd = var.as_deref().unwrap_or(DEFAULT_PAGER);
+    let mut words = cmd.split_whitespace().map(str::to_owned);
+    let program = words.next()?;
+    Some((program, words.collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pager_from_env() {
+        let less = ("less".to_owned(), vec!["-R".to_owned()]);
+        assert_eq!(pager_command(None), Some(less.clone()));
+        assert_eq!(pager_command(Some("  ".to_owned())), Some(less));
+        assert_eq!(
+            pager_command(Some("most -s".to_owned())),
+            Some(("most".to_owned(), vec!["-s".to_owned()]))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn paged_only_when_too_long() {
+        let text = "one\ntwo\nthree";
+        assert!(!page_with(text, 4, Some("true".to_owned())));
+        assert!(page_with(text, 3, Some("true".to_owned())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_pager_falls_back_to_printing() {
+        let text = "one\ntwo\nthree";
+        assert!(!page_with(text, 2, Some("false".to_owned())));
+        assert!(!page_with(text, 2, Some("bpaf-no-such-pager".to_owned())));
+    }
+}
@@ -59,17 +59,21 @@
 //!
 #![cfg_attr(not(doctest), doc = include_str!("docs2/command.md"))]
 //!
-use std::{ffi::OsString, marker::PhantomData, str::FromStr};
+use std::{borrow::Cow, ffi::OsString, marker::PhantomData, rc::Rc, str::FromStr};
 
 use crate::{
     args::{Arg, State},
+    error::KeyValuePart,
     error::{Message, MissingItem},
-    from_os_str::parse_os_str,
+    from_os_str::{parse_os_str, split_pair},
     item::ShortLong,
     meta_help::Metavar,
     Doc, Error, Item, Meta, OptionParser, Parser,
 };
 
+#[cfg(feature = "prompt")]
+use crate::prompt::Prompt;
+
 #[cfg(doc)]
 use crate::{any, command, env, long, positional, short};
 
@@ -106,20 +110,38 @@ use crate::{any, command, env, long, positional, short};
 #[derive(Clone, Debug)]
 pub struct NamedArg {
     pub(crate) short: Vec<char>,
-    pub(crate) long: Vec<&'static str>,
-    pub(crate) env: Vec<&'static str>,
+    pub(crate) long: Vec<Cow<'static, str>>,
+    pub(crate) env: Vec<Cow<'static, str>>,
+    pub(crate) config: Vec<Cow<'static, str>>,
     pub(crate) help: Option<Doc>,
 }
 
 impl NamedArg {
-    pub(crate) fn flag_item(&self) -> Option<Item> {
+    pub(crate) fn flag_item(&self, negatable: bool) -> Option<Item> {
         Some(Item::Flag {
             name: ShortLong::try_from(self).ok()?,
             help: self.help.clone(),
-            env: self.env.first().copied(),
+            env: self.env.first().cloned(),
+            config: self.config.first().cloned(),
             shorts: self.short.clone(),
+            negatable,
         })
     }
+
+    /// Long names with `no-` prefix, used by [`negatable_switch`](NamedArg::negatable_switch)
+    fn negated(&self) -> NamedArg {
+        NamedArg {
+            short: Vec::new(),
+            long: self
+                .long
+                .iter()
+                .map(|long| Cow::Owned(format!("no-{}", long)))
+                .collect(),
+            env: Vec::new(),
+            config: Vec::new(),
+            help: self.help.clone(),
+        }
+    }
 }
 
 impl NamedArg {
@@ -136,8 +158,8 @@ impl NamedArg {
     ///
     #[cfg_attr(not(doctest), doc = include_str!("docs2/short_long_env.md"))]
     #[must_use]
-    pub fn long(mut self, long: &'static str) -> Self {
-        self.long.push(long);
+    pub fn long(mut self, long: impl Into<Cow<'static, str>>) -> Self {
+        self.long.push(long.into());
         self
     }
 
@@ -156,8 +178,37 @@ impl NamedArg {
     /// ```
     #[cfg_attr(not(doctest), doc = include_str!("docs2/short_long_env.md"))]
     #[must_use]
-    pub fn env(mut self, variable: &'static str) -> Self {
-        self.env.push(variable);
+    pub fn env(mut self, variable: impl Into<Cow<'static, str>>) -> Self {
+        self.env.push(variable.into());
+        self
+    }
+
+    /// Configuration file fallback
+    ///
+    /// If named value isn't present on a command line or in the environment - try to fallback
+    /// to this key in a [`ConfigSource`](crate::ConfigSource) given to
+    /// [`config_source`](OptionParser::config_source). Resulting precedence is
+    /// CLI > env > config > [`fallback`](Parser::fallback).
+    ///
+    /// You can specify it multiple times, `bpaf` would use items past the first one as hidden aliases.
+    ///
+    /// For [`flag`](NamedArg::flag) and [`switch`](NamedArg::switch) config value is treated as
+    /// a boolean: anything other than an empty string, `false`, `0`, `no` or `off` gives the same
+    /// result as the flag being present.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = long("verbose")
+    ///     .config("log.verbose")
+    ///     .switch()
+    ///     .to_options()
+    ///     .config_source(|key: &str| (key == "log.verbose").then(|| "true".to_owned()));
+    ///
+    /// assert!(parser.run_inner(&[]).unwrap());
+    /// ```
+    #[must_use]
+    pub fn config(mut self, key: impl Into<Cow<'static, str>>) -> Self {
+        self.config.push(key.into());
         self
     }
 
@@ -197,6 +248,33 @@ impl NamedArg {
         build_flag_parser(true, Some(false), self)
     }
 
+    /// Boolean flag that can also be turned off with a `no-` prefixed long name
+    ///
+    /// Accepts both `--color` and `--no-color` any number of times, the last one wins, the same
+    /// way as with [`last`](Parser::last). Without either of them the value comes from
+    /// environment variable or config key if they are set and is `false` otherwise. Short names
+    /// only turn the flag on. Help shows both forms as `--[no-]color`.
+    ///
+    /// In derive API use `negatable` annotation on a `bool` field.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = long("color")
+    ///     .help("Use colors in the output")
+    ///     .negatable_switch()
+    ///     .to_options();
+    ///
+    /// assert_eq!(parser.run_inner(&["--color"]).unwrap(), true);
+    /// assert_eq!(parser.run_inner(&["--color", "--no-color"]).unwrap(), false);
+    /// assert_eq!(parser.run_inner(&[]).unwrap(), false);
+    /// ```
+    #[must_use]
+    pub fn negatable_switch(self) -> ParseFlag<bool> {
+        let mut parser = build_flag_parser(true, Some(false), self);
+        parser.negatable = true;
+        parser
+    }
+
     /// Flag with custom present/absent values
     ///
     /// More generic version of [`switch`](NamedArg::switch) that can use arbitrary type instead of
@@ -247,11 +325,133 @@ impl NamedArg {
     ///
     /// You can further restrict it using [`adjacent`](ParseArgument::adjacent)
     #[must_use]
-    pub fn argument<T>(self, metavar: &'static str) -> ParseArgument<T>
+    pub fn argument<T>(self, metavar: impl Into<Cow<'static, str>>) -> ParseArgument<T>
     where
         T: FromStr + 'static,
     {
-        build_argument(self, metavar)
+        build_argument(self, metavar.into())
+    }
+
+    /// Argument that accepts one of a fixed set of values
+    ///
+    /// Values are listed in `--help` output and manpages and offered by shell completion, anything
+    /// else is rejected with a suggestion for a close match. Metavariable is derived from the
+    /// long name: `--mode` uses `MODE`.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = long("mode")
+    ///     .help("Processing mode")
+    ///     .choice(["fast", "safe", "off"])
+    ///     .to_options();
+    ///
+    /// assert_eq!(parser.run_inner(&["--mode", "safe"]).unwrap(), "safe");
+    ///
+    /// let r = parser.run_inner(&["--mode", "fsat"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "`fsat` is not a valid value, did you mean `fast`?");
+    ///
+    /// let help = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// let expected = "\
+    /// Usage: --mode=MODE
+    ///
+    /// Available options:
+    ///         --mode=MODE  Processing mode
+    ///                      [possible values: fast, safe, off]
+    ///     -h, --help       Prints help information
+    /// ";
+    /// assert_eq!(help, expected);
+    /// ```
+    #[must_use]
+    pub fn choice(
+        self,
+        choices: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> ParseArgument<String> {
+        build_choice(self, collect_choices(choices))
+    }
+
+    /// Argument that accepts one of the values of a [`Choice`] type
+    ///
+    /// Same as [`choice`](NamedArg::choice), with the result parsed into `T`
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # use std::str::FromStr;
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    /// enum Mode {
+    ///     Fast,
+    ///     Safe,
+    /// }
+    ///
+    /// impl FromStr for Mode {
+    ///     type Err = String;
+    ///     fn from_str(s: &str) -> Result<Self, String> {
+    ///         match s {
+    ///             "fast" => Ok(Mode::Fast),
+    ///             "safe" => Ok(Mode::Safe),
+    ///             _ => Err(format!("{} is not a mode", s)),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// impl Choice for Mode {
+    ///     const CHOICES: &'static [&'static str] = &["fast", "safe"];
+    /// }
+    ///
+    /// let parser = long("mode").choice_of::<Mode>().to_options();
+    /// assert_eq!(parser.run_inner(&["--mode=fast"]).unwrap(), Mode::Fast);
+    /// ```
+    #[must_use]
+    pub fn choice_of<T>(self) -> ParseArgument<T>
+    where
+        T: Choice + 'static,
+    {
+        build_choice(self, collect_choices(T::CHOICES.iter().copied()))
+    }
+
+    /// Argument with a value that can be omitted
+    ///
+    /// `--color` alone produces `bare`, `--color=never` or `-cnever` parse the value as usual.
+    /// Value must be attached to the name: `--color never` is a bare `--color` followed by
+    /// a positional item. Help and usage show it as `--color[=WHEN]`.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = short('c')
+    ///     .long("color")
+    ///     .help("When to use colors")
+    ///     .optional_value("WHEN", String::from("always"))
+    ///     .fallback(String::from("auto"))
+    ///     .to_options();
+    ///
+    /// assert_eq!(parser.run_inner(&[]).unwrap(), "auto");
+    /// assert_eq!(parser.run_inner(&["--color"]).unwrap(), "always");
+    /// assert_eq!(parser.run_inner(&["--color=never"]).unwrap(), "never");
+    /// assert_eq!(parser.run_inner(&["-cnever"]).unwrap(), "never");
+    ///
+    /// let help = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// let expected = "\
+    /// Usage: [-c[=WHEN]]
+    ///
+    /// Available options:
+    ///     -c, --color[=WHEN]  When to use colors
+    ///     -h, --help          Prints help information
+    /// ";
+    /// assert_eq!(help, expected);
+    /// ```
+    #[must_use]
+    pub fn optional_value<T>(
+        self,
+        metavar: impl Into<Cow<'static, str>>,
+        bare: T,
+    ) -> ParseOptionalValue<T>
+    where
+        T: FromStr + Clone + 'static,
+    {
+        ParseOptionalValue {
+            named: self,
+            metavar: metavar.into(),
+            bare,
+        }
     }
 
     /// `adjacent` requires for the argument to be present in the same word as the flag:
@@ -259,7 +459,9 @@ impl NamedArg {
     pub(crate) fn matches_arg(&self, arg: &Arg, adjacent: bool) -> bool {
         match arg {
             Arg::Short(s, is_adj, _) => self.short.contains(s) && (!adjacent || *is_adj),
-            Arg::Long(l, is_adj, _) => self.long.contains(&l.as_str()) && (!adjacent || *is_adj),
+            Arg::Long(l, is_adj, _) => {
+                self.long.iter().any(|long| long == l) && (!adjacent || *is_adj)
+            }
             Arg::ArgWord(_) | Arg::Word(_) | Arg::PosWord(_) => false,
         }
     }
@@ -299,12 +501,12 @@ impl<T> OptionParser<T> {
     /// To represent multiple possible commands it is convenient to use enums
     #[cfg_attr(not(doctest), doc = include_str!("docs2/command_enum.md"))]
     #[must_use]
-    pub fn command(self, name: &'static str) -> ParseCommand<T>
+    pub fn command(self, name: impl Into<Cow<'static, str>>) -> ParseCommand<T>
     where
         T: 'static,
     {
         ParseCommand {
-            longs: vec![name],
+            longs: vec![name.into()],
             shorts: Vec::new(),
             help: self.short_descr().map(Into::into),
             subparser: self,
@@ -317,7 +519,7 @@ impl<T> OptionParser<T> {
 ///
 /// Created with [`command`], implements parser for the inner structure, gives access to [`help`](ParseCommand::help).
 pub struct ParseCommand<T> {
-    pub(crate) longs: Vec<&'static str>,
+    pub(crate) longs: Vec<Cow<'static, str>>,
     pub(crate) shorts: Vec<char>,
     // short help!
     pub(crate) help: Option<Doc>,
@@ -395,8 +597,8 @@ impl<P> ParseCommand<P> {
     /// Behavior is similar to [`long`](NamedArg::long), but since you had to specify the first long
     /// name when making the command - this one becomes a hidden alias.
     #[must_use]
-    pub fn long(mut self, long: &'static str) -> Self {
-        self.longs.push(long);
+    pub fn long(mut self, long: impl Into<Cow<'static, str>>) -> Self {
+        self.longs.push(long.into());
         self
     }
 
@@ -440,7 +642,11 @@ impl<T> Parser<T> for ParseCommand<T> {
                 // in completion mode prefer to autocomplete the command name vs going inside the
                 // parser
                 args.clear_comps();
-                args.push_command(self.longs[0], self.shorts.first().copied(), &self.help);
+                args.push_command(
+                    self.longs[0].clone(),
+                    self.shorts.first().copied(),
+                    &self.help,
+                );
                 return Err(Error(Message::Missing(Vec::new())));
             }
 
@@ -484,7 +690,11 @@ impl<T> Parser<T> for ParseCommand<T> {
             }
         } else {
             #[cfg(feature = "autocomplete")]
-            args.push_command(self.longs[0], self.shorts.first().copied(), &self.help);
+            args.push_command(
+                self.longs[0].clone(),
+                self.shorts.first().copied(),
+                &self.help,
+            );
 
             let missing = MissingItem {
                 item: self.item(),
@@ -503,11 +713,12 @@ impl<T> Parser<T> for ParseCommand<T> {
 impl<T> ParseCommand<T> {
     fn item(&self) -> Item {
         Item::Command {
-            name: self.longs[0],
+            name: self.longs[0].clone(),
             short: self.shorts.first().copied(),
             help: self.help.clone(),
             meta: Box::new(self.subparser.inner.meta()),
             info: Box::new(self.subparser.info.clone()),
+            lazy_help: None,
         }
     }
 }
@@ -520,6 +731,7 @@ where
         present,
         absent,
         named,
+        negatable: false,
     }
 }
 
@@ -529,11 +741,45 @@ pub struct ParseFlag<T> {
     present: T,
     absent: Option<T>,
     named: NamedArg,
+    /// Long names with `no-` prefix produce `absent` value
+    negatable: bool,
+}
+
+impl<T: Clone> ParseFlag<T> {
+    /// Consume all the positive and negative forms of a negatable flag, the last one wins
+    fn take_negatable(&self, args: &mut State, absent: &T) -> Option<T> {
+        let negated = self.named.negated();
+        let mut last = None;
+        for (named, value) in [(&self.named, &self.present), (&negated, absent)] {
+            while args.take_flag(named) {
+                if let Some(ix) = args.current {
+                    if last.map_or(true, |(prev, _)| ix > prev) {
+                        last = Some((ix, value));
+                    }
+                }
+            }
+        }
+        let (ix, value) = last?;
+        args.set_argv_source(ix);
+        #[cfg(feature = "autocomplete")]
+        if args.touching_last_remove() {
+            args.push_flag(&self.named);
+            args.push_flag(&negated);
+        }
+        Some(value.clone())
+    }
 }
 
 impl<T: Clone + 'static> Parser<T> for ParseFlag<T> {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
-        if args.take_flag(&self.named) || self.named.env.iter().find_map(std::env::var_os).is_some()
+        if let (true, Some(absent)) = (self.negatable, &self.absent) {
+            if let Some(value) = self.take_negatable(args, absent) {
+                return Ok(value);
+            }
+        }
+        if args.take_flag(&self.named)
+            || args.env_value(&self.named.env).is_some()
+            || args.config_flag(&self.named.config)
         {
             #[cfg(feature = "autocomplete")]
             if args.touching_last_remove() {
@@ -542,11 +788,16 @@ impl<T: Clone + 'static> Parser<T> for ParseFlag<T> {
             Ok(self.present.clone())
         } else {
             #[cfg(feature = "autocomplete")]
-            args.push_flag(&self.named);
+            {
+                args.push_flag(&self.named);
+                if self.negatable {
+                    args.push_flag(&self.named.negated());
+                }
+            }
             match &self.absent {
                 Some(ok) => Ok(ok.clone()),
                 None => {
-                    if let Some(item) = self.named.flag_item() {
+                    if let Some(item) = self.named.flag_item(self.negatable) {
                         let missing = MissingItem {
                             item,
                             position: args.scope().start,
@@ -554,7 +805,7 @@ impl<T: Clone + 'static> Parser<T> for ParseFlag<T> {
                         };
                         Err(Error(Message::Missing(vec![missing])))
                     } else if let Some(name) = self.named.env.first() {
-                        Err(Error(Message::NoEnv(name)))
+                        Err(Error(Message::NoEnv(name.clone())))
                     } else {
                         todo!("no key!")
                     }
@@ -564,7 +815,7 @@ impl<T: Clone + 'static> Parser<T> for ParseFlag<T> {
     }
 
     fn meta(&self) -> Meta {
-        if let Some(item) = self.named.flag_item() {
+        if let Some(item) = self.named.flag_item(self.negatable) {
             item.required(self.absent.is_none())
         } else {
             Meta::Skip
@@ -600,22 +851,58 @@ impl<T> ParseArgument<T> {
     }
 }
 
-fn build_argument<T>(named: NamedArg, metavar: &'static str) -> ParseArgument<T> {
+fn build_argument<T>(named: NamedArg, metavar: Cow<'static, str>) -> ParseArgument<T> {
     ParseArgument {
         named,
         metavar,
         ty: PhantomData,
         adjacent: false,
+        choices: Rc::from(Vec::new()),
+        #[cfg(feature = "prompt")]
+        prompt: None,
     }
 }
 
+fn collect_choices(
+    choices: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+) -> Rc<[Cow<'static, str>]> {
+    choices
+        .into_iter()
+        .map(Into::into)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn build_choice<T>(named: NamedArg, choices: Rc<[Cow<'static, str>]>) -> ParseArgument<T> {
+    let metavar = match named.long.first() {
+        Some(long) => Cow::Owned(long.to_uppercase().replace('-', "_")),
+        None => Cow::Borrowed("VALUE"),
+    };
+    let mut arg = build_argument(named, metavar);
+    arg.choices = choices;
+    arg
+}
+
+/// Type with a fixed set of textual values, see [`choice_of`](NamedArg::choice_of)
+///
+/// With `derive` feature enabled `#[derive(Choice)]` implements this trait and [`FromStr`] for
+/// enums with unit variants, values are variant names in kebab case.
+pub trait Choice: FromStr {
+    /// All the values [`FromStr`] implementation accepts
+    const CHOICES: &'static [&'static str];
+}
+
 /// Parser for a named argument, created with [`argument`](NamedArg::argument).
 #[derive(Clone)]
 pub struct ParseArgument<T> {
     ty: PhantomData<T>,
     named: NamedArg,
-    metavar: &'static str,
+    metavar: Cow<'static, str>,
     adjacent: bool,
+    /// accepted values, empty if any value is accepted
+    choices: Rc<[Cow<'static, str>]>,
+    #[cfg(feature = "prompt")]
+    prompt: Option<Prompt>,
 }
 
 impl<T> ParseArgument<T> {
@@ -634,38 +921,89 @@ impl<T> ParseArgument<T> {
         self
     }
 
+    /// Ask user for a value if it's missing and prompting is enabled
+    ///
+    /// Prompt is used only when the value is absent from the command line, environment and
+    /// config and no [`fallback`](Parser::fallback), [`optional`](Parser::optional),
+    /// [`many`](Parser::many) or an alternative branch handles it. Prompting must be enabled
+    /// with [`OptionParser::interactive`]. Answer goes through the same validation as a value
+    /// from the command line, rejected answers are asked for again.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = long("region")
+    ///     .argument::<String>("REGION")
+    ///     .prompt("Enter region")
+    ///     .to_options()
+    ///     .prompter(|_: &PromptRequest| Some("eu-west".to_owned()));
+    ///
+    /// assert_eq!(parser.run_inner(&["--region", "us-east"]).unwrap(), "us-east");
+    /// assert_eq!(parser.run_inner(&[]).unwrap(), "eu-west");
+    /// ```
+    #[cfg(feature = "prompt")]
+    #[must_use]
+    pub fn prompt(mut self, message: impl Into<Cow<'static, str>>) -> Self {
+        self.prompt = Some(Prompt {
+            message: message.into(),
+            secret: false,
+        });
+        self
+    }
+
+    /// Ask user for a value if it's missing without echoing the input
+    ///
+    /// Same as [`prompt`](ParseArgument::prompt) but for passwords and other secrets, terminal
+    /// prompter supports hidden input only on unix and doesn't ask for secrets elsewhere.
+    #[cfg(feature = "prompt")]
+    #[must_use]
+    pub fn prompt_secret(mut self, message: impl Into<Cow<'static, str>>) -> Self {
+        self.prompt = Some(Prompt {
+            message: message.into(),
+            secret: true,
+        });
+        self
+    }
+
     fn item(&self) -> Option<Item> {
         Some(Item::Argument {
             name: ShortLong::try_from(&self.named).ok()?,
-            metavar: Metavar(self.metavar),
-            env: self.named.env.first().copied(),
+            metavar: Metavar(self.metavar.clone()),
+            env: self.named.env.first().cloned(),
+            config: self.named.config.first().cloned(),
             help: self.named.help.clone(),
             shorts: self.named.short.clone(),
+            choices: self.choices.clone(),
+            choice_keys: false,
+            optional_value: false,
         })
     }
 
     fn take_argument(&self, args: &mut State) -> Result<OsString, Error> {
-        match args.take_arg(&self.named, self.adjacent, Metavar(self.metavar)) {
-            Ok(Some(w)) => {
-                #[cfg(feature = "autocomplete")]
-                if args.touching_last_remove() {
-                    args.push_metavar(self.metavar, &self.named.help, true);
-                }
-                Ok(w)
-            }
+        match args.take_arg(&self.named, self.adjacent, Metavar(self.metavar.clone())) {
+            Ok(Some(w)) => Ok(w),
             Err(err) => {
                 #[cfg(feature = "autocomplete")]
-                args.push_argument(&self.named, self.metavar);
+                args.push_argument(&self.named, self.metavar.clone());
                 Err(err)
             }
             _ => {
                 #[cfg(feature = "autocomplete")]
-                args.push_argument(&self.named, self.metavar);
-                if let Some(val) = self.named.env.iter().find_map(std::env::var_os) {
+                args.push_argument(&self.named, self.metavar.clone());
+                if let Some(val) = args.env_value(&self.named.env) {
                     args.current = None;
                     return Ok(val);
                 }
 
+                if let Some(val) = args.config_value(&self.named.config) {
+                    args.current = None;
+                    return Ok(val);
+                }
+
+                #[cfg(feature = "prompt")]
+                if let Some(val) = args.prompt_value(self.prompt.as_ref(), &self.choices) {
+                    return Ok(val);
+                }
+
                 if let Some(item) = self.item() {
                     let missing = MissingItem {
                         item,
@@ -674,7 +1012,7 @@ impl<T> ParseArgument<T> {
                     };
                     Err(Error(Message::Missing(vec![missing])))
                 } else if let Some(name) = self.named.env.first() {
-                    Err(Error(Message::NoEnv(name)))
+                    Err(Error(Message::NoEnv(name.clone())))
                 } else {
                     unreachable!()
                 }
@@ -690,6 +1028,359 @@ where
 {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
         let os = self.take_argument(args)?;
+        #[cfg(feature = "autocomplete")]
+        if args.touching_last_remove() {
+            if self.choices.is_empty() {
+                args.push_metavar(self.metavar.clone(), &self.named.help, true);
+            } else {
+                let (meta, help) = (self.metavar.clone(), &self.named.help);
+                args.push_choices(&self.choices, &os, meta, help, true);
+            }
+        }
+        check_choice(args, &os, &self.choices)?;
+        match parse_os_str::<T>(os) {
+            Ok(ok) => Ok(ok),
+            Err(err) => {
+                #[cfg(feature = "prompt")]
+                args.reject_prompt();
+                Err(Error(Message::ParseFailed(args.current, err)))
+            }
+        }
+    }
+
+    fn meta(&self) -> Meta {
+        if let Some(item) = self.item() {
+            Meta::from(item)
+        } else {
+            Meta::Skip
+        }
+    }
+}
+
+impl ParseArgument<String> {
+    /// Parse the value as a `KEY=VALUE` pair
+    ///
+    /// Value is split at the first `=`, key and value are parsed with their own [`FromStr`]
+    /// implementations. Use [`collect`](Parser::collect) to put the pairs into a
+    /// [`HashMap`](std::collections::HashMap) or a [`BTreeMap`](std::collections::BTreeMap).
+    ///
+    /// `metavar` replaces the metavariable of the argument, parts before and after `=` name the
+    /// halves in error messages and shell completion, so pick something like `KEY=VALUE` or
+    /// `NAME=VAL`. Values passed to [`choice`](NamedArg::choice) restrict the key and are listed
+    /// as possible keys in `--help`.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # use std::collections::BTreeMap;
+    /// let parser = short('D')
+    ///     .help("Define a variable")
+    ///     .argument("VAR")
+    ///     .key_value::<String, u32>("KEY=VALUE")
+    ///     .collect::<BTreeMap<_, _>>()
+    ///     .to_options();
+    ///
+    /// let r = parser.run_inner(&["-D", "width=80", "-Dheight=25"]).unwrap();
+    /// assert_eq!(r.get("width"), Some(&80));
+    /// assert_eq!(r.get("height"), Some(&25));
+    ///
+    /// let r = parser.run_inner(&["-D", "width=wide"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "couldn't parse value `wide` in `width=wide`: invalid digit found in string");
+    ///
+    /// let r = parser.run_inner(&["-D", "width"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "expected `KEY=VALUE`, got `width`");
+    /// ```
+    #[must_use]
+    pub fn key_value<K, V>(mut self, metavar: impl Into<Cow<'static, str>>) -> ParseKeyValue<K, V>
+    where
+        K: FromStr + 'static,
+        V: FromStr + 'static,
+    {
+        self.metavar = metavar.into();
+        ParseKeyValue {
+            inner: self,
+            #[cfg(feature = "autocomplete")]
+            values: None,
+            ty: PhantomData,
+        }
+    }
+}
+
+/// Completer for the value half of a `KEY=VALUE` pair, takes the key and the value typed so far
+#[cfg(feature = "autocomplete")]
+type ValueCompleter = dyn Fn(&str, &str) -> Vec<(String, Option<String>)>;
+
+/// Parser for `KEY=VALUE` pairs, created with [`key_value`](ParseArgument::key_value)
+#[derive(Clone)]
+pub struct ParseKeyValue<K, V> {
+    inner: ParseArgument<String>,
+    #[cfg(feature = "autocomplete")]
+    values: Option<Rc<ValueCompleter>>,
+    ty: PhantomData<(K, V)>,
+}
+
+#[cfg(feature = "autocomplete")]
+impl<K, V> ParseKeyValue<K, V> {
+    /// Dynamic shell completion for the value half of a pair
+    ///
+    /// `op` gets the key and the part of the value typed so far and returns values with
+    /// optional descriptions, shell completion offers them after `KEY=`. Keys are completed
+    /// from [`choice`](NamedArg::choice), if any.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// fn sizes(key: &str, value: &str) -> Vec<(String, Option<String>)> {
+    ///     let sizes: &[&str] = match key {
+    ///         "width" => &["80", "120"],
+    ///         _ => &["25", "50"],
+    ///     };
+    ///     sizes
+    ///         .iter()
+    ///         .filter(|s| s.starts_with(value))
+    ///         .map(|s| (s.to_string(), None))
+    ///         .collect()
+    /// }
+    ///
+    /// let parser = long("limit")
+    ///     .choice(["width", "height"])
+    ///     .key_value::<String, u32>("DIM=SIZE")
+    ///     .complete_value(sizes)
+    ///     .to_options();
+    ///
+    /// let r = parser.run_inner(&["--limit", "width=80"]).unwrap();
+    /// assert_eq!(r, ("width".to_owned(), 80));
+    /// ```
+    #[must_use]
+    pub fn complete_value<F>(mut self, op: F) -> Self
+    where
+        F: Fn(&str, &str) -> Vec<(String, Option<String>)> + 'static,
+    {
+        self.values = Some(Rc::new(op));
+        self
+    }
+}
+
+/// Value of a `key_value` argument that is not a valid pair
+#[derive(Debug, Clone)]
+pub(crate) struct PairError {
+    pub(crate) part: KeyValuePart,
+    /// Whole value as passed by the user
+    pub(crate) input: String,
+    pub(crate) key: String,
+    pub(crate) value: String,
+    pub(crate) metavar: Metavar,
+    pub(crate) message: String,
+}
+
+impl<K, V> ParseKeyValue<K, V> {
+    /// Names of the key and the value: halves of the metavariable or the whole metavariable
+    /// for both
+    #[cfg(feature = "autocomplete")]
+    fn halves(&self) -> (Cow<'static, str>, Cow<'static, str>) {
+        match self.inner.metavar.split_once('=') {
+            Some((key, value)) => (Cow::Owned(key.to_owned()), Cow::Owned(value.to_owned())),
+            None => (self.inner.metavar.clone(), self.inner.metavar.clone()),
+        }
+    }
+
+    fn failed(
+        &self,
+        args: &mut State,
+        part: KeyValuePart,
+        os: &OsString,
+        message: String,
+    ) -> Error {
+        #[cfg(feature = "prompt")]
+        args.reject_prompt();
+        let (key, value) = match split_pair(os) {
+            Some((key, value)) => (
+                key.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            ),
+            None => (String::new(), String::new()),
+        };
+        let err = PairError {
+            part,
+            input: os.to_string_lossy().into_owned(),
+            key,
+            value,
+            metavar: Metavar(self.inner.metavar.clone()),
+            message,
+        };
+        Error(Message::KeyValueFailed(args.current, Box::new(err)))
+    }
+}
+
+impl<K, V> Parser<(K, V)> for ParseKeyValue<K, V>
+where
+    K: FromStr + 'static,
+    <K as std::str::FromStr>::Err: std::fmt::Display,
+    V: FromStr + 'static,
+    <V as std::str::FromStr>::Err: std::fmt::Display,
+{
+    fn eval(&self, args: &mut State) -> Result<(K, V), Error> {
+        let os = self.inner.take_argument(args)?;
+        let pair = split_pair(&os);
+        #[cfg(feature = "autocomplete")]
+        if args.touching_last_remove() {
+            let (key, value) = self.halves();
+            let help = &self.inner.named.help;
+            if let (Some((k, v)), Some(values)) = (&pair, &self.values) {
+                let (k, v) = (k.to_string_lossy(), v.to_string_lossy());
+                let values = values(&k, &v)
+                    .into_iter()
+                    .map(|(value, descr)| (format!("{}={}", k, value), descr))
+                    .collect::<Vec<_>>();
+                // same as with `choice` - metavar is dropped when there's a single good suggestion
+                if values.len() != 1 {
+                    args.push_metavar(value, help, true);
+                }
+                let depth = args.depth();
+                if let Some(comp) = args.comp_mut() {
+                    for (body, descr) in values {
+                        comp.push_value(body, descr, None, depth, true);
+                    }
+                }
+            } else if pair.is_some() {
+                args.push_metavar(value, help, true);
+            } else if self.inner.choices.is_empty() {
+                args.push_metavar(key, help, true);
+            } else {
+                args.push_choices(&self.inner.choices, &os, key, help, true);
+            }
+        }
+        let (key, value) = match pair {
+            Some(pair) => pair,
+            None => {
+                return Err(self.failed(args, KeyValuePart::Separator, &os, String::new()));
+            }
+        };
+        check_choice(args, &key, &self.inner.choices)?;
+        let key = match parse_os_str::<K>(key) {
+            Ok(key) => key,
+            Err(err) => return Err(self.failed(args, KeyValuePart::Key, &os, err)),
+        };
+        match parse_os_str::<V>(value) {
+            Ok(value) => Ok((key, value)),
+            Err(err) => Err(self.failed(args, KeyValuePart::Value, &os, err)),
+        }
+    }
+
+    fn meta(&self) -> Meta {
+        let mut meta = self.inner.meta();
+        if let Meta::Item(item) = &mut meta {
+            if let Item::Argument { choice_keys, .. } = item.as_mut() {
+                *choice_keys = true;
+            }
+        }
+        meta
+    }
+}
+
+/// Reject a value that is not one of `choices`, unless `choices` is empty
+fn check_choice(
+    args: &mut State,
+    os: &OsString,
+    choices: &Rc<[Cow<'static, str>]>,
+) -> Result<(), Error> {
+    if choices.is_empty()
+        || os
+            .to_str()
+            .map_or(false, |s| choices.iter().any(|c| c == s))
+    {
+        return Ok(());
+    }
+    #[cfg(feature = "prompt")]
+    args.reject_prompt();
+    let value = os.to_string_lossy().into_owned();
+    Err(Error(Message::InvalidChoice(
+        args.current,
+        value,
+        choices.clone(),
+    )))
+}
+
+/// Parser for a named argument with an optional value, created with
+/// [`optional_value`](NamedArg::optional_value).
+#[derive(Clone)]
+pub struct ParseOptionalValue<T> {
+    named: NamedArg,
+    metavar: Cow<'static, str>,
+    /// value to use when the name is present without a value
+    bare: T,
+}
+
+impl<T> ParseOptionalValue<T> {
+    /// Add a help message to an `optional_value`
+    ///
+    /// See [`NamedArg::help`]
+    #[must_use]
+    pub fn help<M>(mut self, help: M) -> Self
+    where
+        M: Into<Doc>,
+    {
+        self.named.help = Some(help.into());
+        self
+    }
+
+    fn item(&self) -> Option<Item> {
+        Some(Item::Argument {
+            name: ShortLong::try_from(&self.named).ok()?,
+            metavar: Metavar(self.metavar.clone()),
+            env: self.named.env.first().cloned(),
+            config: self.named.config.first().cloned(),
+            help: self.named.help.clone(),
+            shorts: self.named.short.clone(),
+            choices: Rc::from(Vec::new()),
+            choice_keys: false,
+            optional_value: true,
+        })
+    }
+}
+
+impl<T> Parser<T> for ParseOptionalValue<T>
+where
+    T: FromStr + Clone + 'static,
+    <T as std::str::FromStr>::Err: std::fmt::Display,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        let os = match args.take_opt_arg(&self.named) {
+            Some(Some(os)) => {
+                #[cfg(feature = "autocomplete")]
+                if args.touching_last_remove() {
+                    args.push_metavar(self.metavar.clone(), &self.named.help, true);
+                }
+                os
+            }
+            Some(None) => {
+                #[cfg(feature = "autocomplete")]
+                if args.touching_last_remove() {
+                    args.push_flag(&self.named);
+                }
+                return Ok(self.bare.clone());
+            }
+            None => {
+                #[cfg(feature = "autocomplete")]
+                args.push_flag(&self.named);
+                if let Some(val) = args.env_value(&self.named.env) {
+                    args.current = None;
+                    val
+                } else if let Some(val) = args.config_value(&self.named.config) {
+                    args.current = None;
+                    val
+                } else if let Some(item) = self.item() {
+                    let missing = MissingItem {
+                        item,
+                        position: args.scope().start,
+                        scope: args.scope(),
+                    };
+                    return Err(Error(Message::Missing(vec![missing])));
+                } else if let Some(name) = self.named.env.first() {
+                    return Err(Error(Message::NoEnv(name.clone())));
+                } else {
+                    unreachable!()
+                }
+            }
+        };
         match parse_os_str::<T>(os) {
             Ok(ok) => Ok(ok),
             Err(err) => Err(Error(Message::ParseFailed(args.current, err))),
@@ -705,12 +1396,15 @@ where
     }
 }
 
-pub(crate) fn build_positional<T>(metavar: &'static str) -> ParsePositional<T> {
+pub(crate) fn build_positional<T>(metavar: Cow<'static, str>) -> ParsePositional<T> {
     ParsePositional {
         metavar,
         help: None,
+        choices: Rc::from(Vec::new()),
         result_type: PhantomData,
         strict: false,
+        #[cfg(feature = "prompt")]
+        prompt: None,
     }
 }
 
@@ -720,10 +1414,14 @@ pub(crate) fn build_positional<T>(metavar: &'static str) -> ParsePositional<T> {
 /// and [`strict`](Self::strict) on this struct.
 #[derive(Clone)]
 pub struct ParsePositional<T> {
-    metavar: &'static str,
+    metavar: Cow<'static, str>,
     help: Option<Doc>,
+    /// accepted values, empty if any value is accepted
+    choices: Rc<[Cow<'static, str>]>,
     result_type: PhantomData<T>,
     strict: bool,
+    #[cfg(feature = "prompt")]
+    prompt: Option<Prompt>,
 }
 
 impl<T> ParsePositional<T> {
@@ -774,10 +1472,68 @@ impl<T> ParsePositional<T> {
         self
     }
 
+    /// Accept only one of a fixed set of values
+    ///
+    /// Works the same way as [`NamedArg::choice`]: values are listed in `--help` output and
+    /// offered by shell completion, anything else is rejected with a suggestion for a close match.
+    /// For a [`Choice`] type use `T::CHOICES.iter().copied()`.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = positional::<String>("FORMAT")
+    ///     .choice(["json", "yaml"])
+    ///     .to_options();
+    ///
+    /// assert_eq!(parser.run_inner(&["yaml"]).unwrap(), "yaml");
+    ///
+    /// let r = parser.run_inner(&["jsno"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "`jsno` is not a valid value, did you mean `json`?");
+    /// ```
+    #[must_use]
+    pub fn choice(
+        mut self,
+        choices: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> Self {
+        self.choices = collect_choices(choices);
+        self
+    }
+
+    /// Ask user for a value if it's missing and prompting is enabled
+    ///
+    /// See [`ParseArgument::prompt`] for details
+    #[cfg(feature = "prompt")]
+    #[must_use]
+    pub fn prompt(mut self, message: impl Into<Cow<'static, str>>) -> Self {
+        self.prompt = Some(Prompt {
+            message: message.into(),
+            secret: false,
+        });
+        self
+    }
+
+    /// Ask user for a value if it's missing without echoing the input
+    ///
+    /// See [`ParseArgument::prompt_secret`] for details
+    #[cfg(feature = "prompt")]
+    #[must_use]
+    pub fn prompt_secret(mut self, message: impl Into<Cow<'static, str>>) -> Self {
+        self.prompt = Some(Prompt {
+            message: message.into(),
+            secret: true,
+        });
+        self
+    }
+
     fn meta(&self) -> Meta {
+        self.positional_meta(false)
+    }
+
+    fn positional_meta(&self, passthrough: bool) -> Meta {
         let meta = Meta::from(Item::Positional {
-            metavar: Metavar(self.metavar),
+            metavar: Metavar(self.metavar.clone()),
             help: self.help.clone(),
+            choices: self.choices.clone(),
+            passthrough,
         });
         if self.strict {
             Meta::Strict(Box::new(meta))
@@ -787,14 +1543,78 @@ impl<T> ParsePositional<T> {
     }
 }
 
+impl ParsePositional<OsString> {
+    /// Stop parsing at this positional item, capture it and everything after it
+    ///
+    /// Useful for wrappers that run another program the way `exec`, `sudo` or `docker run` do:
+    /// ```console
+    /// $ mytool run prog --its-flags
+    /// ```
+    /// here `mytool` takes `prog` and everything after it, including `--its-flags`, as
+    /// they were written on the command line. Items after the first positional are not
+    /// available to any other parser, even to the ones that come before this one in
+    /// `construct!`. This holds as long as the number of positional items before this one is
+    /// known from the parser structure: it can't be decided in advance after `many` positional
+    /// items or inside alternatives, there only parsers that run later lose access to the
+    /// tail. Similarly to [`strict`](Self::strict) positional items this parser should be
+    /// the last one.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # use std::ffi::OsString;
+    /// let verbose = short('v').switch();
+    /// let prog = positional("PROG").help("Program to run").stop_parsing();
+    /// let parser = construct!(verbose, prog).to_options();
+    ///
+    /// let (verbose, prog) = parser.run_inner(&["-v", "ls", "-v", "--color"]).unwrap();
+    /// assert!(verbose);
+    /// assert_eq!(prog, ["ls", "-v", "--color"]);
+    /// ```
+    #[must_use]
+    pub fn stop_parsing(self) -> ParseStopParsing {
+        ParseStopParsing { inner: self }
+    }
+}
+
+/// Parser that captures the first positional item and everything after it, created with
+/// [`ParsePositional::stop_parsing`]
+#[derive(Clone)]
+pub struct ParseStopParsing {
+    inner: ParsePositional<OsString>,
+}
+
+impl Parser<Vec<OsString>> for ParseStopParsing {
+    fn eval(&self, args: &mut State) -> Result<Vec<OsString>, Error> {
+        // tail is usually reserved before parsing starts, see `Meta::passthrough_start`
+        if let Some(words) = args.take_tail() {
+            return Ok(words);
+        }
+        // position of the tail can't be known in advance when it follows optional positional
+        // items, parsers that already ran might have taken something from it
+        let metavar = Metavar(self.inner.metavar.clone());
+        parse_pos_word(args, self.inner.strict, metavar, &self.inner.help, &[])?;
+        let start = args.current.unwrap_or_else(|| args.scope().start);
+        args.reserve_tail(start, args.path.clone());
+        Ok(args.take_tail().unwrap_or_default())
+    }
+
+    fn meta(&self) -> Meta {
+        Meta::Many(Box::new(Meta::Required(Box::new(
+            self.inner.positional_meta(true),
+        ))))
+    }
+}
+
+// `help` and `choices` are only used for completion
+#[cfg_attr(not(feature = "autocomplete"), allow(unused_variables))]
 fn parse_pos_word(
     args: &mut State,
     strict: bool,
-    metavar: &'static str,
+    metavar: Metavar,
     help: &Option<Doc>,
+    choices: &[Cow<'static, str>],
 ) -> Result<OsString, Error> {
-    let metavar = Metavar(metavar);
-    match args.take_positional_word(metavar) {
+    match args.take_positional_word(metavar.clone()) {
         Ok((ix, is_strict, word)) => {
             if strict && !is_strict {
                 #[cfg(feature = "autocomplete")]
@@ -804,7 +1624,11 @@ fn parse_pos_word(
             }
             #[cfg(feature = "autocomplete")]
             if args.touching_last_remove() && !args.check_no_pos_ahead() {
-                args.push_metavar(metavar.0, help, false);
+                if choices.is_empty() {
+                    args.push_metavar(metavar.0.clone(), help, false);
+                } else {
+                    args.push_choices(choices, &word, metavar.0.clone(), help, false);
+                }
                 args.set_no_pos_ahead();
             }
             Ok(word)
@@ -812,7 +1636,7 @@ fn parse_pos_word(
         Err(err) => {
             #[cfg(feature = "autocomplete")]
             if !args.check_no_pos_ahead() {
-                args.push_metavar(metavar.0, help, false);
+                args.push_metavar(metavar.0.clone(), help, false);
                 args.set_no_pos_ahead();
             }
             Err(err)
@@ -826,10 +1650,24 @@ where
     <T as std::str::FromStr>::Err: std::fmt::Display,
 {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
-        let os = parse_pos_word(args, self.strict, self.metavar, &self.help)?;
+        let metavar = Metavar(self.metavar.clone());
+        let os = parse_pos_word(args, self.strict, metavar, &self.help, &self.choices);
+        #[cfg(feature = "prompt")]
+        let os = match os {
+            Err(Error(Message::Missing(missing))) => args
+                .prompt_value(self.prompt.as_ref(), &self.choices)
+                .ok_or(Error(Message::Missing(missing))),
+            os => os,
+        };
+        let os = os?;
+        check_choice(args, &os, &self.choices)?;
         match parse_os_str::<T>(os) {
             Ok(ok) => Ok(ok),
-            Err(err) => Err(Error(Message::ParseFailed(args.current, err))),
+            Err(err) => {
+                #[cfg(feature = "prompt")]
+                args.reject_prompt();
+                Err(Error(Message::ParseFailed(args.current, err)))
+            }
         }
     }
 
@@ -894,6 +1732,7 @@ impl<T> Parser<T> for ParseAny<T> {
                 Arg::ArgWord(os) | Arg::Word(os) | Arg::PosWord(os) => (os, false),
             };
             if let Some(i) = (self.check)(os.clone()) {
+                args.set_argv_source(ix);
                 args.remove(ix);
                 if next {
                     args.remove(ix + 1);
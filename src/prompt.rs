@@ -0,0 +1,276 @@
+//! Asking user for missing values, see [`OptionParser::interactive`]
+
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    ffi::OsString,
+    io::{BufRead, Write},
+    rc::Rc,
+};
+
+#[cfg(doc)]
+use crate::{parsers::ParseArgument, OptionParser};
+
+/// How many answers can be rejected before `bpaf` gives up
+const MAX_ATTEMPTS: usize = 3;
+
+/// A request for a single missing value, passed to a [`Prompter`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PromptRequest<'a> {
+    /// Message given to [`prompt`](ParseArgument::prompt)
+    pub message: &'a str,
+    /// Input should not be echoed, see [`prompt_secret`](ParseArgument::prompt_secret)
+    pub secret: bool,
+    /// Values user can pick from, produced by `complete`, can be empty
+    pub choices: &'a [String],
+    /// Why the previous answer was rejected, if it was
+    pub error: Option<&'a str>,
+}
+
+/// A way to ask user for missing values, see [`OptionParser::prompter`]
+///
+/// `bpaf` comes with a terminal implementation used by [`OptionParser::interactive`], custom
+/// implementations are useful for GUI frontends or tests. `Prompter` is implemented for closures:
+///
+/// ```rust
+/// # use bpaf::*;
+/// let parser = long("region")
+///     .argument::<String>("REGION")
+///     .prompt("Enter region")
+///     .to_options()
+///     .prompter(|req: &PromptRequest| {
+///         assert_eq!(req.message, "Enter region");
+///         Some("eu-west".to_owned())
+///     });
+///
+/// assert_eq!(parser.run_inner(&[]).unwrap(), "eu-west");
+/// ```
+pub trait Prompter {
+    /// Ask user for a value, `None` means there's no answer and parser should fail as usual
+    fn prompt(&self, request: &PromptRequest) -> Option<String>;
+}
+
+impl<F> Prompter for F
+where
+    F: Fn(&PromptRequest) -> Option<String>,
+{
+    fn prompt(&self, request: &PromptRequest) -> Option<String> {
+        self(request)
+    }
+}
+
+/// Asks for values on stderr/stdin, but only if stdin is a terminal
+pub(crate) struct Terminal;
+
+impl Prompter for Terminal {
+    // `prompt` feature documents that it raises MSRV to 1.70, see the feature list in lib.rs
+    #[allow(clippy::incompatible_msrv)]
+    fn prompt(&self, request: &PromptRequest) -> Option<String> {
+        use std::io::IsTerminal;
+        let stdin = std::io::stdin();
+        if !stdin.is_terminal() {
+            return None;
+        }
+        let mut stderr = std::io::stderr();
+        if let Some(error) = request.error {
+            writeln!(stderr, "{}", error).ok()?;
+        }
+        for (ix, choice) in request.choices.iter().enumerate() {
+            writeln!(stderr, "  {}) {}", ix + 1, choice).ok()?;
+        }
+        write!(stderr, "{}: ", request.message).ok()?;
+        stderr.flush().ok()?;
+
+        let mut line = String::new();
+        if request.secret {
+            let _echo = Echo::off()?;
+            stdin.lock().read_line(&mut line).ok()?;
+            writeln!(stderr).ok()?;
+        } else {
+            stdin.lock().read_line(&mut line).ok()?;
+        }
+        if line.is_empty() {
+            // end of input
+            return None;
+        }
+        let line = line.trim_end_matches(&['\r', '\n'][..]);
+        match line.parse::<usize>() {
+            Ok(n) if n > 0 && n <= request.choices.len() => Some(request.choices[n - 1].clone()),
+            _ => Some(line.to_owned()),
+        }
+    }
+}
+
+/// Disables terminal echo while alive
+struct Echo;
+
+impl Echo {
+    /// Hidden input is only supported on unix, on other platforms secrets are not asked for
+    #[cfg(unix)]
+    fn off() -> Option<Self> {
+        Self::stty("-echo").then(|| Echo)
+    }
+
+    #[cfg(not(unix))]
+    fn off() -> Option<Self> {
+        None
+    }
+
+    #[cfg(unix)]
+    fn stty(arg: &str) -> bool {
+        std::process::Command::new("stty")
+            .arg(arg)
+            .stdin(std::process::Stdio::inherit())
+            .status()
+            .map_or(false, |s| s.success())
+    }
+}
+
+impl Drop for Echo {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        Self::stty("echo");
+    }
+}
+
+/// Prompt attached to an item with [`prompt`](ParseArgument::prompt)
+#[derive(Debug, Clone)]
+pub(crate) struct Prompt {
+    pub(crate) message: Cow<'static, str>,
+    pub(crate) secret: bool,
+}
+
+/// Shared handle to a [`Prompter`] stored in [`Info`](crate::info::Info)
+#[derive(Clone)]
+pub(crate) struct PromptProvider(Rc<dyn Prompter>);
+
+impl PromptProvider {
+    pub(crate) fn new<P: Prompter + 'static>(prompter: P) -> Self {
+        Self(Rc::new(prompter))
+    }
+}
+
+impl std::fmt::Debug for PromptProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PromptProvider")
+    }
+}
+
+/// Answers collected during a single [`run_inner`](OptionParser::run_inner), shared between
+/// attempts so only rejected answers are asked for again
+#[derive(Debug)]
+pub(crate) struct Session {
+    prompter: PromptProvider,
+    /// Prompt message and the answer
+    answers: RefCell<Vec<(Cow<'static, str>, String)>>,
+    /// Prompt whose answer was used most recently
+    last: RefCell<Option<Cow<'static, str>>>,
+    /// Prompt whose answer failed validation during the current attempt
+    rejected: RefCell<Option<Cow<'static, str>>>,
+    /// Why the last answer was rejected
+    error: RefCell<Option<String>>,
+    /// How many answers were rejected so far
+    attempts: RefCell<usize>,
+}
+
+impl Session {
+    pub(crate) fn new(prompter: PromptProvider) -> Self {
+        Self {
+            prompter,
+            answers: RefCell::default(),
+            last: RefCell::default(),
+            rejected: RefCell::default(),
+            error: RefCell::default(),
+            attempts: RefCell::default(),
+        }
+    }
+
+    fn ask(&self, prompt: &Prompt, choices: &[String]) -> Option<String> {
+        // parser keeps evaluating other items after a failure, there is no point asking for
+        // them until the rejected answer is fixed
+        if self.rejected.borrow().is_some() {
+            return None;
+        }
+        let known = self
+            .answers
+            .borrow()
+            .iter()
+            .find(|(m, _)| *m == prompt.message)
+            .map(|(_, a)| a.clone());
+        let answer = match known {
+            Some(answer) => answer,
+            None => {
+                let error = self.error.borrow_mut().take();
+                let request = PromptRequest {
+                    message: &prompt.message,
+                    secret: prompt.secret,
+                    choices,
+                    error: error.as_deref(),
+                };
+                let answer = self.prompter.0.prompt(&request)?;
+                self.answers
+                    .borrow_mut()
+                    .push((prompt.message.clone(), answer.clone()));
+                answer
+            }
+        };
+        *self.last.borrow_mut() = Some(prompt.message.clone());
+        Some(answer)
+    }
+
+    /// The most recently used answer failed validation
+    pub(crate) fn reject_last(&self) {
+        let mut rejected = self.rejected.borrow_mut();
+        if rejected.is_none() {
+            *rejected = self.last.borrow().clone();
+        }
+    }
+
+    /// Forget the rejected answer so it is asked for again on the next attempt, returns `false`
+    /// if nothing was rejected or too many answers were rejected already
+    pub(crate) fn retry(&self, error: String) -> bool {
+        let rejected = match self.rejected.borrow_mut().take() {
+            Some(rejected) => rejected,
+            None => return false,
+        };
+        let mut attempts = self.attempts.borrow_mut();
+        *attempts += 1;
+        if *attempts >= MAX_ATTEMPTS {
+            return false;
+        }
+        *self.error.borrow_mut() = Some(error);
+        self.answers.borrow_mut().retain(|(m, _)| *m != rejected);
+        true
+    }
+}
+
+/// Prompting state stored in [`State`](crate::State)
+#[derive(Debug, Clone)]
+pub(crate) struct PromptState {
+    pub(crate) session: Rc<Session>,
+    /// Instead of asking return an empty answer, used to get completion candidates
+    pub(crate) probe: bool,
+    /// Instead of asking return no answer and set `deferred`, completion candidates are only
+    /// collected for parsers that get to asking
+    pub(crate) defer: bool,
+    /// Parser wanted to ask something while `defer` was set
+    pub(crate) deferred: bool,
+    /// Completion candidates for the next prompt
+    pub(crate) choices: Option<Rc<[String]>>,
+}
+
+impl PromptState {
+    pub(crate) fn ask(&mut self, prompt: &Prompt) -> Option<OsString> {
+        if self.probe {
+            return Some(OsString::new());
+        }
+        if self.defer {
+            self.deferred = true;
+            return None;
+        }
+        let choices = self.choices.take();
+        let choices = choices.as_deref().unwrap_or(&[]);
+        self.session.ask(prompt, choices).map(OsString::from)
+    }
+}
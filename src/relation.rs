@@ -0,0 +1,305 @@
+//! Relations between parsers: one parser requires or excludes another, or a group of items
+//! needs at least or exactly one of them to be present
+//!
+//! For `requires`, `conflicts_with` and `required_if` presence is decided by looking at the
+//! command line only: a parser is present if it consumes something, values coming from
+//! environment variables or fallbacks don't count. `at_least_one_of` and `exactly_one_of` also
+//! count values coming from environment variables and config sources.
+
+use std::marker::PhantomData;
+
+use crate::{
+    args::{Arg, State},
+    buffer::Doc,
+    error::Message,
+    item::Item,
+    locale::Phrase,
+    Error, Meta, Parser, ValueSource,
+};
+
+/// Other side of a relation, checked against the command line as it was when the current
+/// subparser started
+trait Probe {
+    /// `Some` if parser is satisfied, along with the index of the first item it consumed, if any
+    fn check(&self, args: &State) -> Option<Option<usize>>;
+
+    fn meta(&self) -> Meta;
+}
+
+/// Satisfied if the parser consumes anything from the command line
+struct Present<P, T> {
+    inner: P,
+    ty: PhantomData<T>,
+}
+
+impl<P, T> Probe for Present<P, T>
+where
+    P: Parser<T>,
+{
+    fn check(&self, args: &State) -> Option<Option<usize>> {
+        let mut probe = args.probe();
+        let before = probe.clone();
+        let _ = self.inner.eval(&mut probe);
+        probe.taken_since(&before).first().map(|ix| Some(*ix))
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
+}
+
+/// Satisfied if the parser produces `true`
+struct Condition<P> {
+    inner: P,
+}
+
+impl<P> Probe for Condition<P>
+where
+    P: Parser<bool>,
+{
+    fn check(&self, args: &State) -> Option<Option<usize>> {
+        let mut probe = args.probe();
+        let before = probe.clone();
+        match self.inner.eval(&mut probe) {
+            Ok(true) => Some(probe.taken_since(&before).first().copied()),
+            Ok(false) | Err(_) => None,
+        }
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
+}
+
+enum Relation {
+    Requires,
+    ConflictsWith,
+    RequiredIf,
+}
+
+/// Parser with a relation to some other parser, created with [`requires`](Parser::requires),
+/// [`conflicts_with`](Parser::conflicts_with) or [`required_if`](Parser::required_if)
+pub struct ParseRelation<P> {
+    inner: P,
+    other: Box<dyn Probe>,
+    relation: Relation,
+}
+
+impl<P> ParseRelation<P> {
+    pub(crate) fn requires<Q, U>(inner: P, other: Q) -> Self
+    where
+        Q: Parser<U> + 'static,
+        U: 'static,
+    {
+        let other = Box::new(Present {
+            inner: other,
+            ty: PhantomData,
+        });
+        ParseRelation {
+            inner,
+            other,
+            relation: Relation::Requires,
+        }
+    }
+
+    pub(crate) fn conflicts_with<Q, U>(inner: P, other: Q) -> Self
+    where
+        Q: Parser<U> + 'static,
+        U: 'static,
+    {
+        let other = Box::new(Present {
+            inner: other,
+            ty: PhantomData,
+        });
+        ParseRelation {
+            inner,
+            other,
+            relation: Relation::ConflictsWith,
+        }
+    }
+
+    pub(crate) fn required_if<Q>(inner: P, cond: Q) -> Self
+    where
+        Q: Parser<bool> + 'static,
+    {
+        ParseRelation {
+            inner,
+            other: Box::new(Condition { inner: cond }),
+            relation: Relation::RequiredIf,
+        }
+    }
+}
+
+impl<P, T> Parser<T> for ParseRelation<P>
+where
+    P: Parser<T>,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        let before = args.clone();
+        let res = self.inner.eval(args)?;
+        #[cfg(feature = "autocomplete")]
+        if args.is_comp() {
+            return Ok(res);
+        }
+        let mine = args.taken_since(&before).first().copied();
+        let other = self.other.check(args);
+        let err = match (&self.relation, mine, other) {
+            (Relation::Requires, Some(ix), None) => {
+                Message::Requires(Some(ix), Box::new(usage_doc(&self.other.meta())))
+            }
+            (Relation::ConflictsWith, Some(a), Some(Some(b))) => {
+                Message::Conflict(a.min(b), a.max(b))
+            }
+            (Relation::RequiredIf, None, Some(trigger)) => {
+                Message::Requires(trigger, Box::new(usage_doc(&self.inner.meta())))
+            }
+            _ => return Ok(res),
+        };
+        Err(Error(err))
+    }
+
+    fn meta(&self) -> Meta {
+        let inner = self.inner.meta();
+        let other = usage_doc(&self.other.meta());
+        if other.is_empty() {
+            return inner;
+        }
+        let phrase = match self.relation {
+            Relation::Requires => Phrase::RequiresNote,
+            Relation::ConflictsWith => Phrase::ConflictsNote,
+            Relation::RequiredIf => Phrase::RequiredIfNote,
+        };
+        Meta::Suffix(Box::new(inner), phrase, Box::new(other))
+    }
+}
+
+/// Group of items that needs some of them present, created with
+/// [`at_least_one_of`](Parser::at_least_one_of) or [`exactly_one_of`](Parser::exactly_one_of)
+pub struct ParseOneOf<P> {
+    pub(crate) inner: P,
+    /// Reject more than one item
+    pub(crate) exactly: bool,
+}
+
+impl<P, T> Parser<T> for ParseOneOf<P>
+where
+    P: Parser<T>,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        let before = args.clone();
+        let res = self.inner.eval(args)?;
+        #[cfg(feature = "autocomplete")]
+        if args.is_comp() {
+            return Ok(res);
+        }
+        let taken = args.taken_since(&before);
+        let implicit = &args.implicit[before.implicit.len()..];
+        let mut items = Vec::new();
+        collect_items(&self.inner.meta(), &mut items);
+
+        if taken.is_empty() && implicit.is_empty() {
+            return Err(Error(Message::Expected(items, None)));
+        }
+
+        if self.exactly {
+            // item that was seen first along with its position on the command line
+            let mut first = None;
+            for ix in taken {
+                let arg = &args.items[ix];
+                if let Some(item) = items.iter().position(|item| names_match(item, arg)) {
+                    match first {
+                        Some((seen, winner)) if seen != item => {
+                            return Err(Error(Message::Conflict(winner, ix)));
+                        }
+                        Some(_) => {}
+                        None => first = Some((item, ix)),
+                    }
+                }
+            }
+
+            // values from environment variables and config sources come from different items
+            let mut winner = first.map(|(_, ix)| ValueSource::Argv {
+                index: args.origin(ix),
+                spelling: args.items[ix].to_string(),
+            });
+            for source in implicit {
+                match winner {
+                    Some(winner) => {
+                        let pair = Box::new((winner, source.clone()));
+                        return Err(Error(Message::SourceConflict(pair)));
+                    }
+                    None => winner = Some(source.clone()),
+                }
+            }
+        }
+        Ok(res)
+    }
+
+    fn meta(&self) -> Meta {
+        let inner = self.inner.meta();
+        let mut items = Vec::new();
+        collect_items(&inner, &mut items);
+        let mut doc = Doc::default();
+        for (ix, item) in items.iter().enumerate() {
+            if ix > 0 {
+                doc.text(", ");
+            }
+            doc.write_item(item);
+        }
+        let phrase = if self.exactly {
+            Phrase::ExactlyOneNote
+        } else {
+            Phrase::AtLeastOneNote
+        };
+        Meta::Suffix(Box::new(inner), phrase, Box::new(doc))
+    }
+}
+
+/// Usage for a parser on the other side of a relation, without optional brackets around it
+fn usage_doc(meta: &Meta) -> Doc {
+    fn strip(meta: &Meta) -> &Meta {
+        match meta {
+            Meta::Optional(m) | Meta::Required(m) | Meta::Many(m) | Meta::Suffix(m, _, _) => {
+                strip(m)
+            }
+            m => m,
+        }
+    }
+    let mut doc = Doc::default();
+    doc.write_meta(strip(meta), true);
+    doc
+}
+
+/// Visible flags, arguments and positional items
+fn collect_items(meta: &Meta, items: &mut Vec<Item>) {
+    match meta {
+        Meta::And(xs) | Meta::Or(xs) => {
+            for x in xs {
+                collect_items(x, items);
+            }
+        }
+        Meta::Item(item) => items.push(item.as_ref().clone()),
+        Meta::Optional(m)
+        | Meta::Required(m)
+        | Meta::Adjacent(m)
+        | Meta::Many(m)
+        | Meta::Subsection(m, _)
+        | Meta::Suffix(m, _, _)
+        | Meta::CustomUsage(m, _)
+        | Meta::Strict(m)
+        | Meta::Completion(m, _) => collect_items(m, items),
+        Meta::Skip | Meta::Hidden(_) => {}
+    }
+}
+
+/// Check if a command line item is one of the names of a flag or an argument
+fn names_match(item: &Item, arg: &Arg) -> bool {
+    match item {
+        Item::Flag { name, shorts, .. } | Item::Argument { name, shorts, .. } => match arg {
+            Arg::Short(c, _, _) => name.as_short() == Some(*c) || shorts.contains(c),
+            Arg::Long(l, _, _) => name.as_long() == Some(l.as_str()),
+            Arg::ArgWord(_) | Arg::Word(_) | Arg::PosWord(_) => false,
+        },
+        Item::Positional { .. } | Item::Command { .. } | Item::Any { .. } => false,
+    }
+}
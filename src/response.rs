@@ -0,0 +1,168 @@
+//! Response file expansion, see [`Args::with_response_files`]
+//!
+//! A word `@path` on the command line is replaced with words read from the file at `path`.
+//! Words in the file are separated by whitespace, single and double quotes group words with
+//! spaces and backslash escapes the next character everywhere but inside single quotes.
+//! Response files can refer to other response files, nesting is limited to [`MAX_DEPTH`] levels.
+//! Words after `--` are never expanded.
+
+use std::{ffi::OsString, path::Path, rc::Rc};
+
+#[cfg(doc)]
+use crate::Args;
+
+/// How deep response files can be nested, this also catches files including themselves
+const MAX_DEPTH: usize = 16;
+
+/// Position of a word that was read from a response file
+#[derive(Debug, Clone)]
+pub(crate) struct WordSource {
+    pub(crate) file: Rc<str>,
+    pub(crate) line: usize,
+}
+
+/// Response file can't be read or parsed
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseError {
+    pub(crate) file: String,
+    /// Line number, if problem is with file contents
+    pub(crate) line: Option<usize>,
+    pub(crate) message: String,
+}
+
+pub(crate) type Word = (OsString, Option<WordSource>);
+
+/// Expand all the response files in a list of words
+pub(crate) fn expand(items: impl Iterator<Item = OsString>) -> Result<Vec<Word>, ResponseError> {
+    let mut res = Vec::new();
+    let mut pos_only = false;
+    for os in items {
+        push_word(&mut res, os, None, 0, &mut pos_only)?;
+    }
+    Ok(res)
+}
+
+fn push_word(
+    res: &mut Vec<Word>,
+    os: OsString,
+    source: Option<WordSource>,
+    depth: usize,
+    pos_only: &mut bool,
+) -> Result<(), ResponseError> {
+    match os.to_str().and_then(|s| s.strip_prefix('@')) {
+        Some(path) if !path.is_empty() && !*pos_only => {
+            if depth >= MAX_DEPTH {
+                return Err(ResponseError {
+                    file: source
+                        .as_ref()
+                        .map_or_else(String::new, |s| s.file.to_string()),
+                    line: source.map(|s| s.line),
+                    message: format!(
+                        "response files are nested more than {} levels deep",
+                        MAX_DEPTH
+                    ),
+                });
+            }
+            read_file(res, Path::new(path), depth + 1, pos_only)
+        }
+        _ => {
+            *pos_only |= os == "--";
+            res.push((os, source));
+            Ok(())
+        }
+    }
+}
+
+fn read_file(
+    res: &mut Vec<Word>,
+    path: &Path,
+    depth: usize,
+    pos_only: &mut bool,
+) -> Result<(), ResponseError> {
+    let name = path.to_string_lossy().into_owned();
+    let contents = std::fs::read_to_string(path).map_err(|e| ResponseError {
+        file: name.clone(),
+        line: None,
+        message: e.to_string(),
+    })?;
+    let file: Rc<str> = Rc::from(name);
+    for (word, line) in split(&contents).map_err(|line| ResponseError {
+        file: file.to_string(),
+        line: Some(line),
+        message: "unterminated quote".to_owned(),
+    })? {
+        let source = WordSource {
+            file: file.clone(),
+            line,
+        };
+        push_word(res, OsString::from(word), Some(source), depth, pos_only)?;
+    }
+    Ok(())
+}
+
+/// Split response file contents into words with line numbers they start at
+///
+/// Returns the line of an unterminated quote on error
+fn split(contents: &str) -> Result<Vec<(String, usize)>, usize> {
+    let mut res = Vec::new();
+    let mut chars = contents.chars();
+    let mut line = 1;
+    // current word and line it starts at
+    let mut word: Option<(String, usize)> = None;
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if let Some(w) = word.take() {
+                    res.push(w);
+                }
+                if c == '\n' {
+                    line += 1;
+                }
+            }
+            '\'' | '"' => {
+                let start = line;
+                let (w, _) = word.get_or_insert_with(|| (String::new(), line));
+                loop {
+                    match chars.next() {
+                        None => return Err(start),
+                        Some(q) if q == c => break,
+                        Some('\\') if c == '"' => match chars.next() {
+                            None => return Err(start),
+                            Some(e) => {
+                                line += usize::from(e == '\n');
+                                w.push(e);
+                            }
+                        },
+                        Some(e) => {
+                            line += usize::from(e == '\n');
+                            w.push(e);
+                        }
+                    }
+                }
+            }
+            '\\' => {
+                let (w, _) = word.get_or_insert_with(|| (String::new(), line));
+                if let Some(e) = chars.next() {
+                    line += usize::from(e == '\n');
+                    w.push(e);
+                }
+            }
+            c => word.get_or_insert_with(|| (String::new(), line)).0.push(c),
+        }
+    }
+    res.extend(word);
+    Ok(res)
+}
+
+#[test]
+fn split_quoting() {
+    let words = split("-a 'b c'\n\"d\\\"e\" f\\ g\n\n@h").unwrap();
+    let expected = [("-a", 1), ("b c", 1), ("d\"e", 2), ("f g", 2), ("@h", 4)];
+    assert_eq!(words.len(), expected.len());
+    for ((w, l), (ew, el)) in words.iter().zip(expected.iter()) {
+        assert_eq!((w.as_str(), *l), (*ew, *el));
+    }
+
+    assert_eq!(split("a\n'b\nc").unwrap_err(), 2);
+}
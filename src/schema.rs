@@ -0,0 +1,436 @@
+//! Machine readable description of a parser, see [`OptionParser::render_json_schema`]
+
+use crate::{
+    buffer::{Doc, MONOCHROME},
+    info::Info,
+    item::{Item, ShortLong},
+    locale::Phrase,
+    Meta, OptionParser,
+};
+
+/// Version of the schema format, changes whenever existing fields change their meaning
+/// or get removed
+const SCHEMA_VERSION: usize = 1;
+
+/// Minimal JSON value, enough to describe a parser
+enum Json {
+    Null,
+    Bool(bool),
+    Num(usize),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(&'static str, Json)>),
+}
+
+impl From<Option<String>> for Json {
+    fn from(value: Option<String>) -> Self {
+        value.map_or(Json::Null, Json::Str)
+    }
+}
+
+impl Json {
+    fn write(&self, res: &mut String, indent: usize) {
+        match self {
+            Json::Null => res.push_str("null"),
+            Json::Bool(b) => res.push_str(if *b { "true" } else { "false" }),
+            Json::Num(n) => res.push_str(&n.to_string()),
+            Json::Str(s) => write_str(res, s),
+            Json::Arr(xs) if xs.is_empty() => res.push_str("[]"),
+            Json::Arr(xs) => {
+                res.push('[');
+                for (ix, x) in xs.iter().enumerate() {
+                    if ix > 0 {
+                        res.push(',');
+                    }
+                    newline(res, indent + 1);
+                    x.write(res, indent + 1);
+                }
+                newline(res, indent);
+                res.push(']');
+            }
+            Json::Obj(fields) => {
+                res.push('{');
+                for (ix, (key, val)) in fields.iter().enumerate() {
+                    if ix > 0 {
+                        res.push(',');
+                    }
+                    newline(res, indent + 1);
+                    write_str(res, key);
+                    res.push_str(": ");
+                    val.write(res, indent + 1);
+                }
+                newline(res, indent);
+                res.push('}');
+            }
+        }
+    }
+}
+
+fn newline(res: &mut String, indent: usize) {
+    res.push('\n');
+    for _ in 0..indent {
+        res.push_str("  ");
+    }
+}
+
+fn write_str(res: &mut String, s: &str) {
+    res.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+}
+
+/// Plain text version of a document, without line wrapping
+fn text(doc: &Doc) -> String {
+    doc.render_console(true, &MONOCHROME, usize::MAX)
+}
+
+/// Properties items inherit from the surrounding parsers
+#[derive(Clone, Copy)]
+struct Ctx<'a> {
+    required: bool,
+    repeated: bool,
+    hidden: bool,
+    default: Option<&'a Doc>,
+}
+
+/// Kind of a [`Described`] item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Flag,
+    Argument,
+    Positional,
+    Any,
+}
+
+impl Kind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Kind::Flag => "flag",
+            Kind::Argument => "argument",
+            Kind::Positional => "positional",
+            Kind::Any => "any",
+        }
+    }
+}
+
+/// Single item of a command, with properties inherited from the surrounding parsers
+#[derive(Debug, Clone)]
+pub(crate) struct Described {
+    pub(crate) kind: Kind,
+    pub(crate) short: Option<char>,
+    pub(crate) long: Option<String>,
+    pub(crate) metavar: Option<String>,
+    pub(crate) env: Option<String>,
+    pub(crate) config: Option<String>,
+    pub(crate) help: Option<String>,
+    pub(crate) default: Option<String>,
+    pub(crate) required: bool,
+    pub(crate) repeated: bool,
+    pub(crate) hidden: bool,
+    /// values accepted by `choice`, empty if any value is accepted
+    pub(crate) choices: Vec<String>,
+    /// `choices` restrict keys of `KEY=VALUE` pairs
+    pub(crate) choice_keys: bool,
+    /// flag accepts `--no-` form
+    pub(crate) negatable: bool,
+    /// value can be omitted: `--color[=WHEN]`
+    pub(crate) optional_value: bool,
+    /// positional captures everything after it as is
+    pub(crate) passthrough: bool,
+}
+
+impl Described {
+    fn to_json(&self) -> Json {
+        Json::Obj(vec![
+            ("kind", Json::Str(self.kind.as_str().to_owned())),
+            ("short", Json::from(self.short.map(String::from))),
+            ("long", Json::from(self.long.clone())),
+            ("metavar", Json::from(self.metavar.clone())),
+            ("env", Json::from(self.env.clone())),
+            ("config", Json::from(self.config.clone())),
+            ("help", Json::from(self.help.clone())),
+            ("default", Json::from(self.default.clone())),
+            ("required", Json::Bool(self.required)),
+            ("repeated", Json::Bool(self.repeated)),
+            ("hidden", Json::Bool(self.hidden)),
+            (
+                "choices",
+                Json::Arr(self.choices.iter().cloned().map(Json::Str).collect()),
+            ),
+            ("choice_keys", Json::Bool(self.choice_keys)),
+            ("negatable", Json::Bool(self.negatable)),
+            ("optional_value", Json::Bool(self.optional_value)),
+            ("passthrough", Json::Bool(self.passthrough)),
+        ])
+    }
+}
+
+/// A parser or a subcommand along with all the nested subcommands
+#[derive(Debug, Clone)]
+pub(crate) struct Command {
+    pub(crate) name: String,
+    pub(crate) short: Option<char>,
+    pub(crate) help: Option<String>,
+    pub(crate) hidden: bool,
+    pub(crate) version: Option<String>,
+    pub(crate) description: Option<String>,
+    pub(crate) header: Option<String>,
+    pub(crate) footer: Option<String>,
+    pub(crate) items: Vec<Described>,
+    pub(crate) commands: Vec<Command>,
+}
+
+impl Command {
+    /// Describe a parser given its meta and info
+    pub(crate) fn new(name: String, meta: &Meta, info: &Info) -> Self {
+        let mut res = Command {
+            name,
+            short: None,
+            help: None,
+            hidden: false,
+            version: info.version.as_ref().map(text),
+            description: info.descr.as_ref().map(text),
+            header: info.header.as_ref().map(text),
+            footer: info.footer.as_ref().map(text),
+            items: Vec::new(),
+            commands: Vec::new(),
+        };
+        let ctx = Ctx {
+            required: true,
+            repeated: false,
+            hidden: false,
+            default: None,
+        };
+        res.collect(meta, ctx);
+        let ctx = Ctx {
+            required: false,
+            ..ctx
+        };
+        res.collect(&info.help_meta(&info.messages), ctx);
+        res
+    }
+
+    fn collect(&mut self, meta: &Meta, ctx: Ctx) {
+        match meta {
+            Meta::And(xs) => {
+                for x in xs {
+                    self.collect(x, ctx);
+                }
+            }
+            Meta::Or(xs) => {
+                let ctx = Ctx {
+                    required: ctx.required && xs.len() < 2,
+                    ..ctx
+                };
+                for x in xs {
+                    self.collect(x, ctx);
+                }
+            }
+            Meta::Optional(m) => {
+                let ctx = Ctx {
+                    required: false,
+                    ..ctx
+                };
+                self.collect(m, ctx);
+            }
+            Meta::Many(m) => {
+                let ctx = Ctx {
+                    repeated: true,
+                    ..ctx
+                };
+                self.collect(m, ctx);
+            }
+            Meta::Hidden(m) => {
+                let ctx = Ctx {
+                    hidden: true,
+                    ..ctx
+                };
+                self.collect(m, ctx);
+            }
+            Meta::Suffix(m, Phrase::Default, default) => {
+                let ctx = Ctx {
+                    default: Some(default),
+                    ..ctx
+                };
+                self.collect(m, ctx);
+            }
+            Meta::Suffix(m, _, _) => self.collect(m, ctx),
+            Meta::Required(m)
+            | Meta::Adjacent(m)
+            | Meta::Subsection(m, _)
+            | Meta::CustomUsage(m, _)
+            | Meta::Strict(m)
+            | Meta::Completion(m, _) => self.collect(m, ctx),
+            Meta::Item(item) => self.push_item(item, ctx),
+            Meta::Skip => {}
+        }
+    }
+
+    fn push_item(&mut self, item: &Item, ctx: Ctx) {
+        let (kind, name, metavar, env, config, help) = match item {
+            Item::Command {
+                name,
+                short,
+                help,
+                meta,
+                info,
+                lazy_help: _,
+            } => {
+                let mut cmd = Command::new(name.to_string(), meta, info);
+                cmd.short = *short;
+                cmd.help = help.as_ref().map(text);
+                cmd.hidden = ctx.hidden;
+                self.commands.push(cmd);
+                return;
+            }
+            Item::Any { metavar, help, .. } => {
+                (Kind::Any, None, Some(text(metavar)), &None, &None, help)
+            }
+            Item::Positional { metavar, help, .. } => (
+                Kind::Positional,
+                None,
+                Some(metavar.0.to_string()),
+                &None,
+                &None,
+                help,
+            ),
+            Item::Flag {
+                name,
+                env,
+                config,
+                help,
+                ..
+            } => (Kind::Flag, Some(name), None, env, config, help),
+            Item::Argument {
+                name,
+                metavar,
+                env,
+                config,
+                help,
+                ..
+            } => (
+                Kind::Argument,
+                Some(name),
+                Some(metavar.0.to_string()),
+                env,
+                config,
+                help,
+            ),
+        };
+        let mut described = Described {
+            kind,
+            short: name.and_then(ShortLong::as_short),
+            long: name.and_then(ShortLong::as_long).map(String::from),
+            metavar,
+            env: env.as_ref().map(|e| e.to_string()),
+            config: config.as_ref().map(|c| c.to_string()),
+            help: help.as_ref().map(text),
+            default: ctx.default.map(text),
+            required: ctx.required,
+            repeated: ctx.repeated,
+            hidden: ctx.hidden,
+            choices: Vec::new(),
+            choice_keys: false,
+            negatable: false,
+            optional_value: false,
+            passthrough: false,
+        };
+        match item {
+            Item::Positional {
+                choices,
+                passthrough,
+                ..
+            } => {
+                described.choices = choices.iter().map(|c| c.to_string()).collect();
+                described.passthrough = *passthrough;
+            }
+            Item::Flag { negatable, .. } => described.negatable = *negatable,
+            Item::Argument {
+                choices,
+                choice_keys,
+                optional_value,
+                ..
+            } => {
+                described.choices = choices.iter().map(|c| c.to_string()).collect();
+                described.choice_keys = *choice_keys;
+                described.optional_value = *optional_value;
+            }
+            Item::Command { .. } | Item::Any { .. } => {}
+        }
+        self.items.push(described);
+    }
+
+    /// Fields shared between the top level parser and subcommands
+    fn parser_fields(&self) -> Vec<(&'static str, Json)> {
+        vec![
+            ("version", Json::from(self.version.clone())),
+            ("description", Json::from(self.description.clone())),
+            ("header", Json::from(self.header.clone())),
+            ("footer", Json::from(self.footer.clone())),
+            (
+                "items",
+                Json::Arr(self.items.iter().map(Described::to_json).collect()),
+            ),
+            (
+                "commands",
+                Json::Arr(self.commands.iter().map(Command::to_json).collect()),
+            ),
+        ]
+    }
+
+    fn to_json(&self) -> Json {
+        let mut fields = vec![
+            ("name", Json::Str(self.name.clone())),
+            ("short", Json::from(self.short.map(String::from))),
+            ("help", Json::from(self.help.clone())),
+            ("hidden", Json::Bool(self.hidden)),
+        ];
+        fields.extend(self.parser_fields());
+        Json::Obj(fields)
+    }
+}
+
+impl<T> OptionParser<T> {
+    /// Render a description of the command line interface as JSON
+    ///
+    /// Output is meant for external tools such as documentation generators or GUI launchers and
+    /// describes the parser along with all the nested subcommands: named and positional items,
+    /// metavariables, environment variables, config keys, help, defaults set with
+    /// [`display_fallback`](crate::parsers::ParseFallback::display_fallback), hidden status,
+    /// accepted values and the forms items can take.
+    /// Object keys are always present and their order is stable so output can be checked into
+    /// a repository and compared in CI to catch incompatible changes.
+    ///
+    /// Top level object contains a `schema_version` field, it changes whenever existing fields
+    /// change their meaning or get removed.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = short('v').long("verbose").switch().to_options();
+    /// let schema = parser.render_json_schema("app");
+    /// assert!(schema.contains("\"schema_version\": 1"));
+    /// assert!(schema.contains("\"long\": \"verbose\""));
+    /// ```
+    #[must_use]
+    pub fn render_json_schema(&self, app: impl Into<String>) -> String {
+        let cmd = Command::new(app.into(), &self.documented_meta(), &self.info);
+        let mut fields = vec![
+            ("schema_version", Json::Num(SCHEMA_VERSION)),
+            ("name", Json::Str(cmd.name.clone())),
+        ];
+        fields.extend(cmd.parser_fields());
+        let mut res = String::new();
+        Json::Obj(fields).write(&mut res, 0);
+        res.push('\n');
+        res
+    }
+}
@@ -3,9 +3,13 @@ use crate::{
     args::State,
     buffer::MetaInfo,
     error::{Message, MissingItem},
+    locale::Phrase,
     Doc, Error, Meta, Parser,
 };
-use std::marker::PhantomData;
+use std::{borrow::Cow, marker::PhantomData};
+
+#[cfg(feature = "autocomplete")]
+use crate::meta::CompHint;
 
 /// Parser that substitutes missing value with a function results but not parser
 /// failure, created with [`fallback_with`](Parser::fallback_with).
@@ -13,7 +17,7 @@ pub struct ParseFallbackWith<T, P, F, E> {
     pub(crate) inner: P,
     pub(crate) inner_res: PhantomData<T>,
     pub(crate) fallback: F,
-    pub(crate) value_str: String,
+    pub(crate) value_str: Option<String>,
     pub(crate) err: PhantomData<E>,
 }
 
@@ -25,7 +29,7 @@ where
 {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
         let mut clone = args.clone();
-        match self.inner.eval(&mut clone) {
+        match clone.without_prompt(|clone| self.inner.eval(clone)) {
             Ok(ok) => {
                 std::mem::swap(args, &mut clone);
                 Ok(ok)
@@ -34,6 +38,7 @@ where
                 #[cfg(feature = "autocomplete")]
                 args.swap_comps(&mut clone);
                 if e.can_catch() {
+                    args.value_source = Some(ValueSource::Fallback);
                     match (self.fallback)() {
                         Ok(ok) => Ok(ok),
                         Err(e) => Err(Error(Message::PureFailed(e.to_string()))),
@@ -47,11 +52,13 @@ where
 
     fn meta(&self) -> Meta {
         let m = Meta::Optional(Box::new(self.inner.meta()));
-        if self.value_str.is_empty() {
-            m
-        } else {
-            let buf = Doc::from(self.value_str.as_str());
-            Meta::Suffix(Box::new(m), Box::new(buf))
+        match &self.value_str {
+            Some(value) => Meta::Suffix(
+                Box::new(m),
+                Phrase::Default,
+                Box::new(Doc::from(value.as_str())),
+            ),
+            None => m,
         }
     }
 }
@@ -117,7 +124,7 @@ where
 /// Implements [`catch`](ParseMany::catch)
 pub struct ParseSome<P> {
     pub(crate) inner: P,
-    pub(crate) message: &'static str,
+    pub(crate) message: Cow<'static, str>,
     pub(crate) catch: bool,
 }
 
@@ -151,7 +158,7 @@ where
         }
 
         if res.is_empty() {
-            Err(Error(Message::ParseSome(self.message)))
+            Err(Error(Message::ParseSome(self.message.clone())))
         } else {
             Ok(res)
         }
@@ -234,7 +241,7 @@ where
     }
 
     fn meta(&self) -> Meta {
-        Meta::Skip
+        Meta::Hidden(Box::new(self.inner.meta()))
     }
 }
 
@@ -267,6 +274,17 @@ pub struct ParseOrElse<T> {
 
 impl<T> Parser<T> for ParseOrElse<T> {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
+        // ask for missing values only if neither branch succeeds without them, otherwise the
+        // branch that is present on the command line would ask for values of the other one
+        #[cfg(feature = "prompt")]
+        if args.prompt.is_some() {
+            let mut quiet = args.clone();
+            if let Ok(ok) = quiet.without_prompt(|quiet| self.eval(quiet)) {
+                std::mem::swap(args, &mut quiet);
+                return Ok(ok);
+            }
+        }
+
         #[cfg(feature = "autocomplete")]
         let mut comp_items = Vec::new();
         #[cfg(feature = "autocomplete")]
@@ -439,7 +457,11 @@ where
         let t = self.inner.eval(args)?;
         match (self.parse_fn)(t) {
             Ok(r) => Ok(r),
-            Err(e) => Err(Error(Message::ParseFailed(args.current, e.to_string()))),
+            Err(e) => {
+                #[cfg(feature = "prompt")]
+                args.reject_prompt();
+                Err(Error(Message::ParseFailed(args.current, e.to_string())))
+            }
         }
     }
 
@@ -448,12 +470,65 @@ where
     }
 }
 
+/// Parser that reports where the value came from, created with
+/// [`with_source`](Parser::with_source).
+pub struct ParseWithSource<P> {
+    pub(crate) inner: P,
+}
+
+impl<T, P> Parser<(T, ValueSource)> for ParseWithSource<P>
+where
+    P: Parser<T>,
+{
+    fn eval(&self, args: &mut State) -> Result<(T, ValueSource), Error> {
+        let prev = args.value_source.take();
+        let res = self.inner.eval(args);
+        let source = std::mem::replace(&mut args.value_source, prev);
+        Ok((res?, source.unwrap_or(ValueSource::Default)))
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
+}
+
+/// Where a value produced by a parser came from, see [`with_source`](Parser::with_source)
+///
+/// For parsers that consume several items, such as [`many`](Parser::many) or
+/// [`construct!`](crate::construct!), this describes the last consumed item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValueSource {
+    /// Value was taken from the command line
+    Argv {
+        /// Index of the argument, not counting the program name
+        index: usize,
+        /// Name of a flag or an argument as the user spelled it: `-v` or `--verbose`,
+        /// for positional items - the item itself
+        spelling: String,
+    },
+    /// Value was taken from an environment variable with this name
+    Env(Cow<'static, str>),
+    /// Value was taken from a [config source](crate::OptionParser::config_source) with this key
+    Config(Cow<'static, str>),
+    /// Value was produced by [`fallback`](Parser::fallback) or
+    /// [`fallback_with`](Parser::fallback_with)
+    Fallback,
+    /// Value was entered by the user, see [`interactive`](crate::OptionParser::interactive)
+    #[cfg(feature = "prompt")]
+    Prompt,
+    /// Parser succeeded without consuming anything: [`pure`](crate::pure), absent
+    /// [`switch`](crate::parsers::NamedArg::switch) or [`optional`](Parser::optional) item and
+    /// similar
+    Default,
+}
+
 /// Parser that substitutes missing value but not parse failure, created with
 /// [`fallback`](Parser::fallback).
 pub struct ParseFallback<P, T> {
     pub(crate) inner: P,
     pub(crate) value: T,
-    pub(crate) value_str: String,
+    pub(crate) value_str: Option<String>,
 }
 
 impl<P, T> Parser<T> for ParseFallback<P, T>
@@ -463,7 +538,7 @@ where
 {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
         let mut clone = args.clone();
-        match self.inner.eval(&mut clone) {
+        match clone.without_prompt(|clone| self.inner.eval(clone)) {
             Ok(ok) => {
                 std::mem::swap(args, &mut clone);
                 Ok(ok)
@@ -472,6 +547,7 @@ where
                 #[cfg(feature = "autocomplete")]
                 args.swap_comps(&mut clone);
                 if e.can_catch() {
+                    args.value_source = Some(ValueSource::Fallback);
                     Ok(self.value.clone())
                 } else {
                     Err(Error(e))
@@ -482,11 +558,13 @@ where
 
     fn meta(&self) -> Meta {
         let m = Meta::Optional(Box::new(self.inner.meta()));
-        if self.value_str.is_empty() {
-            m
-        } else {
-            let buf = Doc::from(self.value_str.as_str());
-            Meta::Suffix(Box::new(m), Box::new(buf))
+        match &self.value_str {
+            Some(value) => Meta::Suffix(
+                Box::new(m),
+                Phrase::Default,
+                Box::new(Doc::from(value.as_str())),
+            ),
+            None => m,
         }
     }
 }
@@ -498,7 +576,7 @@ impl<P, T: std::fmt::Display> ParseFallback<P, T> {
     #[cfg_attr(not(doctest), doc = include_str!("docs2/dis_fallback.md"))]
     #[must_use]
     pub fn display_fallback(mut self) -> Self {
-        self.value_str = format!("[default: {}]", self.value);
+        self.value_str = Some(self.value.to_string());
         self
     }
 }
@@ -510,7 +588,7 @@ impl<P, T: std::fmt::Debug> ParseFallback<P, T> {
     #[cfg_attr(not(doctest), doc = include_str!("docs2/deb_fallback_with.md"))]
     #[must_use]
     pub fn debug_fallback(mut self) -> Self {
-        self.value_str = format!("[default: {:?}]", self.value);
+        self.value_str = Some(format!("{:?}", self.value));
         self
     }
 }
@@ -528,7 +606,7 @@ where
     #[must_use]
     pub fn display_fallback(mut self) -> Self {
         if let Ok(val) = (self.fallback)() {
-            self.value_str = format!("[default: {}]", val);
+            self.value_str = Some(val.to_string());
         }
         self
     }
@@ -547,7 +625,7 @@ where
     #[must_use]
     pub fn debug_fallback(mut self) -> Self {
         if let Ok(val) = (self.fallback)() {
-            self.value_str = format!("[default: {:?}]", val);
+            self.value_str = Some(format!("{:?}", val));
         }
         self
     }
@@ -557,7 +635,7 @@ where
 pub struct ParseGuard<P, F> {
     pub(crate) inner: P,
     pub(crate) check: F,
-    pub(crate) message: &'static str,
+    pub(crate) message: Cow<'static, str>,
 }
 
 impl<T, P, F> Parser<T> for ParseGuard<P, F>
@@ -570,7 +648,12 @@ where
         if (self.check)(&t) {
             Ok(t)
         } else {
-            Err(Error(Message::GuardFailed(args.current, self.message)))
+            #[cfg(feature = "prompt")]
+            args.reject_prompt();
+            Err(Error(Message::GuardFailed(
+                args.current,
+                self.message.clone(),
+            )))
         }
     }
 
@@ -720,7 +803,7 @@ where
     P: Parser<T>,
 {
     let mut orig_args = args.clone();
-    match parser.eval(args) {
+    match args.without_prompt(|args| parser.eval(args)) {
         // we keep including values for as long as we consume values from the argument
         // list or at least one value
         Ok(val) => Ok(if args.len() < *len {
@@ -808,13 +891,13 @@ impl<T: Clone + 'static, F: Fn() -> Result<T, E>, E: ToString> Parser<T>
 
 /// Parser that fails without consuming any input, created with [`fail`](crate::fail).
 pub struct ParseFail<T> {
-    pub(crate) field1: &'static str,
+    pub(crate) field1: Cow<'static, str>,
     pub(crate) field2: PhantomData<T>,
 }
 impl<T> Parser<T> for ParseFail<T> {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
         args.current = None;
-        Err(Error(Message::ParseFail(self.field1)))
+        Err(Error(Message::ParseFail(self.field1.clone())))
     }
 
     fn meta(&self) -> Meta {
@@ -945,10 +1028,15 @@ impl<T> ParseCon<T> {
 #[cfg(feature = "autocomplete")]
 pub struct ParseComp<P, F> {
     pub(crate) inner: P,
-    pub(crate) op: F,
+    pub(crate) op: std::rc::Rc<F>,
     pub(crate) group: Option<String>,
+    /// Candidates for a value that failed to parse, see [`suggest_with`](ParseComp::suggest_with)
+    pub(crate) suggest: Option<std::rc::Rc<Suggestions>>,
 }
 
+#[cfg(feature = "autocomplete")]
+type Suggestions = dyn Fn() -> Vec<(String, Option<String>)>;
+
 #[cfg(feature = "autocomplete")]
 impl<P, F> ParseComp<P, F> {
     #[must_use]
@@ -957,6 +1045,152 @@ impl<P, F> ParseComp<P, F> {
         self.group = Some(group.into());
         self
     }
+
+    /// Suggest values completer produces for `value` when a value fails to parse
+    ///
+    /// When a value fails to parse `bpaf` looks for a close match among completion candidates
+    /// to suggest in the error message. Completer needs a parsed value to work with, this method
+    /// gives it one for values that can't be parsed. Only completers set with
+    /// [`complete`](Parser::complete) are used this way, shell completion is never invoked.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # use std::str::FromStr;
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    /// enum Format {
+    ///     Json,
+    ///     Yaml,
+    /// }
+    ///
+    /// impl FromStr for Format {
+    ///     type Err = String;
+    ///     fn from_str(s: &str) -> Result<Self, String> {
+    ///         match s {
+    ///             "json" => Ok(Format::Json),
+    ///             "yaml" => Ok(Format::Yaml),
+    ///             _ => Err(format!("{} is not a known format", s)),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// fn formats(_: &Format) -> Vec<(&'static str, Option<&'static str>)> {
+    ///     vec![("json", None), ("yaml", None)]
+    /// }
+    ///
+    /// let parser = long("format")
+    ///     .argument::<Format>("FORMAT")
+    ///     .complete(formats)
+    ///     .suggest_with(Format::Json)
+    ///     .to_options();
+    ///
+    /// let r = parser.run_inner(&["--format", "jsno"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "`jsno` is not a valid value, did you mean `json`?");
+    /// ```
+    #[must_use]
+    pub fn suggest_with<T, M>(mut self, value: T) -> Self
+    where
+        F: Fn(&T) -> Vec<(M, Option<M>)> + 'static,
+        T: 'static,
+        M: Into<String>,
+    {
+        let op = self.op.clone();
+        self.suggest = Some(std::rc::Rc::new(move || {
+            op(&value)
+                .into_iter()
+                .map(|(value, help)| (value.into(), help.map(Into::into)))
+                .collect()
+        }));
+        self
+    }
+
+    /// Replace metavariables added by inner parser with `values`, computed only when needed
+    fn push_values<M, V>(
+        &self,
+        comp: &mut crate::complete_gen::Complete,
+        comp_items: Vec<crate::complete_gen::Comp>,
+        depth: usize,
+        values: V,
+    ) where
+        M: Into<String>,
+        V: Fn() -> Vec<(M, Option<M>)>,
+    {
+        for ci in comp_items {
+            let is_meta = ci.is_metavar();
+            if let Some(is_arg) = is_meta {
+                let suggestions = values();
+                // strip metavar when completion makes a single good suggestion
+                if suggestions.len() != 1 {
+                    comp.push_comp(ci);
+                }
+                for (replacement, description) in suggestions {
+                    let group = self.group.clone();
+                    comp.push_value(
+                        replacement.into(),
+                        description.map(Into::into),
+                        group,
+                        depth,
+                        is_arg,
+                    );
+                }
+            } else {
+                comp.push_comp(ci);
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "autocomplete", feature = "prompt"))]
+impl<P, F> ParseComp<P, F> {
+    /// Run the inner parser, if it asks user for a value - offer completion candidates
+    ///
+    /// Inner parser runs with prompts deferred first, candidates are only collected when it
+    /// gets to asking: from a value it makes out of an empty answer
+    fn eval_prompted<T, M>(&self, args: &mut State) -> Result<T, Error>
+    where
+        P: Parser<T>,
+        M: Into<String>,
+        F: Fn(&T) -> Vec<(M, Option<M>)>,
+    {
+        match &args.prompt {
+            Some(prompt) if !prompt.defer && !prompt.probe => {}
+            _ => return self.inner.eval(args),
+        }
+
+        let mut attempt = args.clone();
+        if let Some(prompt) = &mut attempt.prompt {
+            prompt.defer = true;
+        }
+        let res = self.inner.eval(&mut attempt);
+        if let Some(prompt) = &mut attempt.prompt {
+            prompt.defer = false;
+            if !std::mem::take(&mut prompt.deferred) {
+                *args = attempt;
+                return res;
+            }
+        }
+
+        let mut probe = args.clone();
+        if let Some(prompt) = &mut probe.prompt {
+            prompt.probe = true;
+        }
+        probe.value_source = None;
+        if let Ok(val) = self.inner.eval(&mut probe) {
+            if probe.value_source == Some(ValueSource::Prompt) {
+                let choices = (self.op)(&val)
+                    .into_iter()
+                    .map(|(choice, _)| choice.into())
+                    .collect::<Vec<String>>();
+                if let Some(prompt) = &mut args.prompt {
+                    prompt.choices = Some(choices.into());
+                }
+            }
+        }
+        let res = self.inner.eval(args);
+        if let Some(prompt) = &mut args.prompt {
+            prompt.choices = None;
+        }
+        res
+    }
 }
 
 #[cfg(feature = "autocomplete")]
@@ -971,14 +1205,23 @@ where
         let mut comp_items = Vec::new();
         args.swap_comps_with(&mut comp_items);
 
+        #[cfg(feature = "prompt")]
+        let res = self.eval_prompted(args);
+        #[cfg(not(feature = "prompt"))]
         let res = self.inner.eval(args);
 
         // restore old, now metavars added by inner parser, if any, are in comp_items
         args.swap_comps_with(&mut comp_items);
 
+        let depth = args.depth();
         if let Some(comp) = &mut args.comp_mut() {
             if res.is_err() {
-                comp.extend_comps(comp_items);
+                // value that failed to parse while looking for a replacement
+                if let (Some(suggest), true) = (&self.suggest, comp.probe) {
+                    self.push_values(comp, comp_items, depth, || suggest());
+                } else {
+                    comp.extend_comps(comp_items);
+                }
                 return res;
             }
         }
@@ -987,36 +1230,14 @@ where
 
         // completion function generates suggestions based on the parsed inner value, for
         // that `res` must contain a parsed value
-        let depth = args.depth();
         if let Some(comp) = &mut args.comp_mut() {
-            for ci in comp_items {
-                let is_meta = ci.is_metavar();
-                if let Some(is_arg) = is_meta {
-                    let suggestions = (self.op)(&res);
-                    // strip metavar when completion makes a single good suggestion
-                    if suggestions.len() != 1 {
-                        comp.push_comp(ci);
-                    }
-                    for (replacement, description) in suggestions {
-                        let group = self.group.clone();
-                        comp.push_value(
-                            replacement.into(),
-                            description.map(Into::into),
-                            group,
-                            depth,
-                            is_arg,
-                        );
-                    }
-                } else {
-                    comp.push_comp(ci);
-                }
-            }
+            self.push_values(comp, comp_items, depth, || (self.op)(&res));
         }
         Ok(res)
     }
 
     fn meta(&self) -> Meta {
-        self.inner.meta()
+        Meta::Completion(Box::new(self.inner.meta()), Box::new(CompHint::Dynamic))
     }
 }
 
@@ -94,3 +94,41 @@ fn de_yoda() {
     let r = parser.run_inner(&["-a", "-b"]).unwrap();
     assert_eq!(r, (true, true));
 }
+
+#[test]
+fn color_choice_is_kept_in_failure() {
+    use crate::{long, short, ColorChoice, Parser};
+    let parser = short('v').switch().to_options().color_arg(long("color"));
+
+    let color = |args: &[&str]| match parser.run_inner(args).unwrap_err() {
+        crate::ParseFailure::Stdout(doc, _) | crate::ParseFailure::Stderr(doc) => doc.color,
+        crate::ParseFailure::Completion(_) => unreachable!(),
+    };
+    assert_eq!(
+        color(&["--color=never", "--help"]),
+        Some(ColorChoice::Never)
+    );
+    assert_eq!(color(&["--color", "-x"]), Some(ColorChoice::Always));
+    assert_eq!(color(&["-x"]), None);
+}
+
+#[test]
+fn failure_keeps_theme_and_locale() {
+    use crate::{buffer::ColorChoice, long, Catalog, Parser, Phrase, Theme};
+
+    let catalog = Catalog::new("de").translate(Phrase::ErrorPrefix, "Fehler: ");
+    let parser = long("verbose").switch().to_options().locale(catalog);
+    let err = parser.run_inner(&["--verbos"]).unwrap_err();
+    let text = err.render_message(100, Some(ColorChoice::Never), false);
+    assert!(text.starts_with("Fehler: "), "{}", text);
+
+    let theme = Theme::bright();
+    let parser = long("verbose").switch().to_options().theme(theme.clone());
+    let err = parser.run_inner(&["--verbos"]).unwrap_err();
+    match &err {
+        crate::ParseFailure::Stderr(doc) => {
+            assert_eq!(doc.style.as_ref().map(|s| &s.0), Some(&theme))
+        }
+        _ => unreachable!(),
+    }
+}
@@ -1425,3 +1425,452 @@ fn positionals_with_no_completions_are_not_duplicated() {
 \tBETA\t\tBeta argument\n\n"
     );
 }
+
+#[test]
+fn nu_and_pwsh_output() {
+    let a = short('a')
+        .long("arg")
+        .help("Alpha \"argument\"")
+        .argument::<String>("ALPHA");
+    let parser = a.to_options();
+
+    let r = parser
+        .run_inner(Args::from(&[""]).set_comp(10))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "[{\"value\": \"--arg\", \"description\": \"Alpha \\\"argument\\\"\"}]\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&[""]).set_comp(11))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "[System.Management.Automation.CompletionResult]::new('--arg', '--arg=ALPHA', 'ParameterName', 'Alpha \"argument\"')\n"
+    );
+
+    let parser = positional::<String>("FILE")
+        .complete_shell(ShellComp::Dir { mask: None })
+        .to_options();
+
+    // nushell falls back to file completion when there are no other candidates
+    let r = parser
+        .run_inner(Args::from(&[""]).set_comp(10))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "null\n");
+
+    let r = parser
+        .run_inner(Args::from(&[""]).set_comp(11))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "[System.Management.Automation.CompletionCompleters]::CompleteFilename($wordToComplete) | Where-Object { $_.ResultType -eq 'ProviderContainer' }\n"
+    );
+}
+
+#[test]
+fn static_completion_fish() {
+    let output = short('o')
+        .long("output")
+        .help("Output file")
+        .argument::<String>("FILE")
+        .complete_shell(ShellComp::File {
+            mask: Some("*.toml"),
+        });
+    let build = output
+        .to_options()
+        .command("build")
+        .short('b')
+        .help("Build the project");
+    let verbose = short('v').help("Be verbose").switch();
+    let parser = construct!(verbose, build).to_options();
+
+    let r = parser.render_static_completion("app", CompletionShell::Fish);
+    let expected = r#"function __bpaf_static_app_ctx
+    set -l ctx ''
+    for word in (commandline -opc)[2..-1]
+        switch "$ctx/$word"
+            case '/build' '/b'
+                set ctx '/build'
+        end
+    end
+    test "$ctx" = "$argv[1]"
+end
+
+complete -c app -f
+complete -c app -n '__bpaf_static_app_ctx ""' -s v -d 'Be verbose'
+complete -c app -n '__bpaf_static_app_ctx ""' -s h -l help -d 'Prints help information'
+complete -c app -n '__bpaf_static_app_ctx ""' -a build -d 'Build the project'
+complete -c app -n '__bpaf_static_app_ctx "/build"' -s o -l output -r -a '(__fish_complete_suffix .toml)' -d 'Output file'
+complete -c app -n '__bpaf_static_app_ctx "/build"' -s h -l help -d 'Prints help information'
+"#;
+    assert_eq!(r, expected);
+
+    let r = parser.render_static_completion("app", CompletionShell::Bash);
+    assert!(r.contains("        '/build --output' | '/build -o')\n            COMPREPLY+=( $(compgen -d -- \"$cur\") $(compgen -f -X '!*.toml' -- \"$cur\") )\n            return ;;\n"));
+    assert!(r.contains("            words='-v --help -h build'\n"));
+}
+
+/// Directory for test files, removed when the test finishes, even if it fails
+#[cfg(unix)]
+struct TempDir(std::path::PathBuf);
+
+#[cfg(unix)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn delegated_completion() {
+    use std::{ffi::OsString, os::unix::fs::PermissionsExt};
+    let dir = std::env::temp_dir().join(format!("bpaf_delegate_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let dir = TempDir(dir);
+    let dir = &dir.0;
+    let checker = dir.join("checker");
+    let script = "#!/bin/sh
+shift
+case \"$*\" in
+    *--rel) printf '%s\\t%s\\t\\t%s\\n' --release --release \"$*\" --rel-path --rel-path=PATH ''; echo;;
+    *) printf -- --quiet;;
+esac
+";
+    std::fs::write(&checker, script).unwrap();
+    std::fs::set_permissions(&checker, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let verbose = short('v').help("Be verbose").switch();
+    let args = any::<OsString, _, _>("ARG", Some)
+        .many()
+        .complete_delegate(&checker);
+    let parser = construct!(verbose, args).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["-v", "check", "--rel"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "--release\t--release\t\tcheck --rel\n--rel-path\t--rel-path\t\t\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["check", "--q"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--quiet");
+
+    // candidates from the delegated program are merged with the rest
+    let r = parser
+        .run_inner(Args::from(&["-"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "-v\t-v\t\tBe verbose\n--quiet\t--quiet\t\t\n\n");
+}
+
+#[test]
+fn choice_values() {
+    let parser = long("mode")
+        .help("Processing mode")
+        .choice(["fast", "safe", "safer"])
+        .to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--mode", "f"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "fast");
+
+    let r = parser
+        .run_inner(Args::from(&["--mode", "sa"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "\tMODE\t\tProcessing mode\nsafe\tsafe\t\t\nsafer\tsafer\t\t\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--mode=sa"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "\tMODE\t\tProcessing mode\n--mode=safe\tsafe\t\t\n--mode=safer\tsafer\t\t\n\n"
+    );
+}
+
+#[test]
+fn positional_choice_values() {
+    let verbose = short('v').switch();
+    let format = positional::<String>("FORMAT").choice(["json", "yaml"]);
+    let parser = construct!(verbose, format).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["j"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "json");
+
+    let r = parser
+        .run_inner(Args::from(&[""]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "-v\t-v\t\t\n\tFORMAT\t\t\njson\tjson\t\t\nyaml\tyaml\t\t\n\n"
+    );
+}
+
+#[test]
+fn suggest_values_from_completion() {
+    fn formats(input: &String) -> Vec<(&'static str, Option<&'static str>)> {
+        ["json", "yaml", "toml"]
+            .iter()
+            .filter(|f| f.starts_with(input.as_str()))
+            .map(|f| (*f, None))
+            .collect()
+    }
+    fn check(input: String) -> Result<String, String> {
+        if formats(&input).iter().any(|(f, _)| *f == input) {
+            Ok(input)
+        } else {
+            Err(format!("{} is not supported", input))
+        }
+    }
+    let options = || {
+        let verbose = short('v').switch();
+        let format = long("format")
+            .argument::<String>("FORMAT")
+            .complete(formats)
+            .parse(check);
+        construct!(verbose, format).to_options()
+    };
+    let parser = options();
+
+    let r = parser
+        .run_inner(&["--format", "jsno", "-v"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`jsno` is not a valid value, did you mean `json`?");
+
+    let r = parser
+        .run_inner(&["--format=yml"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`yml` is not a valid value, did you mean `yaml`?");
+
+    let err = parser.run_inner(&["-v", "--format", "tmol"]).unwrap_err();
+    match err.error_kind() {
+        Some(ErrorKind::InvalidChoice {
+            index, suggestion, ..
+        }) => {
+            assert_eq!(*index, Some(2));
+            assert_eq!(suggestion.as_deref(), Some("toml"));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    // nothing close enough, original error stays
+    let r = parser
+        .run_inner(&["--format", "xml-ish"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `xml-ish`: xml-ish is not supported");
+
+    let parser = options().command("export").to_options();
+    let r = parser
+        .run_inner(&["export", "--format", "jsno"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`jsno` is not a valid value, did you mean `json`?");
+}
+
+#[test]
+fn suggest_typed_values_from_completion() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Format {
+        Json,
+        Yaml,
+    }
+    impl std::str::FromStr for Format {
+        type Err = String;
+        fn from_str(s: &str) -> Result<Self, String> {
+            match s {
+                "json" => Ok(Format::Json),
+                "yaml" => Ok(Format::Yaml),
+                _ => Err(format!("{} is not a known format", s)),
+            }
+        }
+    }
+    fn formats(_: &Format) -> Vec<(&'static str, Option<&'static str>)> {
+        vec![("json", None), ("yaml", None)]
+    }
+    fn ports(_: &u16) -> Vec<(&'static str, Option<&'static str>)> {
+        vec![("8080", None), ("8443", None)]
+    }
+
+    let format = long("format")
+        .argument::<Format>("FORMAT")
+        .complete(formats)
+        .suggest_with(Format::Json);
+    let port = long("port")
+        .argument::<u16>("PORT")
+        .complete(ports)
+        .suggest_with(0);
+    let parser = construct!(format, port).to_options();
+
+    let r = parser
+        .run_inner(&["--format", "jsno", "--port", "80"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`jsno` is not a valid value, did you mean `json`?");
+
+    let r = parser
+        .run_inner(&["--format", "json", "--port", "808O"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`808O` is not a valid value, did you mean `8080`?");
+
+    let r = parser.run_inner(&["--format=yaml", "--port=8443"]).unwrap();
+    assert_eq!(r, (Format::Yaml, 8443));
+
+    // without a value to complete from typed values get no suggestions
+    let parser = long("format")
+        .argument::<Format>("FORMAT")
+        .complete(formats)
+        .to_options();
+    let r = parser
+        .run_inner(&["--format", "jsno"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `jsno`: jsno is not a known format");
+}
+
+#[test]
+fn negatable_switch_both_forms() {
+    let color = long("color").help("Use colors").negatable_switch();
+    let check = long("check").switch();
+    let parser = construct!(color, check).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--c"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "--color\t--color\t\tUse colors\n--check\t--check\t\t\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--no"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--no-color");
+}
+
+#[test]
+fn optional_value_completion() {
+    let color = long("color")
+        .help("When to use colors")
+        .optional_value::<String>("WHEN", "always".to_owned())
+        .optional();
+    let check = long("check").switch();
+    let parser = construct!(color, check).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--c"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "--color\t--color\t\tWhen to use colors\n--check\t--check\t\t\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--color="]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tWHEN\t\tWhen to use colors\n\n");
+}
+
+#[test]
+fn key_value_halves() {
+    let parser = short('D')
+        .help("Define a variable")
+        .argument("KEY")
+        .key_value::<String, String>("KEY=VALUE")
+        .many()
+        .to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["-D", "wi"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tKEY\t\tDefine a variable\n\n");
+
+    let r = parser
+        .run_inner(Args::from(&["-D", "width="]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tVALUE\t\tDefine a variable\n\n");
+
+    let parser = long("limit")
+        .choice(["width", "height"])
+        .key_value::<String, u32>("DIM=SIZE")
+        .to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--limit", "wi"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "width");
+
+    let r = parser
+        .run_inner(Args::from(&["--limit", "width="]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tSIZE\t\t\n\n");
+}
+
+#[test]
+fn key_value_complete_value() {
+    fn sizes(key: &str, value: &str) -> Vec<(String, Option<String>)> {
+        let sizes: &[&str] = match key {
+            "width" => &["80", "120"],
+            _ => &["25", "50"],
+        };
+        sizes
+            .iter()
+            .filter(|s| s.starts_with(value))
+            .map(|s| (s.to_string(), Some(format!("{} {}", key, s))))
+            .collect()
+    }
+    let parser = long("limit")
+        .choice(["width", "height"])
+        .key_value::<String, u32>("DIM=SIZE")
+        .complete_value(sizes)
+        .to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--limit", "width="]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "\tSIZE\t\t\nwidth=80\twidth=80\t\twidth 80\nwidth=120\twidth=120\t\twidth 120\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--limit", "height=2"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "height=25");
+}
@@ -123,3 +123,44 @@ Available commands:
     let r = parser.run_inner(&["one"]).unwrap();
     assert_eq!(r, One);
 }
+
+#[test]
+fn choice_enum_field() {
+    #[derive(bpaf::Choice, Debug, Clone, Copy, Eq, PartialEq)]
+    enum Level {
+        Low,
+        VeryHigh,
+    }
+
+    #[derive(Bpaf, Debug, Clone, Eq, PartialEq)]
+    #[bpaf(options)]
+    struct Opts {
+        /// Noise level
+        #[bpaf(choice)]
+        level: Option<Level>,
+    }
+
+    let parser = opts();
+    let help = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [--level=LEVEL]
+
+Available options:
+        --level=LEVEL  Noise level
+                       [possible values: low, very-high]
+    -h, --help         Prints help information
+";
+    assert_eq!(help, expected);
+
+    let r = parser.run_inner(&["--level", "very-high"]).unwrap();
+    assert_eq!(r.level, Some(Level::VeryHigh));
+
+    let r = parser
+        .run_inner(&["--level", "very-hihg"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "`very-hihg` is not a valid value, did you mean `very-high`?"
+    );
+}
@@ -297,3 +297,532 @@ fn used_only_once_is_more_important_error() {
         "argument `--sort` cannot be used multiple times in this context"
     );
 }
+
+#[test]
+fn structured_error_kinds() {
+    let a = short('a').req_flag(());
+    let b = short('b').req_flag(());
+    let parser = construct!([a, b]).to_options();
+    let err = parser.run_inner(&["-a", "-b"]).unwrap_err();
+    match err.error_kind() {
+        Some(ErrorKind::Conflict {
+            index: 1,
+            winner: 0,
+            item: Some(item),
+        }) => assert_eq!(item.to_string(), "-b"),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let parser = long("name").argument::<String>("NAME").to_options();
+    let err = parser.run_inner(&["--name"]).unwrap_err();
+    match err.error_kind() {
+        Some(ErrorKind::NoArgument {
+            index: 0,
+            metavar,
+            item: Some(item),
+        }) if metavar == "NAME" => assert_eq!(item.to_string(), "--name=NAME"),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let err = parser.run_inner(&[]).unwrap_err();
+    match err.error_kind() {
+        Some(ErrorKind::Expected { items, index: None }) => {
+            let items = items.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+            assert_eq!(items, ["--name=NAME"]);
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    // indices refer to the original arguments, not split items
+    let parser = short('n').argument::<usize>("N").to_options();
+    let err = parser.run_inner(&["-n1", "-n=x"]).unwrap_err();
+    match err.error_kind() {
+        Some(ErrorKind::OnlyOnce {
+            index,
+            first,
+            item: Some(item),
+        }) => {
+            assert_eq!((*index, *first), (1, 0));
+            assert_eq!(item.to_string(), "-n=N");
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let err = parser.run_inner(&["-n", "x"]).unwrap_err();
+    match err.error_kind() {
+        Some(ErrorKind::ParseFailed { index, .. }) => assert_eq!(*index, Some(1)),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let parser = short('v').switch().to_options();
+    let err = parser.run_inner(&["--help"]).unwrap_err();
+    assert!(err.error_kind().is_none());
+}
+
+#[test]
+fn structured_error_suggestion() {
+    let verbose = long("verbose").switch();
+    let cmd = verbose.to_options().command("build");
+    let parser = cmd.to_options();
+
+    let err = parser.run_inner(&["--verbose"]).unwrap_err();
+    match err.error_kind() {
+        Some(ErrorKind::Suggestion {
+            index,
+            suggestion,
+            command,
+        }) => {
+            assert_eq!(*index, 0);
+            assert_eq!(suggestion, "--verbose");
+            assert_eq!(command.as_deref(), Some("build"));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn translated_errors() {
+    let german = Catalog::new("de")
+        .translate(Phrase::NotExpected, "{arg} wird hier nicht erwartet")
+        .translate(
+            Phrase::ParseFailed,
+            "{arg} konnte nicht verarbeitet werden: {message}",
+        );
+    let parser = short('n')
+        .argument::<usize>("N")
+        .to_options()
+        .locale(german);
+
+    let r = parser
+        .run_inner(&["-n", "1", "-x"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`-x` wird hier nicht erwartet");
+
+    let r = parser.run_inner(&["-n", "x"]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        "`x` konnte nicht verarbeitet werden: invalid digit found in string"
+    );
+
+    // phrases without translation stay in English
+    let r = parser.run_inner(&["-n"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`-n` requires an argument `N`");
+}
+
+/// Directory for test files, removed when the test finishes, even if it fails
+struct TempDir(std::path::PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn response_file_errors() {
+    let dir = std::env::temp_dir().join(format!("bpaf_response_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let dir = TempDir(dir);
+    let dir = &dir.0;
+    let outer = dir.join("outer.txt");
+    let inner = dir.join("inner.txt");
+    let broken = dir.join("broken.txt");
+    let looped = dir.join("loop.txt");
+    std::fs::write(&outer, format!("-n 1\n@{}\n", inner.display())).unwrap();
+    std::fs::write(&inner, "\n-x\n").unwrap();
+    std::fs::write(&broken, "-n\n'1\n").unwrap();
+    std::fs::write(&looped, format!("@{}", looped.display())).unwrap();
+    let at = |p: &std::path::Path| format!("@{}", p.display());
+
+    let parser = short('n')
+        .argument::<usize>("N")
+        .to_options()
+        .response_files(true);
+
+    // words from response files point to where they came from
+    let args = [at(&outer)];
+    let r = parser.run_inner(&args[..]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        format!(
+            "`-x` is not expected in this context (from `{}`:2)",
+            inner.display()
+        )
+    );
+
+    let args = [at(&broken)];
+    let err = parser.run_inner(&args[..]).unwrap_err();
+    match err.error_kind() {
+        Some(ErrorKind::ResponseFile { line, message, .. }) => {
+            assert_eq!(*line, Some(2));
+            assert_eq!(message, "unterminated quote");
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+    let r = err.unwrap_stderr();
+    assert_eq!(r, format!("`{}`:2: unterminated quote", broken.display()));
+
+    let args = [at(&looped)];
+    let r = parser.run_inner(&args[..]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        format!(
+            "`{}`:1: response files are nested more than 16 levels deep",
+            looped.display()
+        )
+    );
+
+    // expansion is opt-in and stops at --
+    let parser = short('n').argument::<String>("N").to_options();
+    let args = ["-n".to_owned(), at(&outer)];
+    assert_eq!(parser.run_inner(&args[..]).unwrap(), at(&outer));
+    let parser = positional::<String>("REST")
+        .to_options()
+        .response_files(true);
+    let args = ["--".to_owned(), at(&outer)];
+    assert_eq!(parser.run_inner(&args[..]).unwrap(), at(&outer));
+}
+
+#[test]
+fn invalid_choice() {
+    let parser = long("mode")
+        .env("BPAF_TEST_CHOICE_MODE")
+        .choice(["fast", "safe", "off"])
+        .to_options();
+
+    let r = parser
+        .run_inner(&["--mode=fsat"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`fsat` is not a valid value, did you mean `fast`?");
+
+    let r = parser
+        .run_inner(&["--mode", "SAFE"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`SAFE` is not a valid value, did you mean `safe`?");
+
+    let r = parser
+        .run_inner(&["--mode", "xyz"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "`xyz` is not a valid value, expected one of `fast`, `safe`, `off`"
+    );
+
+    std::env::set_var("BPAF_TEST_CHOICE_MODE", "ofg");
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`ofg` is not a valid value, did you mean `off`?");
+    std::env::remove_var("BPAF_TEST_CHOICE_MODE");
+}
+
+#[test]
+fn requires_and_conflicts() {
+    let key = || long("key").argument::<String>("KEY");
+    let dry = || long("dry-run").switch();
+    let sign = long("sign").switch().requires(key()).conflicts_with(dry());
+    let key = key().optional();
+    let parser = construct!(sign, key, dry()).to_options();
+
+    let r = parser.run_inner(&["--sign"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`--sign` requires `--key=KEY`");
+
+    let r = parser
+        .run_inner(&["--dry-run", "--key", "k", "--sign"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`--sign` cannot be used at the same time as `--dry-run`");
+
+    let r = parser.run_inner(&["--sign", "--key", "k"]).unwrap();
+    assert_eq!(r, (true, Some("k".to_owned()), false));
+
+    let r = parser.run_inner(&["--dry-run"]).unwrap();
+    assert_eq!(r, (false, None, true));
+
+    let r = parser
+        .run_inner(&["--sign", "--dry-run", "--key", "k"])
+        .unwrap_err();
+    assert!(matches!(
+        r.error_kind(),
+        Some(ErrorKind::Conflict {
+            index: 1,
+            winner: 0,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn required_if_condition() {
+    let sign = || long("sign").switch();
+    let key = long("key")
+        .env("BPAF_TEST_REQUIRED_IF_KEY")
+        .argument::<String>("KEY")
+        .optional()
+        .required_if(sign());
+    let parser = construct!(sign(), key).to_options();
+
+    let r = parser.run_inner(&["--sign"]).unwrap_err();
+    assert!(matches!(
+        r.error_kind(),
+        Some(ErrorKind::Requires { index: Some(0), requires }) if requires == "--key=KEY"
+    ));
+    assert_eq!(r.unwrap_stderr(), "`--sign` requires `--key=KEY`");
+
+    assert_eq!(parser.run_inner(&[]).unwrap(), (false, None));
+
+    // value from the environment doesn't count as present on the command line
+    std::env::set_var("BPAF_TEST_REQUIRED_IF_KEY", "k");
+    let r = parser.run_inner(&["--sign"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`--sign` requires `--key=KEY`");
+    std::env::remove_var("BPAF_TEST_REQUIRED_IF_KEY");
+
+    // condition that doesn't come from the command line
+    let key = long("key")
+        .argument::<String>("KEY")
+        .optional()
+        .required_if(pure(true));
+    let r = key.to_options().run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `--key=KEY`, pass `--help` for usage information"
+    );
+}
+
+#[test]
+fn one_of_groups() {
+    let file = long("file").argument::<String>("FILE").optional();
+    let stdin = short('s').long("stdin").switch();
+    let parser = construct!(file, stdin).exactly_one_of().to_options();
+
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `--file=FILE` or `--stdin`, pass `--help` for usage information"
+    );
+
+    let r = parser
+        .run_inner(&["-s", "--file", "a"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`--file` cannot be used at the same time as `-s`");
+
+    assert_eq!(parser.run_inner(&["-s"]).unwrap(), (None, true));
+
+    let a = long("a").switch();
+    let b = long("b").switch();
+    let parser = construct!(a, b).at_least_one_of().to_options();
+    assert_eq!(parser.run_inner(&["--a", "--b"]).unwrap(), (true, true));
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `--a` or `--b`, pass `--help` for usage information"
+    );
+}
+
+#[test]
+fn one_of_groups_env_and_config() {
+    let name = "BPAF_TEST_ONE_OF_STDIN";
+    std::env::set_var(name, "1");
+    let file = long("file").argument::<String>("FILE").optional();
+    let stdin = long("stdin").env(name).switch();
+    let parser = construct!(file, stdin).exactly_one_of().to_options();
+    assert_eq!(parser.run_inner(&[]).unwrap(), (None, true));
+
+    let r = parser
+        .run_inner(&["--file", "a"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "environment variable `BPAF_TEST_ONE_OF_STDIN` cannot be used at the same time as `--file`"
+    );
+
+    let a = long("a").config("a").switch();
+    let b = long("b").config("b").switch();
+    let config = |key: &str| Some(key.to_owned());
+    let parser = construct!(a, b)
+        .at_least_one_of()
+        .to_options()
+        .config_source(|key: &str| (key == "a").then(|| "true".to_owned()));
+    assert_eq!(parser.run_inner(&[]).unwrap(), (true, false));
+
+    let a = long("a").config("a").switch();
+    let b = long("b").config("b").switch();
+    let parser = construct!(a, b)
+        .exactly_one_of()
+        .to_options()
+        .config_source(config);
+    let r = parser.run_inner(&[]).unwrap_err();
+    match r.error_kind() {
+        Some(ErrorKind::SourceConflict { source, winner }) => {
+            assert_eq!(source, &ValueSource::Config("b".into()));
+            assert_eq!(winner, &ValueSource::Config("a".into()));
+        }
+        kind => panic!("unexpected error {:?}", kind),
+    }
+    assert_eq!(
+        r.unwrap_stderr(),
+        "config key `b` cannot be used at the same time as config key `a`"
+    );
+}
+
+#[test]
+fn relations_in_help() {
+    let key = || long("key").help("Signing key").argument::<String>("KEY");
+    let sign = long("sign")
+        .help("Sign the release")
+        .switch()
+        .requires(key());
+    let quiet = || short('q').long("quiet").help("Print nothing").switch();
+    let verbose = short('v')
+        .help("Print more")
+        .switch()
+        .conflicts_with(quiet());
+    let key = key().optional();
+    let parser = construct!(sign, key, verbose, quiet()).to_options();
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [--sign] [--key=KEY] [-v] [-q]
+
+Available options:
+        --sign     Sign the release
+                   [requires: --key=KEY]
+        --key=KEY  Signing key
+    -v             Print more
+                   [conflicts with: -q]
+    -q, --quiet    Print nothing
+    -h, --help     Prints help information
+";
+    assert_eq!(r, expected);
+
+    let a = long("a").switch();
+    let b = long("b").switch();
+    let r = construct!(a, b)
+        .exactly_one_of()
+        .to_options()
+        .run_inner(&["--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    let expected = "\
+Usage: [--a] [--b]
+
+Available options:
+        --a
+        --b
+                [exactly one of: --a, --b]
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn relations_inside_subcommand() {
+    let key = || long("key").argument::<String>("KEY");
+    let sign = long("sign").switch().requires(key());
+    let key = key().optional();
+    let publish = construct!(sign, key).to_options().command("publish");
+    let verbose = short('v').switch();
+    let quiet = || short('q').switch();
+    let verbose = verbose.conflicts_with(quiet());
+    let quiet = quiet();
+    let parser = construct!(verbose, quiet, publish).to_options();
+
+    let r = parser
+        .run_inner(&["publish", "--sign"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`--sign` requires `--key=KEY`");
+
+    let r = parser
+        .run_inner(&["-q", "-v", "publish", "--sign", "--key", "k"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`-v` cannot be used at the same time as `-q`");
+
+    let r = parser.run_inner(&["-v", "publish", "--key", "k"]).unwrap();
+    assert_eq!(r, (true, false, (false, Some("k".to_owned()))));
+}
+
+#[test]
+fn key_value_halves() {
+    let parser = short('D')
+        .argument("KEY")
+        .key_value::<char, u8>("KEY=VALUE")
+        .many()
+        .to_options();
+
+    let failure = parser.run_inner(&["-D", "ab=1"]).unwrap_err();
+    assert!(matches!(
+        failure.error_kind(),
+        Some(ErrorKind::KeyValueFailed {
+            index: Some(1),
+            part: KeyValuePart::Key,
+            ..
+        })
+    ));
+    let r = failure.unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse key `ab` in `ab=1`: too many characters in string"
+    );
+
+    let failure = parser.run_inner(&["-Da=300"]).unwrap_err();
+    assert!(matches!(
+        failure.error_kind(),
+        Some(ErrorKind::KeyValueFailed {
+            part: KeyValuePart::Value,
+            ..
+        })
+    ));
+    let r = failure.unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse value `300` in `a=300`: number too large to fit in target type"
+    );
+
+    let failure = parser.run_inner(&["-D", "a"]).unwrap_err();
+    assert!(matches!(
+        failure.error_kind(),
+        Some(ErrorKind::KeyValueFailed {
+            part: KeyValuePart::Separator,
+            ..
+        })
+    ));
+    assert_eq!(failure.unwrap_stderr(), "expected `KEY=VALUE`, got `a`");
+}
+
+#[test]
+fn key_value_choice_keys() {
+    let parser = long("limit")
+        .choice(["width", "height"])
+        .key_value::<String, u32>("DIM=SIZE")
+        .many()
+        .to_options();
+
+    let r = parser.run_inner(&["--limit", "width=80"]).unwrap();
+    assert_eq!(r, [("width".to_owned(), 80)]);
+
+    let failure = parser.run_inner(&["--limit", "widht=80"]).unwrap_err();
+    assert!(matches!(
+        failure.error_kind(),
+        Some(ErrorKind::InvalidChoice { index: Some(1), .. })
+    ));
+    assert_eq!(
+        failure.unwrap_stderr(),
+        "`widht` is not a valid value, did you mean `width`?"
+    );
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [--limit=DIM=SIZE]...
+
+Available options:
+        --limit=DIM=SIZE  [possible keys: width, height]
+    -h, --help            Prints help information
+";
+    assert_eq!(r, expected);
+}
@@ -0,0 +1,137 @@
+#![cfg(unix)]
+use bpaf::*;
+use std::{ffi::OsString, os::unix::fs::PermissionsExt, path::PathBuf};
+
+/// Directory with fake plugins, removed at the end of the test
+struct PluginDir(PathBuf);
+
+impl Drop for PluginDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn plugin_dir(name: &str) -> PluginDir {
+    let dir = std::env::temp_dir().join(format!("bpaf_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = "#!/bin/sh
+case \"$1\" in
+    --help) echo \"Frobnicate the widgets\"; echo; echo \"Usage: myapp-frob\";;
+    --bpaf-complete-rev=*) echo \"$@\";;
+esac
+";
+    for (file, mode) in [
+        ("myapp-frob", 0o755),
+        ("myapp-lint", 0o755),
+        ("myapp-notes.txt", 0o644),
+        ("other-frob", 0o755),
+    ] {
+        let path = dir.join(file);
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+    PluginDir(dir)
+}
+
+#[test]
+fn external_commands_forward_args() {
+    let dir = plugin_dir("external_forward");
+    let verbose = short('v').switch();
+    let plugin = external_commands("myapp-").search_path(&dir.0);
+    let parser = construct!(verbose, plugin).to_options();
+
+    let (verbose, r) = parser
+        .run_inner(&["-v", "frob", "-xy", "--level=3", "file", "--", "-v"])
+        .unwrap();
+    assert!(verbose);
+    assert_eq!(r.name, "frob");
+    assert_eq!(r.path, dir.0.join("myapp-frob"));
+    let expected = ["-xy", "--level=3", "file", "--", "-v"];
+    assert_eq!(
+        r.args,
+        expected.iter().map(OsString::from).collect::<Vec<_>>()
+    );
+
+    let r = parser
+        .run_inner(&["notes.txt"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `COMMAND ...`, got `notes.txt`. Pass `--help` for usage information"
+    );
+}
+
+#[test]
+fn external_commands_help() {
+    let dir = plugin_dir("external_help");
+    let build = pure(()).to_options().descr("Build things").command("build");
+    let plugin = external_commands("myapp-").search_path(&dir.0).map(|_| ());
+    let parser = construct!([build, plugin]).to_options();
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: COMMAND ...
+
+Available options:
+    -h, --help  Prints help information
+
+Available commands:
+    build       Build things
+    frob        Frobnicate the widgets
+    lint        Frobnicate the widgets
+";
+    assert_eq!(r, expected);
+
+    #[cfg(feature = "docgen")]
+    {
+        let r = parser.render_markdown("app");
+        assert!(r.contains("Frobnicate the widgets"), "{}", r);
+        let r = parser.render_manpage("app", doc::Section::General, None, None, None);
+        assert!(r.contains("Frobnicate the widgets"), "{}", r);
+    }
+}
+
+#[test]
+fn external_commands_slow_help() {
+    let dir = plugin_dir("external_slow");
+    let path = dir.0.join("myapp-slow");
+    std::fs::write(&path, "#!/bin/sh\nexec sleep 10\n").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let parser = external_commands("myapp-").search_path(&dir.0).to_options();
+
+    let start = std::time::Instant::now();
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    let expected = "\
+Usage: COMMAND ...
+
+Available options:
+    -h, --help  Prints help information
+
+Available commands:
+    frob        Frobnicate the widgets
+    lint        Frobnicate the widgets
+    slow
+";
+    assert_eq!(r, expected);
+}
+
+#[cfg(feature = "autocomplete")]
+#[test]
+fn external_commands_completion() {
+    let dir = plugin_dir("external_complete");
+    let parser = external_commands("myapp-").search_path(&dir.0).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["f"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "frob");
+
+    let r = parser
+        .run_inner(Args::from(&["frob", "--le"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--bpaf-complete-rev=0 --le\n");
+}
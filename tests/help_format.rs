@@ -606,3 +606,92 @@ fn help_and_version_newline() {
         .unwrap_stdout();
     assert_eq!(r, "Version: 1\n");
 }
+
+#[test]
+fn translated_help_inherited_by_command() {
+    let japanese = Catalog::new("ja")
+        .translate(Phrase::Usage, "使い方")
+        .translate(Phrase::Options, "オプション:")
+        .translate(Phrase::Commands, "コマンド:")
+        .translate(Phrase::HelpDescription, "ヘルプを表示");
+
+    let verbose = short('v')
+        .help(Doc::from("Verbose output").translation("ja", "詳細な出力"))
+        .switch();
+    let build = verbose
+        .to_options()
+        .descr(Doc::from("Build the project").translation("ja", "プロジェクトをビルド"))
+        .command("build")
+        .help(Doc::from("Build the project").translation("ja", "プロジェクトをビルド"));
+    let parser = build.to_options().locale(japanese);
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+使い方: COMMAND ...
+
+オプション:
+    -h, --help  ヘルプを表示
+
+コマンド:
+    build       プロジェクトをビルド
+";
+    assert_eq!(r, expected);
+
+    let r = parser
+        .run_inner(&["build", "--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    let expected = "\
+プロジェクトをビルド
+
+使い方: build [-v]
+
+オプション:
+    -v          詳細な出力
+    -h, --help  ヘルプを表示
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn fixed_width_keeps_term_column() {
+    let a = long("output-format")
+        .help("Format of the report printed at the end of the run")
+        .argument::<String>("FMT");
+    let b = short('q').help("Be quiet").switch();
+    let parser = construct!(a, b).to_options();
+
+    let doc = match parser.run_inner(&["--help"]).unwrap_err() {
+        ParseFailure::Stdout(doc, _) => doc,
+        _ => unreachable!(),
+    };
+
+    let r = format!("{:100}", doc);
+    let expected = "\
+Usage: --output-format=FMT [-q]
+
+Available options:
+        --output-format=FMT  Format of the report printed at the end of the run
+    -q                       Be quiet
+    -h, --help               Prints help information
+";
+    assert_eq!(r, expected);
+
+    // width picked by the user only wraps the text, term column is narrowed only when the
+    // width comes from a narrow terminal, see `auto_width`
+    let r = format!("{:40}", doc);
+    let expected = "\
+Usage: --output-format=FMT [-q]
+
+Available options:
+        --output-format=FMT  Format of
+                             the report
+                             printed at
+                             the end of
+                             the run
+    -q                       Be quiet
+    -h, --help               Prints help
+                             information
+";
+    assert_eq!(r, expected);
+}
@@ -128,3 +128,66 @@ fn very_nested() {
     #[cfg(unix)]
     assert!(write_updated(&roff, "tests/very_nested.1").unwrap());
 }
+
+#[test]
+fn choice_values() {
+    let mode = long("mode")
+        .help("Processing mode")
+        .choice(["fast", "safe"]);
+    let roff = mode
+        .to_options()
+        .render_manpage("choice", Section::General, None, None, None);
+
+    #[cfg(unix)]
+    assert!(write_updated(&roff, "tests/choice.1").unwrap());
+}
+
+#[test]
+fn optional_value() {
+    let color = long("color")
+        .help("When to use colors")
+        .optional_value::<String>("WHEN", "always".to_owned());
+    let roff = color
+        .to_options()
+        .render_manpage("color", Section::General, None, None, None);
+
+    #[cfg(unix)]
+    assert!(write_updated(&roff, "tests/color.1").unwrap());
+}
+
+#[test]
+fn pages_for_subcommands() {
+    let message = short('m')
+        .env("COMMIT_MSG")
+        .help("Commit message")
+        .argument::<String>("MSG");
+    let push = long("force")
+        .help("Overwrite remote history")
+        .switch()
+        .to_options()
+        .descr("Update remote refs")
+        .command("push");
+    let commit = construct!(message, push)
+        .to_options()
+        .descr("Record changes to the repository")
+        .command("commit");
+    let verbose = short('v').help("Be verbose").switch();
+    let pages = construct!(verbose, commit)
+        .to_options()
+        .descr("The stupid content tracker")
+        .render_manpages(
+            "git",
+            Section::General,
+            Some("2024-05-01"),
+            Some("Vendor"),
+            Some("Git Manual"),
+        );
+
+    let names = pages.iter().map(|p| p.0.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["git.1", "git-commit.1", "git-commit-push.1"]);
+
+    #[cfg(unix)]
+    for (name, roff) in &pages {
+        assert!(write_updated(roff, format!("tests/{}", name)).unwrap());
+    }
+}
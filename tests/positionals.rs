@@ -144,3 +144,169 @@ fn strictly_positional() {
     let r = parser.run_inner(&["--"]).unwrap_err().unwrap_stderr();
     assert_eq!(r, "expected `A`, pass `--help` for usage information");
 }
+
+#[test]
+fn stop_parsing_at_first_positional() {
+    let verbose = short('v').switch();
+    let name = short('n').argument::<String>("NAME").optional();
+    let prog = positional("PROG").help("Program to run").stop_parsing();
+    let run = construct!(name, prog).to_options().command("run");
+    let parser = construct!(verbose, run).to_options();
+
+    let r = parser
+        .run_inner(&["run", "--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "Usage: run [-n=NAME] PROG...\n\nAvailable positional items:\n    PROG        Program to run\n\nAvailable options:\n    -n=NAME\n    -h, --help  Prints help information\n"
+    );
+
+    let r = parser
+        .run_inner(&["-v", "run", "-n", "x", "ls", "-v", "-n", "--help", "-abc=d"])
+        .unwrap();
+    assert_eq!(
+        r,
+        (
+            true,
+            (
+                Some("x".to_owned()),
+                ["ls", "-v", "-n", "--help", "-abc=d"]
+                    .iter()
+                    .map(std::ffi::OsString::from)
+                    .collect()
+            )
+        )
+    );
+
+    let r = parser.run_inner(&["run", "--", "-ls", "--", "x"]).unwrap();
+    assert!(!r.0);
+    assert_eq!(r.1 .1, ["-ls", "--", "x"]);
+
+    let r = parser
+        .run_inner(&["run", "-v"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "expected `PROG`, pass `--help` for usage information");
+}
+
+#[test]
+fn stop_parsing_hides_tail_from_outer_parsers() {
+    let quiet = short('q').switch();
+    let verbose = short('v').switch();
+    let prog = positional("PROG").stop_parsing();
+    let run = construct!(verbose, prog).to_options().command("run");
+    let parser = construct!(quiet, run).to_options();
+
+    let r = parser.run_inner(&["run", "prog", "--its", "-q"]).unwrap();
+    assert_eq!(
+        r,
+        (
+            false,
+            (false, vec!["prog".into(), "--its".into(), "-q".into()])
+        )
+    );
+
+    let r = parser
+        .run_inner(&["-q", "run", "-v", "prog", "-v"])
+        .unwrap();
+    assert_eq!(r, (true, (true, vec!["prog".into(), "-v".into()])));
+
+    let quiet = short('q').switch();
+    let prog = positional("PROG").stop_parsing();
+    let parser = construct!(quiet, prog).to_options();
+
+    let r = parser.run_inner(&["prog", "-q"]).unwrap();
+    assert_eq!(r, (false, vec!["prog".into(), "-q".into()]));
+
+    let name = positional::<String>("NAME").optional();
+    let quiet = short('q').switch();
+    let prog = positional("PROG").stop_parsing();
+    let parser = construct!(quiet, name, prog).to_options();
+
+    let r = parser.run_inner(&["x", "prog", "-q"]).unwrap();
+    assert_eq!(
+        r,
+        (
+            false,
+            Some("x".to_owned()),
+            vec!["prog".into(), "-q".into()]
+        )
+    );
+}
+
+#[test]
+fn positional_choice() {
+    let parser = positional::<String>("FORMAT")
+        .help("Output format")
+        .choice(["json", "yaml", "toml"])
+        .to_options();
+
+    let r = parser.run_inner(&["toml"]).unwrap();
+    assert_eq!(r, "toml");
+
+    let r = parser.run_inner(&["jsno"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`jsno` is not a valid value, did you mean `json`?");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: FORMAT
+
+Available positional items:
+    FORMAT      Output format
+                [possible values: json, yaml, toml]
+
+Available options:
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn choice_built_at_runtime() {
+    // values discovered at runtime, for example names of installed toolchains
+    let installed = vec!["stable".to_owned(), "nightly".to_owned()];
+    let toolchain = long("toolchain").choice(installed.clone());
+    let file = positional::<String>("FILE").choice(installed.iter().map(|t| format!("{}.toml", t)));
+    let parser = construct!(toolchain, file).to_options();
+
+    let r = parser
+        .run_inner(&["--toolchain", "nightly", "stable.toml"])
+        .unwrap();
+    assert_eq!(r, ("nightly".to_owned(), "stable.toml".to_owned()));
+
+    let r = parser
+        .run_inner(&["--toolchain", "nightyl", "stable.toml"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`nightyl` is not a valid value, did you mean `nightly`?");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    assert!(
+        r.contains("[possible values: stable.toml, nightly.toml]"),
+        "{}",
+        r
+    );
+}
+
+#[test]
+fn positional_choice_error_kind() {
+    let verbose = short('v').switch();
+    let format = positional::<String>("FORMAT").choice(["json", "yaml"]);
+    let parser = construct!(verbose, format).to_options();
+
+    let err = parser.run_inner(&["-v", "jsno"]).unwrap_err();
+    match err.error_kind() {
+        Some(ErrorKind::InvalidChoice {
+            index,
+            value,
+            suggestion,
+            ..
+        }) => {
+            assert_eq!(*index, Some(1));
+            assert_eq!(value, "jsno");
+            assert_eq!(suggestion.as_deref(), Some("json"));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
@@ -0,0 +1,181 @@
+#![cfg(feature = "prompt")]
+#![allow(clippy::ptr_arg)]
+
+use bpaf::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// Prompter that replays answers and records requests
+fn replay(answers: &[&'static str]) -> (impl Prompter, Rc<RefCell<Vec<String>>>) {
+    let answers = RefCell::new(answers.to_vec());
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let log2 = log.clone();
+    let prompter = move |req: &PromptRequest| {
+        log2.borrow_mut().push(format!(
+            "{} secret:{} choices:{:?} error:{:?}",
+            req.message, req.secret, req.choices, req.error
+        ));
+        let mut answers = answers.borrow_mut();
+        if answers.is_empty() {
+            None
+        } else {
+            Some(answers.remove(0).to_owned())
+        }
+    };
+    (prompter, log)
+}
+
+#[test]
+fn missing_values_are_asked_for() {
+    let port = long("port")
+        .argument::<u16>("PORT")
+        .prompt("Port")
+        .guard(|p| *p > 10, "port must be above 10");
+    let password = long("password")
+        .argument::<String>("PASS")
+        .prompt_secret("Password");
+    let file = positional::<String>("FILE").prompt("File");
+    let parser = construct!(port, password, file);
+
+    let (prompter, log) = replay(&["x", "8", "80", "hunter2", "a.txt"]);
+    let r = parser
+        .to_options()
+        .prompter(prompter)
+        .run_inner(&[])
+        .unwrap();
+    assert_eq!(r, (80, "hunter2".to_owned(), "a.txt".to_owned()));
+    let expected = [
+        "Port secret:false choices:[] error:None",
+        "Port secret:false choices:[] error:Some(\"couldn't parse: invalid digit found in string\")",
+        "Port secret:false choices:[] error:Some(\"check failed: port must be above 10\")",
+        "Password secret:true choices:[] error:None",
+        "File secret:false choices:[] error:None",
+    ];
+    assert_eq!(*log.borrow(), expected);
+}
+
+#[test]
+fn prompts_are_skipped() {
+    fn parser() -> impl Parser<(u16, Option<String>)> {
+        let port = long("port").argument::<u16>("PORT").prompt("Port");
+        let name = long("name")
+            .argument::<String>("NAME")
+            .prompt("Name")
+            .optional();
+        construct!(port, name)
+    }
+
+    // value is present, nothing to ask
+    let (prompter, log) = replay(&[]);
+    let r = parser()
+        .to_options()
+        .prompter(prompter)
+        .run_inner(&["--port", "80"])
+        .unwrap();
+    assert_eq!(r, (80, None));
+    assert!(log.borrow().is_empty(), "{:?}", log.borrow());
+
+    // --help takes priority
+    let (prompter, log) = replay(&[]);
+    let parser = parser().to_options().prompter(prompter);
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    assert!(r.starts_with("Usage: --port=PORT"));
+    assert!(log.borrow().is_empty(), "{:?}", log.borrow());
+
+    // no answer - usual error
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `--port=PORT`, pass `--help` for usage information"
+    );
+    assert_eq!(log.borrow().len(), 1);
+}
+
+#[test]
+fn alternatives_ask_only_when_all_fail() {
+    fn parser() -> impl Parser<Option<String>> {
+        let file = long("file")
+            .argument::<String>("FILE")
+            .prompt("File")
+            .map(Some);
+        let stdin = long("stdin").req_flag(None);
+        construct!([file, stdin])
+    }
+
+    let (prompter, log) = replay(&[]);
+    let r = parser()
+        .to_options()
+        .prompter(prompter)
+        .run_inner(&["--stdin"]);
+    assert_eq!(r.unwrap(), None);
+    assert!(log.borrow().is_empty(), "{:?}", log.borrow());
+
+    let (prompter, log) = replay(&["a.txt"]);
+    let r = parser().to_options().prompter(prompter).run_inner(&[]);
+    assert_eq!(r.unwrap(), Some("a.txt".to_owned()));
+    assert_eq!(log.borrow().len(), 1);
+}
+
+#[test]
+fn value_source_is_prompt() {
+    let (prompter, _log) = replay(&["eu"]);
+    let parser = long("region")
+        .argument::<String>("REGION")
+        .prompt("Region")
+        .with_source()
+        .to_options()
+        .prompter(prompter);
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, ("eu".to_owned(), ValueSource::Prompt));
+}
+
+#[cfg(feature = "autocomplete")]
+#[test]
+fn completion_candidates_are_offered() {
+    fn regions(input: &String) -> Vec<(&'static str, Option<&'static str>)> {
+        ["eu-west", "us-east"]
+            .iter()
+            .filter(|r| r.starts_with(input.as_str()))
+            .map(|r| (*r, None))
+            .collect()
+    }
+    let (prompter, log) = replay(&["us-east"]);
+    let parser = long("region")
+        .argument::<String>("REGION")
+        .prompt("Region")
+        .complete(regions)
+        .to_options()
+        .prompter(prompter);
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, "us-east");
+    let expected = ["Region secret:false choices:[\"eu-west\", \"us-east\"] error:None"];
+    assert_eq!(*log.borrow(), expected);
+}
+
+#[cfg(feature = "autocomplete")]
+#[test]
+fn completion_candidates_only_collected_when_asking() {
+    let calls = Rc::new(std::cell::Cell::new(0));
+    let counter = calls.clone();
+    let (prompter, log) = replay(&["eu-west"]);
+    let parser = long("region")
+        .argument::<String>("REGION")
+        .prompt("Region")
+        .map(move |r| {
+            counter.set(counter.get() + 1);
+            r
+        })
+        .complete(|_| vec![("eu-west", None)])
+        .to_options()
+        .prompter(prompter);
+
+    let r = parser.run_inner(&["--region", "us-east"]).unwrap();
+    assert_eq!(r, "us-east");
+    assert_eq!(calls.get(), 1);
+    assert!(log.borrow().is_empty());
+
+    calls.set(0);
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, "eu-west");
+    // once to get candidates from an empty answer and once with the real answer
+    assert_eq!(calls.get(), 2);
+}
@@ -0,0 +1,292 @@
+#![cfg(feature = "docgen")]
+
+use bpaf::*;
+
+#[test]
+fn nested_commands() {
+    let verbose = short('v').long("verbose").help("Be verbose").switch();
+    let secret = long("secret").switch().hide();
+    let name = long("name")
+        .env("APP_NAME")
+        .help("Who to greet")
+        .argument::<String>("NAME")
+        .fallback("world".to_owned())
+        .display_fallback();
+    let files = positional::<String>("FILE").many();
+    let add = construct!(name, files)
+        .to_options()
+        .descr("Add \"things\"")
+        .command("add")
+        .short('a')
+        .help("Add things");
+    let parser = construct!(verbose, secret, add).to_options().version("1.0");
+
+    let r = parser.render_json_schema("app");
+    let expected = r#"{
+  "schema_version": 1,
+  "name": "app",
+  "version": "1.0",
+  "description": null,
+  "header": null,
+  "footer": null,
+  "items": [
+    {
+      "kind": "flag",
+      "short": "v",
+      "long": "verbose",
+      "metavar": null,
+      "env": null,
+      "config": null,
+      "help": "Be verbose",
+      "default": null,
+      "required": false,
+      "repeated": false,
+      "hidden": false,
+      "choices": [],
+      "choice_keys": false,
+      "negatable": false,
+      "optional_value": false,
+      "passthrough": false
+    },
+    {
+      "kind": "flag",
+      "short": null,
+      "long": "secret",
+      "metavar": null,
+      "env": null,
+      "config": null,
+      "help": null,
+      "default": null,
+      "required": false,
+      "repeated": false,
+      "hidden": true,
+      "choices": [],
+      "choice_keys": false,
+      "negatable": false,
+      "optional_value": false,
+      "passthrough": false
+    },
+    {
+      "kind": "flag",
+      "short": "h",
+      "long": "help",
+      "metavar": null,
+      "env": null,
+      "config": null,
+      "help": "Prints help information",
+      "default": null,
+      "required": false,
+      "repeated": false,
+      "hidden": false,
+      "choices": [],
+      "choice_keys": false,
+      "negatable": false,
+      "optional_value": false,
+      "passthrough": false
+    },
+    {
+      "kind": "flag",
+      "short": "V",
+      "long": "version",
+      "metavar": null,
+      "env": null,
+      "config": null,
+      "help": "Prints version information",
+      "default": null,
+      "required": false,
+      "repeated": false,
+      "hidden": false,
+      "choices": [],
+      "choice_keys": false,
+      "negatable": false,
+      "optional_value": false,
+      "passthrough": false
+    }
+  ],
+  "commands": [
+    {
+      "name": "add",
+      "short": "a",
+      "help": "Add things",
+      "hidden": false,
+      "version": null,
+      "description": "Add \"things\"",
+      "header": null,
+      "footer": null,
+      "items": [
+        {
+          "kind": "argument",
+          "short": null,
+          "long": "name",
+          "metavar": "NAME",
+          "env": "APP_NAME",
+          "config": null,
+          "help": "Who to greet",
+          "default": "world",
+          "required": false,
+          "repeated": false,
+          "hidden": false,
+          "choices": [],
+          "choice_keys": false,
+          "negatable": false,
+          "optional_value": false,
+          "passthrough": false
+        },
+        {
+          "kind": "positional",
+          "short": null,
+          "long": null,
+          "metavar": "FILE",
+          "env": null,
+          "config": null,
+          "help": null,
+          "default": null,
+          "required": false,
+          "repeated": true,
+          "hidden": false,
+          "choices": [],
+          "choice_keys": false,
+          "negatable": false,
+          "optional_value": false,
+          "passthrough": false
+        },
+        {
+          "kind": "flag",
+          "short": "h",
+          "long": "help",
+          "metavar": null,
+          "env": null,
+          "config": null,
+          "help": "Prints help information",
+          "default": null,
+          "required": false,
+          "repeated": false,
+          "hidden": false,
+          "choices": [],
+          "choice_keys": false,
+          "negatable": false,
+          "optional_value": false,
+          "passthrough": false
+        }
+      ],
+      "commands": []
+    }
+  ]
+}
+"#;
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn compatibility() {
+    fn old() -> OptionParser<()> {
+        let verbose = short('v').long("verbose").switch();
+        let quiet = short('q').long("quiet").switch();
+        let jobs = short('j').long("jobs").argument::<u32>("N").optional();
+        let file = positional::<String>("FILE").optional();
+        let build = pure(()).to_options().command("build");
+        let clean = pure(()).to_options().command("clean");
+        let cmd = construct!([build, clean]);
+        construct!(verbose, quiet, jobs, file, cmd)
+            .map(|_| ())
+            .to_options()
+    }
+
+    fn new() -> OptionParser<()> {
+        let verbose = short('v').long("loud").switch();
+        let jobs = short('J')
+            .long("jobs")
+            .env("JOBS")
+            .argument::<u32>("N")
+            .optional();
+        let color = long("color").switch();
+        let file = positional::<String>("FILE");
+        let release = long("release").switch();
+        let build = release.to_options().command("build");
+        construct!(verbose, jobs, color, file, build)
+            .map(|_| ())
+            .to_options()
+    }
+
+    let changes = new()
+        .check_compatibility(&old())
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let expected = [
+        "Breaking: `--verbose` was renamed to `--loud`",
+        "Breaking: `--quiet` was removed",
+        "Breaking: `--jobs` short name changed from -j to -J",
+        "Warning: `--jobs` environment variable changed from nothing to JOBS",
+        "Info: optional `--color` was added",
+        "Breaking: `FILE` became required",
+        "Info: build optional `--release` was added",
+        "Breaking: command `clean` was removed",
+    ];
+    assert_eq!(changes, expected);
+
+    assert!(old().check_compatibility(&old()).is_empty());
+
+    let worst = new()
+        .check_compatibility(&old())
+        .iter()
+        .map(CliChange::severity)
+        .max();
+    assert_eq!(worst, Some(Severity::Breaking));
+}
+
+#[test]
+fn compatibility_value_forms() {
+    fn old() -> OptionParser<()> {
+        let color = long("color").argument::<String>("WHEN").optional();
+        let level = long("level").choice(["low", "high"]).optional();
+        let mode = long("mode").choice(["fast", "slow"]).optional();
+        let cache = long("cache").switch();
+        let prog = positional::<String>("PROG").optional();
+        construct!(color, level, mode, cache, prog)
+            .map(|_| ())
+            .to_options()
+    }
+
+    fn new() -> OptionParser<()> {
+        let color = long("color")
+            .optional_value("WHEN", "auto".to_owned())
+            .optional();
+        let level = long("level").choice(["low", "mid", "high"]).optional();
+        let mode = long("mode").choice(["fast"]).optional();
+        let cache = long("cache").negatable_switch();
+        let prog = positional("PROG").stop_parsing().optional();
+        construct!(color, level, mode, cache, prog)
+            .map(|_| ())
+            .to_options()
+    }
+
+    let changes = new()
+        .check_compatibility(&old())
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let expected = [
+        "Breaking: `--color` value is optional now",
+        "Info: `--level` accepted values changed from low, high to low, mid, high",
+        "Breaking: `--mode` accepted values changed from fast, slow to fast",
+        "Info: `--cache` is negatable now",
+        "Warning: `PROG` is a passthrough now",
+    ];
+    assert_eq!(changes, expected);
+
+    let changes = old()
+        .check_compatibility(&new())
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let expected = [
+        "Breaking: `--color` value is not optional now",
+        "Breaking: `--level` accepted values changed from low, mid, high to low, high",
+        "Info: `--mode` accepted values changed from fast to fast, slow",
+        "Breaking: `--cache` is not negatable now",
+        "Breaking: `PROG` no longer accepts several values",
+        "Breaking: `PROG` is not a passthrough now",
+    ];
+    assert_eq!(changes, expected);
+}
@@ -817,6 +817,82 @@ Available options:
     assert_eq!(res, "top s3cr3t");
 }
 
+#[test]
+fn config_source_precedence() {
+    let name = "BPAF_CONFIG_PORT";
+    let port = long("port")
+        .env(name)
+        .config("net.port")
+        .help("port to listen on")
+        .argument::<u16>("PORT")
+        .fallback(1);
+    let verbose = long("verbose").config("log.verbose").switch();
+    let parser = construct!(port, verbose)
+        .to_options()
+        .config_source(|key: &str| match key {
+            "net.port" => Some("8080".to_owned()),
+            "log.verbose" => Some("false".to_owned()),
+            _ => None,
+        });
+
+    let help = parser.run_inner(&["-h"]).unwrap_err().unwrap_stdout();
+    let expected_help = "\
+Usage: [--port=PORT] [--verbose]
+
+Available options:
+        --port=PORT  port to listen on
+                     [env:BPAF_CONFIG_PORT: N/A]
+                     [config:net.port]
+        --verbose    [config:log.verbose]
+    -h, --help       Prints help information
+";
+    assert_eq!(expected_help, help);
+
+    // config beats fallback
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (8080, false));
+
+    // env beats config
+    std::env::set_var(name, "80");
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (80, false));
+
+    // command line beats env
+    let r = parser.run_inner(&["--port", "443", "--verbose"]).unwrap();
+    assert_eq!(r, (443, true));
+    std::env::remove_var(name);
+
+    // without config source items fall back as usual
+    let parser = long("port")
+        .config("net.port")
+        .argument::<u16>("PORT")
+        .fallback(1)
+        .to_options();
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, 1);
+}
+
+#[test]
+fn config_source_inherited_by_command() {
+    let mut config = std::collections::HashMap::new();
+    config.insert("run.jobs".to_owned(), "4".to_owned());
+    config.insert("run.dry".to_owned(), "yes".to_owned());
+
+    let jobs = long("jobs").config("run.jobs").argument::<usize>("N");
+    let dry = long("dry").config("run.dry").switch();
+    let parser = construct!(jobs, dry)
+        .to_options()
+        .command("run")
+        .to_options()
+        .config_source(config);
+
+    let r = parser.run_inner(&["run"]).unwrap();
+    assert_eq!(r, (4, true));
+
+    let r = parser.run_inner(&["run", "--jobs", "2"]).unwrap();
+    assert_eq!(r, (2, true));
+}
+
 #[test]
 fn default_plays_nicely_with_command() {
     #[derive(Debug, Clone)]
@@ -1827,3 +1903,336 @@ fn flag_like_commands() {
         "Usage: --add -a\n\nAvailable options:\n    -a\n    -h, --help  Prints help information\n";
     assert_eq!(r, expected);
 }
+
+#[test]
+fn value_sources() {
+    let name = long("name")
+        .env("BPAF_SOURCE_NAME")
+        .argument::<String>("NAME");
+    let name = name.fallback("anon".to_owned()).with_source();
+    let port = long("port").config("net.port").argument::<u16>("PORT");
+    let port = port.with_source();
+    let verbose = short('v').long("verbose").switch().with_source();
+    let file = positional::<String>("FILE").with_source();
+    let parser = construct!(name, port, verbose, file)
+        .to_options()
+        .config_source(|key: &str| (key == "net.port").then(|| "80".to_owned()));
+
+    let (name, port, verbose, file) = parser
+        .run_inner(&["--port", "8080", "--name", "bob", "-v", "x"])
+        .unwrap();
+    assert_eq!(
+        name.1,
+        ValueSource::Argv {
+            index: 2,
+            spelling: "--name".to_owned()
+        }
+    );
+    assert_eq!(
+        port,
+        (
+            8080,
+            ValueSource::Argv {
+                index: 0,
+                spelling: "--port".to_owned()
+            }
+        )
+    );
+    assert_eq!(
+        verbose.1,
+        ValueSource::Argv {
+            index: 4,
+            spelling: "-v".to_owned()
+        }
+    );
+    assert_eq!(
+        file.1,
+        ValueSource::Argv {
+            index: 5,
+            spelling: "x".to_owned()
+        }
+    );
+
+    let (name, port, verbose, _) = parser.run_inner(&["--verbose", "x"]).unwrap();
+    assert_eq!(name, ("anon".to_owned(), ValueSource::Fallback));
+    assert_eq!(port, (80, ValueSource::Config("net.port".into())));
+    assert_eq!(
+        verbose.1,
+        ValueSource::Argv {
+            index: 0,
+            spelling: "--verbose".to_owned()
+        }
+    );
+
+    std::env::set_var("BPAF_SOURCE_NAME", "alice");
+    let (name, _, verbose, _) = parser.run_inner(&["x"]).unwrap();
+    std::env::remove_var("BPAF_SOURCE_NAME");
+    assert_eq!(
+        name,
+        (
+            "alice".to_owned(),
+            ValueSource::Env("BPAF_SOURCE_NAME".into())
+        )
+    );
+    assert_eq!(verbose, (false, ValueSource::Default));
+}
+
+#[test]
+fn names_built_at_runtime() {
+    // plugin manifests loaded at runtime: command name, option name and metavar
+    let manifest = vec![
+        ("build".to_owned(), "target".to_owned(), "TARGET".to_owned()),
+        ("lint".to_owned(), "level".to_owned(), "LEVEL".to_owned()),
+    ];
+    let commands = manifest.into_iter().map(|(cmd, opt, meta)| {
+        let value = long(opt.clone())
+            .env(format!("APP_{}", opt.to_uppercase()))
+            .argument::<String>(meta.clone());
+        let file = positional::<String>(format!("{}_FILE", meta));
+        construct!(value, file).to_options().command(cmd).boxed()
+    });
+    let parser = choice(commands).to_options();
+
+    let r = parser
+        .run_inner(&["lint", "--level", "high", "a.rs"])
+        .unwrap();
+    assert_eq!(r, ("high".to_owned(), "a.rs".to_owned()));
+
+    let r = parser
+        .run_inner(&["build", "a.rs"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `--target=TARGET`, pass `--help` for usage information"
+    );
+
+    let r = parser
+        .run_inner(&["lint", "--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    assert!(r.contains("--level=LEVEL"), "{}", r);
+    assert!(r.contains("[env:APP_LEVEL"), "{}", r);
+
+    let parser = fail::<()>(format!("plugin {} is disabled", "lint")).to_options();
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "plugin lint is disabled");
+
+    let max = 3;
+    let parser = long("job")
+        .argument::<usize>("JOB")
+        .guard(move |j| *j <= max, format!("at most {} jobs", max))
+        .some(format!("{} needs at least one job", "lint"))
+        .to_options();
+    let r = parser
+        .run_inner(&["--job", "4"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`4`: at most 3 jobs");
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "lint needs at least one job");
+
+    let parser = literal(String::from("lint")).to_options();
+    assert!(parser.run_inner(&["lint"]).is_ok());
+}
+
+#[test]
+fn negatable_switch() {
+    let color = short('c')
+        .long("color")
+        .env("BPAF_TEST_NEGATABLE_COLOR")
+        .help("Use colors in the output")
+        .negatable_switch();
+    let verbose = short('v').switch();
+    let parser = construct!(color, verbose).to_options();
+
+    let r = parser.run_inner(&["--color"]).unwrap();
+    assert_eq!(r, (true, false));
+
+    let r = parser.run_inner(&["--no-color", "-v"]).unwrap();
+    assert_eq!(r, (false, true));
+
+    let r = parser
+        .run_inner(&["--no-color", "-cv", "--color", "--no-color"])
+        .unwrap();
+    assert_eq!(r, (false, true));
+
+    let r = parser.run_inner(&["--no-color", "-c"]).unwrap();
+    assert_eq!(r, (true, false));
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (false, false));
+
+    std::env::set_var("BPAF_TEST_NEGATABLE_COLOR", "1");
+    assert_eq!(parser.run_inner(&[]).unwrap(), (true, false));
+    assert_eq!(parser.run_inner(&["--no-color"]).unwrap(), (false, false));
+    std::env::remove_var("BPAF_TEST_NEGATABLE_COLOR");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-c] [-v]
+
+Available options:
+    -c, --[no-]color  Use colors in the output
+                      [env:BPAF_TEST_NEGATABLE_COLOR: not set]
+    -v
+    -h, --help        Prints help information
+";
+    assert_eq!(r, expected);
+
+    let parser = long("color").negatable_switch().to_options();
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [--[no-]color]
+
+Available options:
+        --[no-]color
+    -h, --help        Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn optional_value_argument() {
+    let color = short('c')
+        .long("color")
+        .help("When to use colors")
+        .optional_value::<String>("WHEN", "always".to_owned())
+        .optional();
+    let name = positional::<String>("NAME").optional();
+    let parser = construct!(color, name).to_options();
+
+    let r = parser.run_inner(&["--color"]).unwrap();
+    assert_eq!(r, (Some("always".to_owned()), None));
+
+    let r = parser.run_inner(&["--color=never"]).unwrap();
+    assert_eq!(r, (Some("never".to_owned()), None));
+
+    let r = parser.run_inner(&["-c=never"]).unwrap();
+    assert_eq!(r, (Some("never".to_owned()), None));
+
+    let r = parser.run_inner(&["-cnever"]).unwrap();
+    assert_eq!(r, (Some("never".to_owned()), None));
+
+    let r = parser.run_inner(&["-c"]).unwrap();
+    assert_eq!(r, (Some("always".to_owned()), None));
+
+    // separate word is not a value
+    let r = parser.run_inner(&["--color", "never"]).unwrap();
+    assert_eq!(r, (Some("always".to_owned()), Some("never".to_owned())));
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (None, None));
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-c[=WHEN]] [NAME]
+
+Available options:
+    -c, --color[=WHEN]  When to use colors
+    -h, --help          Prints help information
+";
+    assert_eq!(r, expected);
+
+    let level = long("level").optional_value::<u32>("N", 1).to_options();
+    assert_eq!(level.run_inner(&["--level"]).unwrap(), 1);
+    assert_eq!(level.run_inner(&["--level=3"]).unwrap(), 3);
+    let r = level.run_inner(&["--level=x"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "couldn't parse `x`: invalid digit found in string");
+    let r = level.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `--level[=N]`, pass `--help` for usage information"
+    );
+}
+
+#[test]
+fn key_value_map() {
+    use std::collections::HashMap;
+    let labels = long("label")
+        .help("Attach a label")
+        .argument("NAME")
+        .key_value::<String, String>("NAME=VAL")
+        .collect::<HashMap<_, _>>();
+    let parser = labels.to_options();
+
+    let r = parser
+        .run_inner(&["--label", "a=b", "--label=c=d=e", "--label", "f="])
+        .unwrap();
+    let mut expected = HashMap::new();
+    expected.insert("a".to_owned(), "b".to_owned());
+    expected.insert("c".to_owned(), "d=e".to_owned());
+    expected.insert("f".to_owned(), String::new());
+    assert_eq!(r, expected);
+
+    assert_eq!(parser.run_inner(&[]).unwrap(), HashMap::new());
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: --label=NAME=VAL...
+
+Available options:
+        --label=NAME=VAL  Attach a label
+    -h, --help            Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn color_arg() {
+    let verbose = short('v').help("Verbose output").switch();
+    let file = positional::<String>("FILE").optional();
+    let parser = construct!(verbose, file)
+        .to_options()
+        .color_arg(short('c').long("color"));
+
+    let r = parser.run_inner(&["--color", "-v"]).unwrap();
+    assert_eq!(r, (true, None));
+
+    let r = parser.run_inner(&["-v", "--color=never", "x"]).unwrap();
+    assert_eq!(r, (true, Some("x".to_owned())));
+
+    let r = parser.run_inner(&["-cnever", "x"]).unwrap();
+    assert_eq!(r, (false, Some("x".to_owned())));
+
+    let r = parser
+        .run_inner(&["--color=rainbow"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse `rainbow`: expected auto, always or never"
+    );
+
+    let r = parser
+        .run_inner(&["--color=always", "--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    let expected = "\
+Usage: [-v] [FILE]
+
+Available options:
+    -v                  Verbose output
+    -c, --color[=WHEN]  When to use colors: auto, always or never
+    -h, --help          Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn color_arg_before_subcommand() {
+    let color = long("color").argument::<String>("COLOR").optional();
+    let paint = color.to_options().command("paint");
+    let parser = construct!(paint).to_options().color_arg(long("color"));
+
+    let r = parser
+        .run_inner(&["--color=never", "paint", "--color", "red"])
+        .unwrap();
+    assert_eq!(r, Some("red".to_owned()));
+
+    let r = parser.run_inner(&["paint", "--color=red"]).unwrap();
+    assert_eq!(r, Some("red".to_owned()));
+
+    let r = parser.run_inner(&["paint"]).unwrap();
+    assert_eq!(r, None);
+}